@@ -0,0 +1,26 @@
+#[macro_use]
+extern crate crusp;
+
+fn main() {
+    let result = cp_model!(
+        model {
+            let a = var bool;
+            let b = var bool;
+            let c = var bool;
+
+            constraint a < b;
+            constraint c == a;
+        }
+        branchers {
+            branch([a,b,c], variables_order, domain_order);
+        }
+        solve;
+        output (a,b,c);
+    );
+    match result {
+        Some((a, b, c)) => {
+            println!("a = {}, b = {}, c = {}", value!(a), value!(b), value!(c))
+        }
+        None => println!("No solution!"),
+    }
+}
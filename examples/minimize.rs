@@ -0,0 +1,26 @@
+#[macro_use]
+extern crate crusp;
+
+fn main() {
+    let result = cp_model!(
+        model {
+            let a = var int(0 .. 10);
+            let b = var int(0 .. 10);
+            let cost = var int(0 .. 30);
+
+            constraint a < b;
+            constraint cost = (2*a + b);
+        }
+        branchers {
+            branch([a,b], variables_order, domain_max);
+        }
+        minimize cost;
+        output (a,b,cost);
+    );
+    match result {
+        Some((a, b, cost)) => {
+            println!("{} = 2 * {} + {}", value!(cost), value!(a), value!(b))
+        }
+        None => println!("No solution!"),
+    }
+}
@@ -0,0 +1,49 @@
+extern crate crusp;
+
+use crusp::branchers::values_selector::MaxValueSelector;
+use crusp::branchers::variables_selector::SequentialVariableSelector;
+use crusp::constraints::arithmetic::LessThan;
+use crusp::model::Model;
+use crusp::search::Solver;
+use crusp::variables::int_var::IntVarValues;
+use crusp::variables::Variable;
+
+fn main() {
+    // The domains would typically be read from an input file.
+    let domains = [(3, 9), (2, 5), (1, 8), (0, 9)];
+
+    let mut model = Model::new();
+    let vars = domains
+        .iter()
+        .map(|&(min, max)| model.new_int_var(min, max).unwrap())
+        .collect::<Vec<_>>();
+    for pair in vars.windows(2) {
+        model.post(LessThan::new(pair[0], pair[1]));
+    }
+    model.branch(
+        SequentialVariableSelector::new(vars.clone().into_iter()).unwrap(),
+        MaxValueSelector::new(),
+    );
+
+    let space = match model.build() {
+        Ok(space) => space,
+        Err(_) => {
+            println!("No solution!");
+            return;
+        }
+    };
+    let mut solver = Solver::new(space);
+    if solver.solve() {
+        let solution = solver.solution().unwrap();
+        let values = vars
+            .iter()
+            .map(|view| {
+                let var: &IntVarValues = solution.get_variable(view);
+                format!("{}", var.value().unwrap())
+            })
+            .collect::<Vec<_>>();
+        println!("{}", values.join(" < "));
+    } else {
+        println!("No solution!");
+    }
+}
@@ -35,7 +35,7 @@ fn main() {
         Some((queens,)) => {
             let queens = queens
                 .iter()
-                .map(|ref var| var.value())
+                .map(|var| var.value())
                 .map(Option::unwrap)
                 .map(|val| format!("{}", val))
                 .collect::<Vec<_>>();
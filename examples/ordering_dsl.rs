@@ -0,0 +1,34 @@
+#[macro_use]
+extern crate crusp;
+
+fn main() {
+    let result = cp_model!(
+        model {
+            let a = var int(0 .. 10);
+            let b = var int(0 .. 10);
+            let c = var int(0 .. 10);
+            let d = var int(0 .. 10);
+            let e = var int(0 .. 10);
+
+            constraint increasing([a,b,c]);
+            constraint non_increasing([c,d]);
+            constraint decreasing([d,e]);
+        }
+        branchers {
+            branch([a,b,c,d,e], variables_order, domain_max);
+        }
+        solve;
+        output (a,b,c,d,e);
+    );
+    match result {
+        Some((a, b, c, d, e)) => println!(
+            "{} < {} < {} >= {} > {}",
+            value!(a),
+            value!(b),
+            value!(c),
+            value!(d),
+            value!(e)
+        ),
+        None => println!("No solution!"),
+    }
+}
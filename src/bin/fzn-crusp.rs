@@ -0,0 +1,159 @@
+extern crate crusp;
+
+use crusp::flatzinc::{self, FlatZincError, FlatZincInstance, FlatZincSpace};
+use crusp::search::branch_and_bound::BranchAndBoundSolver;
+use crusp::search::limits::{SearchLimits, SearchStatus};
+use crusp::search::Solver;
+use std::env;
+use std::fs::File;
+use std::io::Read;
+use std::process;
+use std::time::Duration;
+
+const USAGE: &str =
+    "Usage: fzn-crusp [-a] [-n <solutions>] [-t <milliseconds>] <model.fzn>";
+
+struct Options {
+    all_solutions: bool,
+    solutions: Option<usize>,
+    timeout: Option<Duration>,
+    path: String,
+}
+
+fn parse_options() -> Result<Options, String> {
+    let mut options = Options {
+        all_solutions: false,
+        solutions: None,
+        timeout: None,
+        path: String::new(),
+    };
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-a" => options.all_solutions = true,
+            "-n" => {
+                let value = args.next().ok_or("-n requires a value")?;
+                let value = value.parse().map_err(|_| "-n requires an integer")?;
+                options.solutions = Some(value);
+            }
+            "-t" => {
+                let value = args.next().ok_or("-t requires a value")?;
+                let value = value.parse().map_err(|_| "-t requires an integer")?;
+                options.timeout = Some(Duration::from_millis(value));
+            }
+            "-h" | "--help" => return Err(USAGE.to_string()),
+            // Other standard flags (e.g. -p, -s, -f) are accepted but ignored.
+            arg if arg.starts_with('-') => {}
+            path => options.path = path.to_string(),
+        }
+    }
+    if options.path.is_empty() {
+        Err(USAGE.to_string())
+    } else {
+        Ok(options)
+    }
+}
+
+fn read_model(path: &str) -> Result<String, String> {
+    let mut content = String::new();
+    File::open(path)
+        .and_then(|mut file| file.read_to_string(&mut content))
+        .map_err(|error| format!("{}: {}", path, error))?;
+    Ok(content)
+}
+
+fn print_status(status: SearchStatus, solutions: usize) {
+    match status {
+        SearchStatus::Infeasible if solutions == 0 => println!("=====UNSATISFIABLE====="),
+        SearchStatus::Interrupted if solutions == 0 => println!("=====UNKNOWN====="),
+        SearchStatus::Interrupted => {}
+        _ => println!("=========="),
+    }
+}
+
+fn print_solution(instance: &FlatZincInstance, solution: &FlatZincSpace) {
+    match instance.format_solution(solution) {
+        Ok(solution) => println!("{}", solution),
+        Err(error) => {
+            eprintln!("{}", error);
+            process::exit(1);
+        }
+    }
+}
+
+fn satisfy(instance: &FlatZincInstance, options: &Options, limits: SearchLimits) {
+    let max_solutions = match (options.all_solutions, options.solutions) {
+        (_, Some(n)) => n,
+        (true, None) => usize::MAX,
+        (false, None) => 1,
+    };
+    let solver = Solver::with_limits(instance.space().clone(), limits);
+    let mut solutions = solver.solutions();
+    let mut found = 0;
+    while found < max_solutions {
+        match solutions.next() {
+            Some(solution) => {
+                found += 1;
+                print_solution(instance, &solution);
+            }
+            None => {
+                // The search tree is exhausted (or the search interrupted).
+                print_status(solutions.status(), found);
+                return;
+            }
+        }
+    }
+}
+
+fn optimize(instance: &FlatZincInstance, limits: SearchLimits) {
+    let (objective, direction) = instance.objective().unwrap();
+    let mut solver = BranchAndBoundSolver::with_limits(
+        instance.space().clone(),
+        objective,
+        direction,
+        limits,
+    );
+    let found = solver.solve();
+    if found {
+        let solution = solver.solution().unwrap();
+        print_solution(instance, &solution);
+    }
+    print_status(solver.status(), found as usize);
+}
+
+fn main() {
+    let options = match parse_options() {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}", message);
+            process::exit(1);
+        }
+    };
+    let content = match read_model(&options.path) {
+        Ok(content) => content,
+        Err(message) => {
+            eprintln!("{}", message);
+            process::exit(1);
+        }
+    };
+    let instance =
+        match flatzinc::parse(&content).and_then(|model| FlatZincInstance::new(&model)) {
+            Ok(instance) => instance,
+            Err(FlatZincError::Inconsistent(_)) => {
+                println!("=====UNSATISFIABLE=====");
+                return;
+            }
+            Err(error) => {
+                eprintln!("{}", error);
+                process::exit(1);
+            }
+        };
+    let limits = match options.timeout {
+        Some(timeout) => SearchLimits::new().with_timeout(timeout),
+        None => SearchLimits::new(),
+    };
+    match instance.objective() {
+        None => satisfy(&instance, &options, limits),
+        Some(_) => optimize(&instance, limits),
+    }
+}
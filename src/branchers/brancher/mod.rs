@@ -1,4 +1,4 @@
-use super::{Brancher, SpecificBrancher, ValuesSelector, VariableSelector};
+use super::{Brancher, Branches, SpecificBrancher, ValuesSelector, VariableSelector};
 use std::marker::PhantomData;
 use variables::handlers::{
     VariableContainerHandler, VariableContainerView, VariablesHandler,
@@ -7,7 +7,9 @@ use variables::handlers::{
 #[derive(Clone, Debug)]
 pub struct DefaultBrancher<Handler, View, VarSel, ValSel>
 where
-    Handler: VariablesHandler + VariableContainerHandler<View> + 'static,
+    Handler: VariablesHandler
+        + VariableContainerHandler<View::Container, View = View>
+        + 'static,
     View: VariableContainerView,
     VarSel: VariableSelector<Handler, View> + Clone + 'static,
     ValSel: ValuesSelector<Handler, View> + Clone + 'static,
@@ -22,7 +24,9 @@ where
 unsafe impl<Handler, View, VarSel, ValSel> Sync
     for DefaultBrancher<Handler, View, VarSel, ValSel>
 where
-    Handler: VariablesHandler + VariableContainerHandler<View> + 'static,
+    Handler: VariablesHandler
+        + VariableContainerHandler<View::Container, View = View>
+        + 'static,
     View: VariableContainerView,
     VarSel: VariableSelector<Handler, View> + Clone + 'static,
     ValSel: ValuesSelector<Handler, View> + Clone + 'static,
@@ -31,7 +35,9 @@ where
 
 impl<Handler, View, VarSel, ValSel> DefaultBrancher<Handler, View, VarSel, ValSel>
 where
-    Handler: VariablesHandler + VariableContainerHandler<View> + 'static,
+    Handler: VariablesHandler
+        + VariableContainerHandler<View::Container, View = View>
+        + 'static,
     View: VariableContainerView,
     VarSel: VariableSelector<Handler, View> + Clone + 'static,
     ValSel: ValuesSelector<Handler, View> + Clone + 'static,
@@ -39,20 +45,23 @@ where
     pub fn new(
         variables_selector: VarSel,
         values_selector: ValSel,
-    ) -> Option<DefaultBrancher<Handler, View, VarSel, ValSel>> {
-        Some(DefaultBrancher {
-            variables_selector: variables_selector,
-            values_selector: values_selector,
+    ) -> DefaultBrancher<Handler, View, VarSel, ValSel> {
+        DefaultBrancher {
+            variables_selector,
+            values_selector,
             phantom_handler: PhantomData,
             phantom_view: PhantomData,
-        })
+        }
     }
 }
 
 impl<Handler, View, VarSel, ValSel> Brancher<Handler>
     for DefaultBrancher<Handler, View, VarSel, ValSel>
 where
-    Handler: VariablesHandler + VariableContainerHandler<View> + Send + 'static,
+    Handler: VariablesHandler
+        + VariableContainerHandler<View::Container, View = View>
+        + Send
+        + 'static,
     View: VariableContainerView + Send + 'static,
     VarSel: VariableSelector<Handler, View> + Clone + Send + 'static,
     ValSel: ValuesSelector<Handler, View> + Clone + Send + 'static,
@@ -60,12 +69,12 @@ where
     fn branch(
         &mut self,
         variables: &Handler,
-    ) -> Result<Box<Iterator<Item = Box<Fn(&mut Handler) -> () + Send>>>, ()> {
+    ) -> Result<Branches<Handler>, ()> {
         self.specific_branch(variables)
     }
 
     fn mutated_clone(&self) -> Box<Brancher<Handler> + Send + Sync> {
-        let ref_self: &DefaultBrancher<Handler, View, VarSel, ValSel> = &self;
+        let ref_self: &DefaultBrancher<Handler, View, VarSel, ValSel> = self;
         let cloned: DefaultBrancher<Handler, View, VarSel, ValSel> =
             <DefaultBrancher<Handler, View, VarSel, ValSel> as Clone>::clone(ref_self);
 
@@ -76,7 +85,10 @@ where
 impl<Handler, View, VarSel, ValSel> SpecificBrancher<Handler, View>
     for DefaultBrancher<Handler, View, VarSel, ValSel>
 where
-    Handler: VariablesHandler + VariableContainerHandler<View> + Send + 'static,
+    Handler: VariablesHandler
+        + VariableContainerHandler<View::Container, View = View>
+        + Send
+        + 'static,
     View: VariableContainerView + Send + 'static,
     VarSel: VariableSelector<Handler, View> + Clone + Send + 'static,
     ValSel: ValuesSelector<Handler, View> + Clone + Send + 'static,
@@ -84,7 +96,7 @@ where
     fn specific_branch(
         &mut self,
         variables: &Handler,
-    ) -> Result<Box<Iterator<Item = Box<Fn(&mut Handler) -> () + Send>>>, ()> {
+    ) -> Result<Branches<Handler>, ()> {
         let variable = self.variables_selector.select(variables)?;
         self.values_selector.select(variables, variable)
     }
@@ -2,6 +2,9 @@ use variables::handlers::{
     VariableContainerHandler, VariableContainerView, VariablesHandler,
 };
 
+/// The branches of a node: each one modifies the variables of a copy of the node.
+pub type Branches<Handler> = Box<Iterator<Item = Box<Fn(&mut Handler) + Send>>>;
+
 pub mod brancher;
 pub mod values_selector;
 pub mod variables_selector;
@@ -10,32 +13,35 @@ pub mod variables_selector;
 pub trait VariableSelector<Handler, View>
 where
     View: VariableContainerView,
-    Handler: VariablesHandler + VariableContainerHandler<View>,
+    Handler: VariablesHandler + VariableContainerHandler<View::Container, View = View>,
 {
+    #[allow(clippy::result_unit_err)]
     fn select(&mut self, handler: &Handler) -> Result<View, ()>;
 }
 
 pub trait ValuesSelector<Handler, View>
 where
     View: VariableContainerView,
-    Handler: VariablesHandler + VariableContainerHandler<View>,
+    Handler: VariablesHandler + VariableContainerHandler<View::Container, View = View>,
 {
+    #[allow(clippy::result_unit_err)]
     fn select(
         &mut self,
         handler: &Handler,
         view: View,
-    ) -> Result<Box<Iterator<Item = Box<Fn(&mut Handler) -> () + Send>>>, ()>;
+    ) -> Result<Branches<Handler>, ()>;
 }
 
 pub trait SpecificBrancher<Handler, View>: Brancher<Handler>
 where
-    Handler: VariablesHandler + VariableContainerHandler<View>,
+    Handler: VariablesHandler + VariableContainerHandler<View::Container, View = View>,
     View: VariableContainerView,
 {
+    #[allow(clippy::result_unit_err)]
     fn specific_branch(
         &mut self,
         variables: &Handler,
-    ) -> Result<Box<Iterator<Item = Box<Fn(&mut Handler) -> () + Send>>>, ()>;
+    ) -> Result<Branches<Handler>, ()>;
     // Return None if branch is subsumed
     //fn specific_branch(
     //&mut self,
@@ -44,10 +50,11 @@ where
 }
 
 pub trait Brancher<Handler> {
+    #[allow(clippy::result_unit_err)]
     fn branch(
         &mut self,
         variables: &Handler,
-    ) -> Result<Box<Iterator<Item = Box<Fn(&mut Handler) -> () + Send>>>, ()>
+    ) -> Result<Branches<Handler>, ()>
     where
         Handler: VariablesHandler;
 
@@ -66,16 +73,26 @@ where
     Handler: VariablesHandler,
 {
     fn clone(&self) -> BranchersHandler<Handler> {
-        let branchers = self.branchers
+        let branchers = self
+            .branchers
             .iter()
             .map(|brancher| brancher.mutated_clone())
             .collect();
         BranchersHandler {
-            branchers: branchers,
+            branchers,
         }
     }
 }
 
+impl<Handler> Default for BranchersHandler<Handler>
+where
+    Handler: VariablesHandler,
+{
+    fn default() -> BranchersHandler<Handler> {
+        BranchersHandler::new()
+    }
+}
+
 impl<Handler> BranchersHandler<Handler>
 where
     Handler: VariablesHandler,
@@ -93,13 +110,14 @@ where
         self.branchers.push(branch);
     }
 
+    #[allow(clippy::result_unit_err)]
     pub fn branch(
         &mut self,
         variables: &Handler,
-    ) -> Result<Box<Iterator<Item = Box<Fn(&mut Handler) -> () + Send>>>, ()> {
+    ) -> Result<Branches<Handler>, ()> {
         self.branchers
             .iter_mut()
-            .filter_map(|brancher| brancher.branch(&variables).ok())
+            .filter_map(|brancher| brancher.branch(variables).ok())
             .next()
             .ok_or(())
     }
@@ -1,4 +1,4 @@
-use super::ValuesSelector;
+use super::{Branches, ValuesSelector};
 use variables::domains::{AssignableDomain, IterableDomain, OrderedDomain};
 use variables::handlers::{
     VariableContainerHandler, VariableContainerView, VariablesHandler,
@@ -8,6 +8,12 @@ use variables::Variable;
 #[derive(Clone, Debug)]
 pub struct DomainOrderValueSelector {}
 
+impl Default for DomainOrderValueSelector {
+    fn default() -> DomainOrderValueSelector {
+        DomainOrderValueSelector::new()
+    }
+}
+
 impl DomainOrderValueSelector {
     // Check variables empty and if no doublon
     pub fn new() -> DomainOrderValueSelector {
@@ -19,7 +25,7 @@ impl DomainOrderValueSelector {
 impl<Var, Handler, View> ValuesSelector<Handler, View> for DomainOrderValueSelector
 where
     Handler: VariablesHandler + VariableContainerHandler<Var, View = View> + 'static,
-    View: VariableContainerView + Send + 'static,
+    View: VariableContainerView<Container = Var> + Send + 'static,
     Var: Variable + AssignableDomain + IterableDomain + 'static,
     <Var as Variable>::Type: Send,
 {
@@ -28,15 +34,15 @@ where
         &mut self,
         handler: &Handler,
         view: View,
-    ) -> Result<Box<Iterator<Item = Box<Fn(&mut Handler) -> () + Send>>>, ()> {
+    ) -> Result<Branches<Handler>, ()> {
         let var = handler.get(&view);
-        let branches: Vec<_> = var.iter()
-            .cloned()
+        let branches: Vec<_> = var
+            .iter()
             .map(|val| (val, view.clone()))
             .map(move |(value, view)| {
-                let patch: Box<Fn(&mut Handler) -> () + Send> =
+                let patch: Box<Fn(&mut Handler) + Send> =
                     Box::new(move |vars: &mut Handler| {
-                        let var = handler.get_mut(&view);
+                        let var = vars.get_mut(&view);
                         var.set_value(value.clone())
                             .expect("Should not happen DomainOrderValueSelector Fn.");
                     });
@@ -50,6 +56,12 @@ where
 #[derive(Clone, Debug)]
 pub struct MinValueSelector {}
 
+impl Default for MinValueSelector {
+    fn default() -> MinValueSelector {
+        MinValueSelector::new()
+    }
+}
+
 impl MinValueSelector {
     // Check variables empty and if no doublon
     pub fn new() -> MinValueSelector {
@@ -58,33 +70,29 @@ impl MinValueSelector {
 }
 
 // Remove Into<VariableId> Requirement if possible (does not make sense).
-impl<Var, Handler, View, VarType> ValuesSelector<Handler, View> for MinValueSelector
+impl<Var, Handler, View> ValuesSelector<Handler, View> for MinValueSelector
 where
     Handler: VariablesHandler + VariableContainerHandler<Var, View = View> + 'static,
-    View: VariableContainerView + Send + 'static,
-    Var: Variable<Type = VarType>
-        + AssignableDomain
-        + IterableDomain
-        + OrderedDomain
-        + 'static,
-    VarType: Ord + Eq + Clone + Send + 'static,
+    View: VariableContainerView<Container = Var> + Send + 'static,
+    Var: Variable + AssignableDomain + IterableDomain + OrderedDomain + 'static,
+    Var::Type: Ord + Eq + Send + 'static,
 {
     // Error if no value
     fn select(
         &mut self,
         handler: &Handler,
         view: View,
-    ) -> Result<Box<Iterator<Item = Box<Fn(&mut Handler) -> () + Send>>>, ()> {
+    ) -> Result<Branches<Handler>, ()> {
         let var = handler.get(&view);
-        let mut values: Vec<_> = var.iter().cloned().collect();
+        let mut values: Vec<_> = var.iter().collect();
         values.sort();
         let branches: Vec<_> = values
             .into_iter()
             .map(|val| (val, view.clone()))
             .map(move |(value, view)| {
-                let patch: Box<Fn(&mut Handler) -> () + Send> =
+                let patch: Box<Fn(&mut Handler) + Send> =
                     Box::new(move |vars: &mut Handler| {
-                        let var = handler.get_mut(&view);
+                        let var = vars.get_mut(&view);
                         var.set_value(value.clone())
                             .expect("Should not happen DomainOrderValueSelector Fn.");
                     });
@@ -97,6 +105,12 @@ where
 #[derive(Clone, Debug)]
 pub struct MaxValueSelector {}
 
+impl Default for MaxValueSelector {
+    fn default() -> MaxValueSelector {
+        MaxValueSelector::new()
+    }
+}
+
 impl MaxValueSelector {
     // Check variables empty and if no doublon
     pub fn new() -> MaxValueSelector {
@@ -105,34 +119,30 @@ impl MaxValueSelector {
 }
 
 // Remove Into<VariableId> Requirement if possible (does not make sense).
-impl<Var, Handler, View, VarType> ValuesSelector<Handler, View> for MaxValueSelector
+impl<Var, Handler, View> ValuesSelector<Handler, View> for MaxValueSelector
 where
     Handler: VariablesHandler + VariableContainerHandler<Var, View = View> + 'static,
-    View: VariableContainerView + Send + 'static,
-    Var: Variable<Type = VarType>
-        + AssignableDomain
-        + IterableDomain
-        + OrderedDomain
-        + 'static,
-    VarType: Ord + Eq + Clone + Send + 'static,
+    View: VariableContainerView<Container = Var> + Send + 'static,
+    Var: Variable + AssignableDomain + IterableDomain + OrderedDomain + 'static,
+    Var::Type: Ord + Eq + Send + 'static,
 {
     // Error if no value
     fn select(
         &mut self,
         handler: &Handler,
         view: View,
-    ) -> Result<Box<Iterator<Item = Box<Fn(&mut Handler) -> () + Send>>>, ()> {
+    ) -> Result<Branches<Handler>, ()> {
         let var = handler.get(&view);
-        let mut values: Vec<_> = var.iter().cloned().collect();
+        let mut values: Vec<_> = var.iter().collect();
         values.sort();
         let branches: Vec<_> = values
             .into_iter()
             .rev()
             .map(|val| (val, view.clone()))
             .map(move |(value, view)| {
-                let patch: Box<Fn(&mut Handler) -> () + Send> =
+                let patch: Box<Fn(&mut Handler) + Send> =
                     Box::new(move |vars: &mut Handler| {
-                        let var = handler.get_mut(&view);
+                        let var = vars.get_mut(&view);
                         var.set_value(value.clone())
                             .expect("Should not happen DomainOrderValueSelector Fn.");
                     });
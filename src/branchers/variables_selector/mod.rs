@@ -19,6 +19,7 @@ where
     View: VariableContainerView,
 {
     // Check variables empty and if no doublon
+    #[allow(clippy::result_unit_err)]
     pub fn new<Views: Iterator<Item = View>>(
         variables: Views,
     ) -> Result<SequentialVariableSelector<View>, ()> {
@@ -32,18 +33,17 @@ impl<Var, Handler, View> VariableSelector<Handler, View>
     for SequentialVariableSelector<View>
 where
     Handler: VariablesHandler + VariableContainerHandler<Var, View = View>,
-    View: VariableContainerView,
+    View: VariableContainerView<Container = Var>,
     Var: Variable,
 {
     fn select(&mut self, handler: &Handler) -> Result<View, ()> {
         self.variables
             .iter()
-            .filter(|&view| {
+            .find(|&view| {
                 let var = handler.get(view);
                 !var.is_affected()
             })
             .cloned()
-            .next()
             .ok_or(())
     }
 }
@@ -62,6 +62,7 @@ where
     View: VariableContainerView,
 {
     // Check variables empty and if no doublon
+    #[allow(clippy::result_unit_err)]
     pub fn new<Views: Iterator<Item = View>>(
         variables: Views,
     ) -> Result<SmallestDomainVariableSelector<View>, ()> {
@@ -75,14 +76,15 @@ impl<Var, Handler, View> VariableSelector<Handler, View>
     for SmallestDomainVariableSelector<View>
 where
     Handler: VariablesHandler + VariableContainerHandler<Var, View = View>,
-    View: VariableContainerView,
+    View: VariableContainerView<Container = Var>,
     Var: Variable + FiniteDomain,
 {
     fn select(&mut self, handler: &Handler) -> Result<View, ()> {
-        let mut variables = self.variables
+        let mut variables = self
+            .variables
             .iter()
             .map(|view| {
-                let var = handler.get(&view);
+                let var = handler.get(view);
                 (view, var.size())
             })
             .filter(|&(_, dom)| dom > 1)
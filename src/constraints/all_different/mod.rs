@@ -1,21 +1,23 @@
+use constraints::global_cardinality::consistent_domains;
 use constraints::Constraint;
 use constraints::PropagationState;
-use std::cell::RefCell;
-use std::collections::HashMap;
-use std::rc::Rc;
+use std::collections::{BTreeMap, HashMap};
+use std::convert::TryFrom;
 use std::sync::Arc;
-use variables::domains::PrunableDomain;
-use variables::handlers::{VariableContainerHandler, VariablesHandler};
-use variables::{Array, Variable, VariableError, VariableId, VariableState};
+use variables::domains::{IterableDomain, OrderedDomain, PrunableDomain};
+use variables::handlers::{
+    VariableContainerHandler, VariableContainerView, VariablesHandler,
+};
+use variables::{Array, VariableError, VariableId, VariableState};
 
-#[derive(Debug, Clone)]
-pub struct AllDifferent2<Var, Vars>
+/// Enforces that the variables of the array take different values. The values of the
+/// affected variables are removed from the other variables.
+#[derive(Clone)]
+pub struct AllDifferent<Views>
 where
-    Var: Variable,
-    Var::Type: Eq + Ord + Clone,
-    Vars: Array<Variable = Var>,
+    Views: VariableContainerView,
 {
-    array: Rc<RefCell<Vars>>,
+    array: Views,
     used: Vec<bool>,
     nb_used: usize,
     output: Option<Vec<(VariableId, VariableState)>>,
@@ -23,32 +25,13 @@ where
     changes: Option<Vec<usize>>,
 }
 
-#[derive(Debug, Clone)]
-pub struct AllDifferent<Var, Vars, VCH>
+impl<Views> AllDifferent<Views>
 where
-    Var: Variable,
-    Var::Type: Eq + Ord + Clone,
-    Vars: Array<Variable = Var>,
-    VCH: VariableContainerHandler<Vars>,
+    Views: VariableContainerView,
 {
-    array: VCH::View,
-    used: Vec<bool>,
-    nb_used: usize,
-    output: Option<Vec<(VariableId, VariableState)>>,
-    id_to_pos: Arc<HashMap<VariableId, usize>>,
-    changes: Option<Vec<usize>>,
-}
-
-impl<Var, Vars, VCH> AllDifferent<Var, Vars, VCH>
-where
-    Var: Variable,
-    Var::Type: Eq + Ord + Clone,
-    Vars: Array<Variable = Var>,
-    VCH: VariableContainerHandler<Vars>,
-{
-    pub fn new(array: VCH::View) -> Self {
+    pub fn new(array: Views) -> AllDifferent<Views> {
         AllDifferent {
-            array: array,
+            array,
             used: vec![],
             nb_used: 0,
             output: None,
@@ -56,26 +39,344 @@ where
             changes: None,
         }
     }
+
+    fn all_different(&self) -> &AllDifferent<Views> {
+        self
+    }
+
+    fn all_different_mut(&mut self) -> &mut AllDifferent<Views> {
+        self
+    }
+}
+
+/// Enforces that the variables of the array take different values. The bounds of the
+/// variables are made consistent with the Hall intervals, the intervals containing as
+/// many variables as values [LopezOrtizQuimperTrompVanBeek03].
+#[derive(Clone)]
+pub struct AllDifferentBounds<Views>
+where
+    Views: VariableContainerView,
+{
+    inner: AllDifferent<Views>,
+}
+
+impl<Views> AllDifferentBounds<Views>
+where
+    Views: VariableContainerView,
+{
+    pub fn new(array: Views) -> AllDifferentBounds<Views> {
+        AllDifferentBounds {
+            inner: AllDifferent::new(array),
+        }
+    }
+
+    fn all_different(&self) -> &AllDifferent<Views> {
+        &self.inner
+    }
+
+    fn all_different_mut(&mut self) -> &mut AllDifferent<Views> {
+        &mut self.inner
+    }
+}
+
+/// Enforces that the variables of the array take different values. The values which
+/// do not belong to a maximum matching between the variables and the values are
+/// removed, which makes the domains of the variables consistent [Regin94].
+#[derive(Clone)]
+pub struct AllDifferentDomain<Views>
+where
+    Views: VariableContainerView,
+{
+    inner: AllDifferent<Views>,
+}
+
+impl<Views> AllDifferentDomain<Views>
+where
+    Views: VariableContainerView,
+{
+    pub fn new(array: Views) -> AllDifferentDomain<Views> {
+        AllDifferentDomain {
+            inner: AllDifferent::new(array),
+        }
+    }
+
+    fn all_different(&self) -> &AllDifferent<Views> {
+        &self.inner
+    }
+
+    fn all_different_mut(&mut self) -> &mut AllDifferent<Views> {
+        &mut self.inner
+    }
+}
+
+// A filtering of the domains which returns the positions of the modified variables.
+type Filter<Vars> = fn(
+    &mut Vars,
+    &mut Vec<(VariableId, VariableState)>,
+) -> Result<Vec<usize>, VariableError>;
+
+// Keeps the values which belong to a maximum matching between the variables and the
+// values, and returns the positions of the modified variables.
+fn filter_matching<Var, Vars>(
+    vars: &mut Vars,
+    output: &mut Vec<(VariableId, VariableState)>,
+) -> Result<Vec<usize>, VariableError>
+where
+    Var: PrunableDomain + IterableDomain,
+    Var::Type: Eq + Ord,
+    Vars: Array<Variable = Var>,
+{
+    let mut indexes = BTreeMap::new();
+    let mut values = vec![];
+    let domains: Vec<Vec<usize>> = vars
+        .iter()
+        .map(|var| {
+            var.iter()
+                .map(|value| {
+                    let len = values.len();
+                    *indexes.entry(value.clone()).or_insert_with(|| {
+                        values.push(value);
+                        len
+                    })
+                })
+                .collect()
+        })
+        .collect();
+    let lower = vec![0; values.len()];
+    let upper = vec![1; values.len()];
+    let domains = consistent_domains(&domains, &lower, &upper)?;
+
+    let mut changes = vec![];
+    for (pos, (var, domain)) in vars.iter_mut().zip(domains).enumerate() {
+        match var.in_values(domain.into_iter().map(|value| values[value].clone()))? {
+            VariableState::NoChange => {}
+            state => {
+                output.push((var.id(), state));
+                changes.push(pos);
+            }
+        }
+    }
+    Ok(changes)
+}
+
+// The tightened lower and upper bounds of a variable, if any.
+type NewBounds = (Option<i64>, Option<i64>);
+
+// The bounds of a variable, the upper bound being excluded, and their ranks among the
+// sorted bounds of every variables.
+#[derive(Clone, Copy)]
+struct Interval {
+    min: i64,
+    max: i64,
+    min_rank: usize,
+    max_rank: usize,
+}
+
+fn path_max(tree: &[usize], mut pos: usize) -> usize {
+    while tree[pos] > pos {
+        pos = tree[pos];
+    }
+    pos
+}
+
+fn path_min(tree: &[usize], mut pos: usize) -> usize {
+    while tree[pos] < pos {
+        pos = tree[pos];
+    }
+    pos
+}
+
+fn path_set(tree: &mut [usize], start: usize, end: usize, to: usize) {
+    let mut pos = start;
+    while pos != end {
+        let next = tree[pos];
+        tree[pos] = to;
+        pos = next;
+    }
+}
+
+// Computes the new bounds of the variables following the union-find based algorithm of
+// [LopezOrtizQuimperTrompVanBeek03], which runs in O(n log n). The lower bounds are
+// computed by sweeping the intervals by increasing upper bounds and the upper bounds by
+// sweeping them by decreasing lower bounds.
+fn hall_bounds(
+    intervals: &mut [Interval],
+) -> Result<Vec<NewBounds>, VariableError> {
+    let len = intervals.len();
+    let mut new_bounds = vec![(None, None); len];
+    if len == 0 {
+        return Ok(new_bounds);
+    }
+    let mut min_sorted: Vec<usize> = (0..len).collect();
+    min_sorted.sort_by_key(|&pos| intervals[pos].min);
+    let mut max_sorted: Vec<usize> = (0..len).collect();
+    max_sorted.sort_by_key(|&pos| intervals[pos].max);
+
+    // The distinct bounds, surrounded by two sentinels.
+    let mut bounds = vec![0; 2 * len + 2];
+    let mut nb = 0;
+    let mut last = intervals[min_sorted[0]].min - 2;
+    bounds[0] = last;
+    let (mut i, mut j) = (0, 0);
+    loop {
+        if i < len && intervals[min_sorted[i]].min <= intervals[max_sorted[j]].max {
+            let min = intervals[min_sorted[i]].min;
+            if min != last {
+                nb += 1;
+                bounds[nb] = min;
+                last = min;
+            }
+            intervals[min_sorted[i]].min_rank = nb;
+            i += 1;
+        } else {
+            let max = intervals[max_sorted[j]].max;
+            if max != last {
+                nb += 1;
+                bounds[nb] = max;
+                last = max;
+            }
+            intervals[max_sorted[j]].max_rank = nb;
+            j += 1;
+            if j == len {
+                break;
+            }
+        }
+    }
+    bounds[nb + 1] = bounds[nb] + 2;
+
+    let mut tree = vec![0; 2 * len + 2];
+    let mut hall = vec![0; 2 * len + 2];
+    let mut capacity = vec![0; 2 * len + 2];
+
+    for i in 1..(nb + 2) {
+        tree[i] = i - 1;
+        hall[i] = i - 1;
+        capacity[i] = bounds[i] - bounds[i - 1];
+    }
+    for &pos in max_sorted.iter() {
+        let (x, y) = (intervals[pos].min_rank, intervals[pos].max_rank);
+        let mut z = path_max(&tree, x + 1);
+        let j = tree[z];
+        capacity[z] -= 1;
+        if capacity[z] == 0 {
+            tree[z] = z + 1;
+            z = path_max(&tree, z + 1);
+            tree[z] = j;
+        }
+        path_set(&mut tree, x + 1, z, z);
+        if capacity[z] < bounds[z] - bounds[y] {
+            return Err(VariableError::DomainWipeout);
+        }
+        if hall[x] > x {
+            let w = path_max(&hall, hall[x]);
+            new_bounds[pos].0 = Some(bounds[w]);
+            path_set(&mut hall, x, w, w);
+        }
+        if capacity[z] == bounds[z] - bounds[y] {
+            let start = hall[y];
+            path_set(&mut hall, start, j - 1, y);
+            hall[y] = j - 1;
+        }
+    }
+
+    for i in 0..(nb + 1) {
+        tree[i] = i + 1;
+        hall[i] = i + 1;
+        capacity[i] = bounds[i + 1] - bounds[i];
+    }
+    for &pos in min_sorted.iter().rev() {
+        let (x, y) = (intervals[pos].max_rank, intervals[pos].min_rank);
+        let mut z = path_min(&tree, x - 1);
+        let j = tree[z];
+        capacity[z] -= 1;
+        if capacity[z] == 0 {
+            tree[z] = z - 1;
+            z = path_min(&tree, z - 1);
+            tree[z] = j;
+        }
+        path_set(&mut tree, x - 1, z, z);
+        if capacity[z] < bounds[y] - bounds[z] {
+            return Err(VariableError::DomainWipeout);
+        }
+        if hall[x] < x {
+            let w = path_min(&hall, hall[x]);
+            new_bounds[pos].1 = Some(bounds[w] - 1);
+            path_set(&mut hall, x, w, w);
+        }
+        if capacity[z] == bounds[y] - bounds[z] {
+            let start = hall[y];
+            path_set(&mut hall, start, j + 1, y);
+            hall[y] = j + 1;
+        }
+    }
+    Ok(new_bounds)
+}
+
+// Updates the bounds of the variables with the Hall intervals, and returns the
+// positions of the modified variables.
+fn filter_hall_intervals<Var, Vars>(
+    vars: &mut Vars,
+    output: &mut Vec<(VariableId, VariableState)>,
+) -> Result<Vec<usize>, VariableError>
+where
+    Var: OrderedDomain,
+    Var::Type: Eq + Ord + Into<i64> + TryFrom<i64>,
+    Vars: Array<Variable = Var>,
+{
+    let mut intervals: Vec<_> = vars
+        .iter()
+        .map(|var| Interval {
+            min: var.unchecked_min().into(),
+            max: var.unchecked_max().into() + 1,
+            min_rank: 0,
+            max_rank: 0,
+        })
+        .collect();
+    let new_bounds = hall_bounds(&mut intervals)?;
+
+    let mut changes = vec![];
+    for (pos, (var, (min, max))) in vars.iter_mut().zip(new_bounds).enumerate() {
+        let mut states = vec![];
+        if let Some(min) = min {
+            let min =
+                Var::Type::try_from(min).map_err(|_| VariableError::DomainWipeout)?;
+            states.push(var.weak_lowerbound(min)?);
+        }
+        if let Some(max) = max {
+            let max =
+                Var::Type::try_from(max).map_err(|_| VariableError::DomainWipeout)?;
+            states.push(var.weak_upperbound(max)?);
+        }
+        let mut changed = false;
+        for state in states {
+            match state {
+                VariableState::NoChange => {}
+                state => {
+                    output.push((var.id(), state));
+                    changed = true;
+                }
+            }
+        }
+        if changed {
+            changes.push(pos);
+        }
+    }
+    Ok(changes)
 }
-use std::fmt;
 
-impl<Var, Vars, VCH> AllDifferent<Var, Vars, VCH>
+impl<Var, Vars, Views> AllDifferent<Views>
 where
-    Var: Variable + PrunableDomain + 'static,
-    Var::Type: Eq + Ord + Clone + 'static + fmt::Display + fmt::Debug,
+    Views: VariableContainerView<Container = Vars>,
     Vars: Array<Variable = Var>,
-    VCH: VariableContainerHandler<Vars>,
+    Var: PrunableDomain,
+    Var::Type: Eq + Ord,
 {
-    fn propagate_changes<Handler>(
+    fn propagate_changes(
         &mut self,
-        variables_handler: &mut Handler,
-    ) -> Result<PropagationState, VariableError>
-    where
-        Handler: VariablesHandler + VariableContainerHandler<Vars> + Clone,
-    {
+        vars: &mut Vars,
+    ) -> Result<PropagationState, VariableError> {
         use std::collections::BTreeSet;
         use std::mem;
-        use variables::VariableState;
 
         let mut changes = None;
         mem::swap(&mut changes, &mut self.changes);
@@ -85,80 +386,69 @@ where
         let mut changes = changes.unwrap();
         changes.sort();
 
-        {
-            let vars: &mut Vars = variables_handler.get_mut(&self.array);
-
-            let mut affected = BTreeSet::new();
+        let mut affected = BTreeSet::new();
 
-            for pos in changes {
-                let var = vars.get_unchecked_mut(pos);
-                if !var.is_affected() || self.used[pos] {
-                    continue;
-                }
-                let val = var.value().unwrap();
-                self.used[pos] = true;
-                self.nb_used += 1;
-                if !affected.insert(val) {
-                    return Err(VariableError::DomainWipeout);
-                }
+        for pos in changes {
+            let var = vars.get_unchecked_mut(pos);
+            if !var.is_affected() || self.used[pos] {
+                continue;
+            }
+            let val = var.value().unwrap();
+            self.used[pos] = true;
+            self.nb_used += 1;
+            if !affected.insert(val) {
+                return Err(VariableError::DomainWipeout);
             }
+        }
 
-            let mut changes = vec![];
-            for (pos, (var, _)) in vars
-                .iter_mut()
-                .zip(self.used.iter())
-                .enumerate()
-                .filter(|&(_, (_, ref used))| !**used)
-            {
-                match var.remove_if(|val| affected.contains(&val))? {
-                    VariableState::NoChange => {}
-                    state => {
-                        self.output.as_mut().unwrap().push((var.id(), state));
-                    }
-                }
-                if var.is_affected() {
-                    changes.push(pos);
+        let mut changes = vec![];
+        for (pos, (var, _)) in vars
+            .iter_mut()
+            .zip(self.used.iter())
+            .enumerate()
+            .filter(|&(_, (_, used))| !*used)
+        {
+            match var.remove_if(|val| affected.contains(val))? {
+                VariableState::NoChange => {}
+                state => {
+                    self.output.as_mut().unwrap().push((var.id(), state));
                 }
             }
-            if changes.is_empty() {
-                if self.nb_used == self.used.len() {
-                    return Ok(PropagationState::Subsumed);
-                }
+            if var.is_affected() {
+                changes.push(pos);
+            }
+        }
+        if changes.is_empty() {
+            if self.nb_used == self.used.len() {
+                return Ok(PropagationState::Subsumed);
+            }
 
-                if !self.output.as_ref().unwrap().is_empty() {
-                    return Ok(PropagationState::FixPoint);
-                } else {
-                    self.output = None;
-                    return Ok(PropagationState::NoChange);
-                }
+            if !self.output.as_ref().unwrap().is_empty() {
+                return Ok(PropagationState::FixPoint);
+            } else {
+                self.output = None;
+                return Ok(PropagationState::NoChange);
             }
-            self.changes = Some(changes);
         }
+        self.changes = Some(changes);
 
-        self.propagate_changes(variables_handler)
+        self.propagate_changes(vars)
     }
 
-    fn propagate_all<Handler>(
+    fn propagate_all(
         &mut self,
-        variables_handler: &mut Handler,
-    ) -> Result<PropagationState, VariableError>
-    where
-        Handler: VariablesHandler + VariableContainerHandler<Vars> + Clone,
-    {
+        vars: &mut Vars,
+    ) -> Result<PropagationState, VariableError> {
         use std::collections::BTreeSet;
-        use variables::VariableState;
 
         let mut output = vec![];
         self.output = None;
 
         {
-            let vars = variables_handler.get_mut(&self.array);
-
-            let (new_affected, unaffected): (Vec<_>, Vec<_>) = vars.iter()
-                .zip(self.used.iter())
+            let (new_affected, unaffected): (Vec<_>, Vec<_>) = vars
+                .iter()
                 .enumerate()
-                //.filter(|&(_, (_, ref used))| !**used)
-                .map(|(pos, (var, _))| (pos, var.value()))
+                .map(|(pos, var)| (pos, var.value()))
                 .partition(|(_, val)| val.is_some());
             let mut affected = BTreeSet::new();
             for (pos, val) in new_affected.into_iter() {
@@ -178,7 +468,7 @@ where
             let mut changes = Vec::new();
             for (pos, _) in unaffected.into_iter() {
                 let var = vars.get_unchecked_mut(pos);
-                match var.remove_if(|val| affected.contains(&val))? {
+                match var.remove_if(|val| affected.contains(val))? {
                     VariableState::NoChange => {}
                     state => {
                         output.push((var.id(), state));
@@ -186,44 +476,52 @@ where
                     }
                 }
             }
+            // The modified variables may have become affected.
+            self.changes = Some(changes);
         }
 
         if !output.is_empty() {
             self.output = Some(output);
-            self.propagate_changes(variables_handler)
+            self.propagate_changes(vars)
         } else {
             Ok(PropagationState::NoChange)
         }
     }
-}
-
-impl<Var, Vars, VCH> Constraint<VCH> for AllDifferent<Var, Vars, VCH>
-where
-    VCH: VariablesHandler + VariableContainerHandler<Vars> + Clone,
-    Var: Variable + PrunableDomain + 'static,
-    Var::Type: Eq + Ord + Clone + 'static + fmt::Display + fmt::Debug,
-    Vars: Array<Variable = Var>,
-    VCH: VariableContainerHandler<Vars>,
-{
-    fn box_clone(&self) -> Box<Constraint<VCH>> {
-        let ref_self: &AllDifferent<Var, Vars, VCH> = &self;
-        let cloned: AllDifferent<Var, Vars, VCH> =
-            <AllDifferent<Var, Vars, VCH> as Clone>::clone(ref_self);
 
-        Box::new(cloned) as Box<Constraint<VCH>>
+    fn propagate_values(
+        &mut self,
+        vars: &mut Vars,
+    ) -> Result<PropagationState, VariableError> {
+        match self.changes {
+            None => self.propagate_all(vars),
+            _ => self.propagate_changes(vars),
+        }
     }
 
-    fn propagate(
+    // Alternates the filtering of `filter` with the removal of the values of the
+    // affected variables until the fixpoint.
+    fn propagate_consistency(
         &mut self,
-        variables_handler: &mut VCH,
+        vars: &mut Vars,
+        filter: Filter<Vars>,
     ) -> Result<PropagationState, VariableError> {
-        match self.changes {
-            None => self.propagate_all(variables_handler),
-            _ => self.propagate_changes(variables_handler),
+        let mut state = self.propagate_values(vars)?;
+        loop {
+            if let PropagationState::Subsumed = state {
+                return Ok(state);
+            }
+            let mut output = vec![];
+            let changes = filter(vars, &mut output)?;
+            if changes.is_empty() {
+                return Ok(state);
+            }
+            self.output.get_or_insert_with(Vec::new).extend(output);
+            self.changes = Some(changes);
+            state = self.propagate_changes(vars)?;
         }
     }
-    #[allow(unused)]
-    fn prepare(&mut self, states: Box<Iterator<Item = VariableId>>) {
+
+    fn prepare_changes(&mut self, states: Box<Iterator<Item = VariableId>>) {
         self.changes = Some(
             states
                 .map(|id| {
@@ -231,10 +529,12 @@ where
                         .id_to_pos
                         .get(&id)
                         .expect("Error AllDifferent unknown VariableId.")
-                }).collect::<Vec<_>>(),
+                })
+                .collect::<Vec<_>>(),
         );
     }
-    fn result(&mut self) -> Box<Iterator<Item = (VariableId, VariableState)>> {
+
+    fn take_result(&mut self) -> Box<Iterator<Item = (VariableId, VariableState)>> {
         use std::mem;
         let mut res = None;
         mem::swap(&mut self.output, &mut res);
@@ -243,40 +543,233 @@ where
             Some(changes) => Box::new(changes.into_iter()),
         }
     }
-    fn dependencies(
-        &self,
-        variables_handler: &VCH,
-    ) -> Box<Iterator<Item = (VariableId, VariableState)>> {
-        //Box::new({
-        //let deps: Vec<_> = self.id_to_pos
-        //.keys()
-        //.cloned()
-        //.map(|id| (id, VariableState::MaxBoundChange))
-        //.collect();
-        //deps.into_iter()
-        //})
-        let deps: Vec<_> = variables_handler
-            .get(&self.array)
-            .iter()
-            .map(|var| (var.id(), VariableState::ValuesChange))
-            .collect();
-        Box::new(deps.into_iter())
-    }
-    fn initialise(
-        &mut self,
-        variables_handler: &mut VCH,
-    ) -> Result<PropagationState, VariableError> {
-        let len = variables_handler.get(&self.array).len();
-        self.used = vec![false; len];
+
+    fn initialise_positions(&mut self, vars: &Vars) {
+        self.used = vec![false; vars.len()];
+        self.nb_used = 0;
         self.id_to_pos = Arc::new(
-            variables_handler
-                .get(&self.array)
-                .iter()
+            vars.iter()
                 .map(|val| val.id())
                 .enumerate()
                 .map(|(pos, id)| (id, pos))
                 .collect(),
         );
-        self.propagate(variables_handler)
+    }
+}
+
+macro_rules! all_different_constraint_impl {
+    ($name:ident; |$all_different:ident, $vars:ident| $propagate:expr; $($bounds:tt)+) => {
+        impl<Var, Vars, Views, Handler> Constraint<Handler> for $name<Views>
+        where
+            Handler: VariablesHandler + VariableContainerHandler<Vars, View = Views> + Clone,
+            Views: VariableContainerView<Container = Vars> + 'static,
+            Vars: Array<Variable = Var>,
+            $($bounds)+
+        {
+            fn box_clone(&self) -> Box<Constraint<Handler>> {
+                let ref_self: &$name<Views> = &self;
+                let cloned: $name<Views> = <$name<Views> as Clone>::clone(ref_self);
+
+                Box::new(cloned) as Box<Constraint<Handler>>
+            }
+            fn propagate(
+                &mut self,
+                variables_handler: &mut Handler,
+            ) -> Result<PropagationState, VariableError> {
+                let $all_different = self.all_different_mut();
+                let array = $all_different.array.clone();
+                let $vars = variables_handler.get_mut(&array);
+                $propagate
+            }
+            fn prepare(&mut self, states: Box<Iterator<Item = VariableId>>) {
+                self.all_different_mut().prepare_changes(states)
+            }
+            fn result(&mut self) -> Box<Iterator<Item = (VariableId, VariableState)>> {
+                self.all_different_mut().take_result()
+            }
+            fn dependencies(
+                &self,
+                variables_handler: &Handler,
+            ) -> Box<Iterator<Item = (VariableId, VariableState)>> {
+                let array = &self.all_different().array;
+                let deps: Vec<_> = variables_handler
+                    .get(array)
+                    .iter()
+                    .map(|var| (var.id(), VariableState::ValuesChange))
+                    .collect();
+                Box::new(deps.into_iter())
+            }
+            fn initialise(
+                &mut self,
+                variables_handler: &mut Handler,
+            ) -> Result<PropagationState, VariableError> {
+                {
+                    let all_different = self.all_different_mut();
+                    let vars = variables_handler.get(&all_different.array);
+                    all_different.initialise_positions(vars);
+                }
+                self.propagate(variables_handler)
+            }
+        }
+    };
+}
+
+all_different_constraint_impl!(
+    AllDifferent; |all_different, vars| all_different.propagate_values(vars);
+    Var: PrunableDomain,
+    Var::Type: Eq + Ord,
+);
+all_different_constraint_impl!(
+    AllDifferentBounds;
+    |all_different, vars| all_different.propagate_consistency(vars, filter_hall_intervals);
+    Var: PrunableDomain + OrderedDomain,
+    Var::Type: Eq + Ord + Into<i64> + TryFrom<i64>,
+);
+all_different_constraint_impl!(
+    AllDifferentDomain;
+    |all_different, vars| all_different.propagate_consistency(vars, filter_matching);
+    Var: PrunableDomain + IterableDomain,
+    Var::Type: Eq + Ord,
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use branchers::values_selector::MinValueSelector;
+    use branchers::variables_selector::SequentialVariableSelector;
+    use constraints::arithmetic::AddConstant;
+    use model::{Model, ModelError, ModelSpace};
+    use search::Solver;
+    use variables::handlers::macros::VarView;
+    use variables::int_var::IntVarValues;
+
+    #[derive(Clone, Copy)]
+    enum Consistency {
+        Value,
+        Bounds,
+        Domain,
+    }
+
+    fn post(
+        model: &mut Model,
+        vars: Vec<VarView<IntVarValues>>,
+        consistency: Consistency,
+    ) {
+        let array = model.new_array_of_refs(vars).unwrap();
+        match consistency {
+            Consistency::Value => model.post(AllDifferent::new(array)),
+            Consistency::Bounds => model.post(AllDifferentBounds::new(array)),
+            Consistency::Domain => model.post(AllDifferentDomain::new(array)),
+        }
+    }
+
+    fn queens(n: i32, consistency: Consistency) -> usize {
+        let mut model = Model::new();
+        let queens: Vec<_> = (0..n)
+            .map(|_| model.new_int_var(0, n - 1).unwrap())
+            .collect();
+        let mut diag1 = vec![];
+        let mut diag2 = vec![];
+        for (i, &queen) in queens.iter().enumerate() {
+            let i = i as i32;
+            let d1 = model.new_int_var(i, n - 1 + i).unwrap();
+            let d2 = model.new_int_var(-i, n - 1 - i).unwrap();
+            model.post(AddConstant::new(d1, queen, i));
+            model.post(AddConstant::new(d2, queen, -i));
+            diag1.push(d1);
+            diag2.push(d2);
+        }
+        post(&mut model, queens.clone(), consistency);
+        post(&mut model, diag1, consistency);
+        post(&mut model, diag2, consistency);
+        model.branch(
+            SequentialVariableSelector::new(queens.into_iter()).unwrap(),
+            MinValueSelector::new(),
+        );
+        Solver::new(model.build().unwrap()).count_solutions()
+    }
+
+    // Three pigeons in two holes, with a fourth variable free.
+    fn pigeonhole(consistency: Consistency) -> Result<ModelSpace, ModelError> {
+        let mut model = Model::new();
+        let mut vars: Vec<_> = (0..3).map(|_| model.new_int_var(0, 1).unwrap()).collect();
+        let free = model.new_int_var(0, 5).unwrap();
+        vars.push(free);
+        post(&mut model, vars.clone(), consistency);
+        model.branch(
+            SequentialVariableSelector::new(vars.into_iter()).unwrap(),
+            MinValueSelector::new(),
+        );
+        model.build()
+    }
+
+    fn domain(space: &ModelSpace, view: &VarView<IntVarValues>) -> Vec<i32> {
+        let var: &IntVarValues = space.get_variable(view);
+        var.iter().collect()
+    }
+
+    #[test]
+    fn nqueens() {
+        for &consistency in
+            [Consistency::Value, Consistency::Bounds, Consistency::Domain].iter()
+        {
+            assert_eq!(queens(4, consistency), 2);
+            assert_eq!(queens(5, consistency), 10);
+            assert_eq!(queens(6, consistency), 4);
+        }
+    }
+
+    #[test]
+    fn pigeonhole_without_search() {
+        // Only the values of the assigned variables are removed.
+        let mut space = pigeonhole(Consistency::Value).unwrap();
+        assert!(space.run().is_ok());
+        assert_eq!(Solver::new(space).count_solutions(), 0);
+        // The Hall interval [0, 1] is too small for three variables.
+        assert!(pigeonhole(Consistency::Bounds).is_err());
+        assert!(pigeonhole(Consistency::Domain).is_err());
+    }
+
+    #[test]
+    fn bounds_prune() {
+        // x and y fill the Hall interval [1, 2].
+        let mut model = Model::new();
+        let x = model.new_int_var(1, 2).unwrap();
+        let y = model.new_int_var(1, 2).unwrap();
+        let z = model.new_int_var(1, 4).unwrap();
+        let t = model.new_int_var_from_values(vec![0, 2, 4]).unwrap();
+        post(&mut model, vec![x, y, z, t], Consistency::Bounds);
+        let mut space = model.build().unwrap();
+        space.run().unwrap();
+        assert_eq!(domain(&space, &z), vec![3, 4]);
+        // Only the bounds are filtered, 2 stays in the domain of t.
+        assert_eq!(domain(&space, &t), vec![0, 2, 4]);
+    }
+
+    #[test]
+    fn domain_prunes() {
+        let mut model = Model::new();
+        let x = model.new_int_var(1, 2).unwrap();
+        let y = model.new_int_var(1, 2).unwrap();
+        let z = model.new_int_var(1, 4).unwrap();
+        let t = model.new_int_var_from_values(vec![0, 2, 4]).unwrap();
+        post(&mut model, vec![x, y, z, t], Consistency::Domain);
+        let mut space = model.build().unwrap();
+        space.run().unwrap();
+        assert_eq!(domain(&space, &z), vec![3, 4]);
+        assert_eq!(domain(&space, &t), vec![0, 4]);
+    }
+
+    #[test]
+    fn value_prunes() {
+        let mut model = Model::new();
+        let x = model.new_int_var(1, 1).unwrap();
+        let y = model.new_int_var(1, 2).unwrap();
+        let z = model.new_int_var(1, 3).unwrap();
+        post(&mut model, vec![x, y, z], Consistency::Value);
+        let mut space = model.build().unwrap();
+        space.run().unwrap();
+        assert_eq!(domain(&space, &y), vec![2]);
+        assert_eq!(domain(&space, &z), vec![3]);
     }
 }
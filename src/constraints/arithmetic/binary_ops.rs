@@ -1,10 +1,11 @@
 use constraints::{Constraint, PropagationState};
+use std::fmt::Debug;
 use std::ops::{Add, Sub};
 use variables::domains::{IterableDomain, PrunableDomain};
 use variables::handlers::{
     VariableContainerHandler, VariableContainerView, VariablesHandler,
 };
-use variables::{Variable, VariableError, VariableId, VariableState};
+use variables::{VariableError, VariableId, VariableState};
 
 #[derive(Clone)]
 pub struct AddConstant<VarType, View>
@@ -25,20 +26,18 @@ where
 {
     pub fn new(res: View, var: View, coef: VarType) -> AddConstant<VarType, View> {
         AddConstant {
-            res: res,
-            var: var,
-            coef: coef,
+            res,
+            var,
+            coef,
             output: None,
         }
     }
 }
 
-use std::fmt::Debug;
-
 impl<Var, VarType, View, Handler> Constraint<Handler> for AddConstant<VarType, View>
 where
-    Handler: VariablesHandler + VariableContainerHandler<View> + Clone,
-    View: VariableContainerView + 'static,
+    Handler: VariablesHandler + VariableContainerHandler<Var, View = View> + Clone,
+    View: VariableContainerView<Container = Var> + 'static,
     Var: PrunableDomain<Type = VarType> + IterableDomain + Debug,
     VarType: Eq
         + Ord
@@ -49,7 +48,7 @@ where
         + Debug,
 {
     fn box_clone(&self) -> Box<Constraint<Handler>> {
-        let ref_self: &AddConstant<VarType, View> = &self;
+        let ref_self: &AddConstant<VarType, View> = self;
         let cloned: AddConstant<VarType, View> =
             <AddConstant<VarType, View> as Clone>::clone(ref_self);
 
@@ -68,10 +67,9 @@ where
             let var: &mut Var =
                 unsafe_from_raw_point!(variables_handler.get_mut(&self.var));
             let domain: Vec<_> = var.iter()
-                .cloned()
                 .map(|var| var + self.coef.clone())
                 .collect();
-            let state = res.in_values(domain.into_iter())?;
+            let state = res.in_values(domain)?;
             match state {
                 VariableState::NoChange => {}
                 state => {
@@ -79,10 +77,9 @@ where
                 }
             }
             let domain: Vec<_> = res.iter()
-                .cloned()
                 .map(|res| res - self.coef.clone())
                 .collect();
-            let state = var.in_values(domain.into_iter())?;
+            let state = var.in_values(domain)?;
             match state {
                 VariableState::NoChange => {}
                 state => {
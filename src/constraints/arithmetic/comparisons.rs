@@ -1,50 +1,46 @@
-use constraints::{Constraint, PropagationState};
+use constraints::{Constraint, PropagationState, Reifiable};
 use variables::domains::OrderedDomain;
 use variables::handlers::{
     VariableContainerHandler, VariableContainerView, VariablesHandler,
 };
-use variables::{Variable, VariableError, VariableId, VariableState};
+use variables::{VariableError, VariableId, VariableState};
 
 macro_rules! compare_constraint_impl {
-    ($name:ident; $method:ident) => {
+    ($name:ident; $method:ident; $negation:ident; $op:tt; $lhs_bound:ident; $rhs_bound:ident) => {
         #[derive(Debug, Clone)]
-        pub struct $name<Var, View>
+        pub struct $name<View>
         where
             View: VariableContainerView,
-            //View::Container: OrderedDomain<Type = Var>,
-            Var: Eq + Ord + Clone + 'static,
         {
             lhs: View,
             rhs: View,
             output: Option<Vec<(VariableId, VariableState)>>,
         }
 
-        impl<Var, View> $name<Var, View>
+        impl<View> $name<View>
         where
             View: VariableContainerView,
-            //View::Container: OrderedDomain<Type = Var>,
-            Var: Eq + Ord + Clone + 'static,
         {
-            pub fn new(lhs: View, rhs: View) -> $name<Var, View> {
+            pub fn new(lhs: View, rhs: View) -> $name<View> {
                 $name {
-                    lhs: lhs,
-                    rhs: rhs,
+                    lhs,
+                    rhs,
                     output: None,
                 }
             }
         }
 
-        impl<Var, Handler> Constraint<Handler> for $name<Var, Handler::View>
+        impl<Var, View, Handler> Constraint<Handler> for $name<View>
         where
-            Handler: VariablesHandler + VariableContainerHandler<Var> + Clone,
-            //View: VariableContainerView + 'static,
-            Var: OrderedDomain<Type = Var>,
-            //Var::Type: Eq + Ord + Clone + 'static,
+            Handler: VariablesHandler + VariableContainerHandler<Var, View = View> + Clone,
+            View: VariableContainerView<Container = Var> + 'static,
+            Var: OrderedDomain,
+            Var::Type: Ord + Eq,
         {
             fn box_clone(&self) -> Box<Constraint<Handler>> {
-                let ref_self: &$name<Var, Handler::View> = &self;
-                let cloned: $name<Var, Handler::View> =
-                    <$name<Var, Handler::View> as Clone>::clone(ref_self);
+                let ref_self: &$name<View> = &self;
+                let cloned: $name<View> =
+                    <$name<View> as Clone>::clone(ref_self);
 
                 Box::new(cloned) as Box<Constraint<Handler>>
             }
@@ -123,10 +119,207 @@ macro_rules! compare_constraint_impl {
                 self.propagate(variables_handler)
             }
         }
+
+        impl<Var, View, Handler> Reifiable<Handler> for $name<View>
+        where
+            Handler: VariablesHandler + VariableContainerHandler<Var, View = View> + Clone,
+            View: VariableContainerView<Container = Var> + 'static,
+            Var: OrderedDomain,
+            Var::Type: Ord + Eq,
+        {
+            fn box_clone_reifiable(&self) -> Box<Reifiable<Handler>> {
+                let ref_self: &$name<View> = &self;
+                let cloned: $name<View> =
+                    <$name<View> as Clone>::clone(ref_self);
+
+                Box::new(cloned) as Box<Reifiable<Handler>>
+            }
+            // The comparison holds for every values if it holds for the worst bounds,
+            // and for none of them if it does not hold for the best bounds.
+            fn entailment(&self, variables_handler: &Handler) -> Option<bool> {
+                let lhs = variables_handler.get(&self.lhs);
+                let rhs = variables_handler.get(&self.rhs);
+                if lhs.$lhs_bound() $op rhs.$rhs_bound() {
+                    Some(true)
+                } else if !(lhs.$rhs_bound() $op rhs.$lhs_bound()) {
+                    Some(false)
+                } else {
+                    None
+                }
+            }
+            fn propagate_negation(
+                &mut self,
+                variables_handler: &mut Handler,
+            ) -> Result<PropagationState, VariableError> {
+                let mut output = vec![];
+                self.output = None;
+                unsafe {
+                    let lhs: &mut Var =
+                        unsafe_from_raw_point!(variables_handler.get_mut(&self.lhs));
+                    let rhs: &mut Var =
+                        unsafe_from_raw_point!(variables_handler.get_mut(&self.rhs));
+                    let (lhs_state, rhs_state) = lhs.$negation(rhs)?;
+                    match lhs_state {
+                        VariableState::NoChange => {}
+                        state => {
+                            output.push((lhs.id(), state));
+                        }
+                    }
+                    match rhs_state {
+                        VariableState::NoChange => {}
+                        state => {
+                            output.push((rhs.id(), state));
+                        }
+                    }
+                }
+                if !output.is_empty() {
+                    self.output = Some(output);
+                    Ok(PropagationState::FixPoint)
+                } else {
+                    Ok(PropagationState::NoChange)
+                }
+            }
+        }
+    };
+}
+
+compare_constraint_impl!(LessThan; less_than; greater_or_equal_than; <; unchecked_max; unchecked_min);
+compare_constraint_impl!(LessOrEqualThan; less_or_equal_than; greater_than; <=; unchecked_max; unchecked_min);
+compare_constraint_impl!(GreaterThan; greater_than; less_or_equal_than; >; unchecked_min; unchecked_max);
+compare_constraint_impl!(GreaterOrEqualThan; greater_or_equal_than; less_than; >=; unchecked_min; unchecked_max);
+
+// Same as `compare_constraint_impl` with a constant on the right hand side.
+macro_rules! compare_constant_constraint_impl {
+    ($name:ident; $method:ident; $negation:ident; $op:tt; $worst_bound:ident; $best_bound:ident) => {
+        #[derive(Debug, Clone)]
+        pub struct $name<VarType, View>
+        where
+            View: VariableContainerView,
+            VarType: Clone,
+        {
+            var: View,
+            value: VarType,
+            output: Option<Vec<(VariableId, VariableState)>>,
+        }
+
+        impl<VarType, View> $name<VarType, View>
+        where
+            View: VariableContainerView,
+            VarType: Clone,
+        {
+            pub fn new(var: View, value: VarType) -> $name<VarType, View> {
+                $name {
+                    var,
+                    value,
+                    output: None,
+                }
+            }
+        }
+
+        impl<Var, VarType, View, Handler> Constraint<Handler> for $name<VarType, View>
+        where
+            Handler: VariablesHandler + VariableContainerHandler<Var, View = View> + Clone,
+            View: VariableContainerView<Container = Var> + 'static,
+            Var: OrderedDomain<Type = VarType>,
+            VarType: Ord + Eq + Clone + 'static,
+        {
+            fn box_clone(&self) -> Box<Constraint<Handler>> {
+                let ref_self: &$name<VarType, View> = &self;
+                let cloned: $name<VarType, View> =
+                    <$name<VarType, View> as Clone>::clone(ref_self);
+
+                Box::new(cloned) as Box<Constraint<Handler>>
+            }
+            fn propagate(
+                &mut self,
+                variables_handler: &mut Handler,
+            ) -> Result<PropagationState, VariableError> {
+                self.output = None;
+                let var = variables_handler.get_mut(&self.var);
+                match var.$method(self.value.clone())? {
+                    VariableState::NoChange => Ok(PropagationState::NoChange),
+                    state => {
+                        self.output = Some(vec![(var.id(), state)]);
+                        Ok(PropagationState::FixPoint)
+                    }
+                }
+            }
+            #[allow(unused)]
+            fn prepare(&mut self, states: Box<Iterator<Item = VariableId>>) {
+                // Do nothing
+            }
+            fn result(&mut self) -> Box<Iterator<Item = (VariableId, VariableState)>> {
+                use std::mem;
+                let mut res = None;
+                mem::swap(&mut self.output, &mut res);
+                match res {
+                    None => Box::new(vec![].into_iter()),
+                    Some(changes) => Box::new(changes.into_iter()),
+                }
+            }
+            fn dependencies(
+                &self,
+                variables_handler: &Handler,
+            ) -> Box<Iterator<Item = (VariableId, VariableState)>> {
+                Box::new(
+                    vec![(
+                        variables_handler.get(&self.var).id(),
+                        VariableState::ValuesChange,
+                    )].into_iter(),
+                )
+            }
+            #[allow(unused)]
+            fn initialise(
+                &mut self,
+                variables_handler: &mut Handler,
+            ) -> Result<PropagationState, VariableError> {
+                self.propagate(variables_handler)
+            }
+        }
+
+        impl<Var, VarType, View, Handler> Reifiable<Handler> for $name<VarType, View>
+        where
+            Handler: VariablesHandler + VariableContainerHandler<Var, View = View> + Clone,
+            View: VariableContainerView<Container = Var> + 'static,
+            Var: OrderedDomain<Type = VarType>,
+            VarType: Ord + Eq + Clone + 'static,
+        {
+            fn box_clone_reifiable(&self) -> Box<Reifiable<Handler>> {
+                let ref_self: &$name<VarType, View> = &self;
+                let cloned: $name<VarType, View> =
+                    <$name<VarType, View> as Clone>::clone(ref_self);
+
+                Box::new(cloned) as Box<Reifiable<Handler>>
+            }
+            fn entailment(&self, variables_handler: &Handler) -> Option<bool> {
+                let var = variables_handler.get(&self.var);
+                if var.$worst_bound() $op self.value {
+                    Some(true)
+                } else if !(var.$best_bound() $op self.value) {
+                    Some(false)
+                } else {
+                    None
+                }
+            }
+            fn propagate_negation(
+                &mut self,
+                variables_handler: &mut Handler,
+            ) -> Result<PropagationState, VariableError> {
+                self.output = None;
+                let var = variables_handler.get_mut(&self.var);
+                match var.$negation(self.value.clone())? {
+                    VariableState::NoChange => Ok(PropagationState::NoChange),
+                    state => {
+                        self.output = Some(vec![(var.id(), state)]);
+                        Ok(PropagationState::FixPoint)
+                    }
+                }
+            }
+        }
     };
 }
 
-compare_constraint_impl!(LessThan; less_than);
-compare_constraint_impl!(LessOrEqualThan; less_or_equal_than);
-compare_constraint_impl!(GreaterThan; greater_than);
-compare_constraint_impl!(GreaterOrEqualThan; greater_or_equal_than);
+compare_constant_constraint_impl!(LessThanConstant; strict_upperbound; weak_lowerbound; <; unchecked_max; unchecked_min);
+compare_constant_constraint_impl!(LessOrEqualThanConstant; weak_upperbound; strict_lowerbound; <=; unchecked_max; unchecked_min);
+compare_constant_constraint_impl!(GreaterThanConstant; strict_lowerbound; weak_upperbound; >; unchecked_min; unchecked_max);
+compare_constant_constraint_impl!(GreaterOrEqualThanConstant; weak_lowerbound; strict_upperbound; >=; unchecked_min; unchecked_max);
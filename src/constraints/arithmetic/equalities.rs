@@ -1,47 +1,45 @@
-use constraints::{Constraint, PropagationState};
-use variables::domains::{OrderedDomain, PrunableDomain};
+use constraints::{Constraint, PropagationState, Reifiable};
+use std::collections::BTreeSet;
+use variables::domains::{IterableDomain, OrderedDomain, PrunableDomain};
 use variables::handlers::{
     VariableContainerHandler, VariableContainerView, VariablesHandler,
 };
-use variables::{Variable, VariableError, VariableId, VariableState};
+use variables::{VariableError, VariableId, VariableState};
 
 // COMME ALLDIFF
 #[derive(Clone)]
-pub struct Equal<VarType, View>
+pub struct Equal<View>
 where
     View: VariableContainerView,
-    VarType: Eq + Ord + Clone + 'static,
 {
     lhs: View,
     rhs: View,
     output: Option<Vec<(VariableId, VariableState)>>,
 }
 
-impl<VarType, View> Equal<VarType, View>
+impl<View> Equal<View>
 where
     View: VariableContainerView,
-    VarType: Eq + Ord + Clone + 'static,
 {
-    pub fn new(lhs: View, rhs: View) -> Equal<VarType, View> {
+    pub fn new(lhs: View, rhs: View) -> Equal<View> {
         Equal {
-            lhs: lhs,
-            rhs: rhs,
+            lhs,
+            rhs,
             output: None,
         }
     }
 }
 
-impl<Var, VarType, View, Handler> Constraint<Handler> for Equal<VarType, View>
+impl<Var, View, Handler> Constraint<Handler> for Equal<View>
 where
-    Handler: VariablesHandler + VariableContainerHandler<View> + Clone,
-    View: VariableContainerView + 'static,
-    Var: PrunableDomain<Type = VarType>,
-    Var::Type: Eq + Ord + Clone + 'static,
+    Handler: VariablesHandler + VariableContainerHandler<Var, View = View> + Clone,
+    View: VariableContainerView<Container = Var> + 'static,
+    Var: PrunableDomain,
+    Var::Type: Eq,
 {
     fn box_clone(&self) -> Box<Constraint<Handler>> {
-        let ref_self: &Equal<VarType, View> = &self;
-        let cloned: Equal<VarType, View> =
-            <Equal<VarType, View> as Clone>::clone(ref_self);
+        let ref_self: &Equal<View> = self;
+        let cloned: Equal<View> = <Equal<View> as Clone>::clone(ref_self);
 
         Box::new(cloned) as Box<Constraint<Handler>>
     }
@@ -101,7 +99,8 @@ where
             vec![
                 (variables.get(&self.lhs).id(), VariableState::ValuesChange),
                 (variables.get(&self.rhs).id(), VariableState::ValuesChange),
-            ].into_iter(),
+            ]
+            .into_iter(),
         )
     }
     #[allow(unused)]
@@ -113,42 +112,103 @@ where
     }
 }
 
+impl<Var, View, Handler> Reifiable<Handler> for Equal<View>
+where
+    Handler: VariablesHandler + VariableContainerHandler<Var, View = View> + Clone,
+    View: VariableContainerView<Container = Var> + 'static,
+    Var: PrunableDomain + IterableDomain,
+    Var::Type: Eq + Ord,
+{
+    fn box_clone_reifiable(&self) -> Box<Reifiable<Handler>> {
+        let ref_self: &Equal<View> = self;
+        let cloned: Equal<View> = <Equal<View> as Clone>::clone(ref_self);
+
+        Box::new(cloned) as Box<Reifiable<Handler>>
+    }
+    fn entailment(&self, variables: &Handler) -> Option<bool> {
+        let lhs = variables.get(&self.lhs);
+        let rhs = variables.get(&self.rhs);
+        match (lhs.value(), rhs.value()) {
+            (Some(lhs), Some(rhs)) => Some(lhs == rhs),
+            _ => {
+                let values: BTreeSet<_> = lhs.iter().collect();
+                if rhs.iter().any(|value| values.contains(&value)) {
+                    None
+                } else {
+                    Some(false)
+                }
+            }
+        }
+    }
+    fn propagate_negation(
+        &mut self,
+        variables_handler: &mut Handler,
+    ) -> Result<PropagationState, VariableError> {
+        let mut output = vec![];
+        self.output = None;
+
+        unsafe {
+            let lhs: &mut Var =
+                unsafe_from_raw_point!(variables_handler.get_mut(&self.lhs));
+            let rhs: &mut Var =
+                unsafe_from_raw_point!(variables_handler.get_mut(&self.rhs));
+            let (lhs_state, rhs_state) = lhs.not_equal(rhs)?;
+            match lhs_state {
+                VariableState::NoChange => {}
+                state => {
+                    output.push((lhs.id(), state));
+                }
+            }
+            match rhs_state {
+                VariableState::NoChange => {}
+                state => {
+                    output.push((rhs.id(), state));
+                }
+            }
+        }
+
+        if !output.is_empty() {
+            self.output = Some(output);
+            Ok(PropagationState::FixPoint)
+        } else {
+            Ok(PropagationState::NoChange)
+        }
+    }
+}
+
 #[derive(Clone)]
-pub struct EqualBounds<VarType, View>
+pub struct EqualBounds<View>
 where
     View: VariableContainerView,
-    VarType: Eq + Ord + Clone + 'static,
 {
     lhs: View,
     rhs: View,
     output: Option<Vec<(VariableId, VariableState)>>,
 }
 
-impl<VarType, View> EqualBounds<VarType, View>
+impl<View> EqualBounds<View>
 where
     View: VariableContainerView,
-    VarType: Eq + Ord + Clone + 'static,
 {
-    pub fn new(lhs: View, rhs: View) -> EqualBounds<VarType, View> {
+    pub fn new(lhs: View, rhs: View) -> EqualBounds<View> {
         EqualBounds {
-            lhs: lhs,
-            rhs: rhs,
+            lhs,
+            rhs,
             output: None,
         }
     }
 }
 
-impl<Var, VarType, View, Handler> Constraint<Handler> for EqualBounds<VarType, View>
+impl<Var, View, Handler> Constraint<Handler> for EqualBounds<View>
 where
-    Handler: VariablesHandler + VariableContainerHandler<View> + Clone,
-    View: VariableContainerView + 'static,
-    Var: OrderedDomain<Type = VarType>,
-    VarType: Eq + Ord + Clone + 'static,
+    Handler: VariablesHandler + VariableContainerHandler<Var, View = View> + Clone,
+    View: VariableContainerView<Container = Var> + 'static,
+    Var: OrderedDomain,
+    Var::Type: Eq + Ord,
 {
     fn box_clone(&self) -> Box<Constraint<Handler>> {
-        let ref_self: &EqualBounds<VarType, View> = &self;
-        let cloned: EqualBounds<VarType, View> =
-            <EqualBounds<VarType, View> as Clone>::clone(ref_self);
+        let ref_self: &EqualBounds<View> = self;
+        let cloned: EqualBounds<View> = <EqualBounds<View> as Clone>::clone(ref_self);
 
         Box::new(cloned) as Box<Constraint<Handler>>
     }
@@ -221,7 +281,8 @@ where
             vec![
                 (variables.get(&self.lhs).id(), VariableState::ValuesChange),
                 (variables.get(&self.rhs).id(), VariableState::ValuesChange),
-            ].into_iter(),
+            ]
+            .into_iter(),
         )
     }
     #[allow(unused)]
@@ -232,3 +293,212 @@ where
         self.propagate(variables_handler)
     }
 }
+
+// Removes `value` from the domain of `var` if it is one of its bounds.
+fn exclude_bound<Var>(
+    var: &mut Var,
+    value: Var::Type,
+) -> Result<VariableState, VariableError>
+where
+    Var: OrderedDomain,
+    Var::Type: Eq + Ord,
+{
+    if var.unchecked_min() == value {
+        var.strict_lowerbound(value)
+    } else if var.unchecked_max() == value {
+        var.strict_upperbound(value)
+    } else {
+        Ok(VariableState::NoChange)
+    }
+}
+
+impl<Var, View, Handler> Reifiable<Handler> for EqualBounds<View>
+where
+    Handler: VariablesHandler + VariableContainerHandler<Var, View = View> + Clone,
+    View: VariableContainerView<Container = Var> + 'static,
+    Var: OrderedDomain,
+    Var::Type: Eq + Ord,
+{
+    fn box_clone_reifiable(&self) -> Box<Reifiable<Handler>> {
+        let ref_self: &EqualBounds<View> = self;
+        let cloned: EqualBounds<View> = <EqualBounds<View> as Clone>::clone(ref_self);
+
+        Box::new(cloned) as Box<Reifiable<Handler>>
+    }
+    fn entailment(&self, variables: &Handler) -> Option<bool> {
+        let lhs = variables.get(&self.lhs);
+        let rhs = variables.get(&self.rhs);
+        if lhs.unchecked_max() < rhs.unchecked_min()
+            || rhs.unchecked_max() < lhs.unchecked_min()
+        {
+            Some(false)
+        } else if lhs.unchecked_min() == rhs.unchecked_max()
+            && lhs.unchecked_max() == rhs.unchecked_min()
+        {
+            Some(true)
+        } else {
+            None
+        }
+    }
+    // Only the bounds are filtered, so the negation prunes a variable when the other
+    // one is assigned to one of its bounds.
+    fn propagate_negation(
+        &mut self,
+        variables_handler: &mut Handler,
+    ) -> Result<PropagationState, VariableError> {
+        let mut output = vec![];
+        self.output = None;
+        unsafe {
+            let lhs: &mut Var =
+                unsafe_from_raw_point!(variables_handler.get_mut(&self.lhs));
+            let rhs: &mut Var =
+                unsafe_from_raw_point!(variables_handler.get_mut(&self.rhs));
+
+            if let Some(value) = lhs.value() {
+                let state = exclude_bound(rhs, value)?;
+                match state {
+                    VariableState::NoChange => {}
+                    state => {
+                        output.push((rhs.id(), state));
+                    }
+                }
+            }
+            if let Some(value) = rhs.value() {
+                let state = exclude_bound(lhs, value)?;
+                match state {
+                    VariableState::NoChange => {}
+                    state => {
+                        output.push((lhs.id(), state));
+                    }
+                }
+            }
+        }
+        if !output.is_empty() {
+            self.output = Some(output);
+            Ok(PropagationState::FixPoint)
+        } else {
+            Ok(PropagationState::NoChange)
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct EqualConstant<VarType, View>
+where
+    View: VariableContainerView,
+    VarType: Clone,
+{
+    var: View,
+    value: VarType,
+    output: Option<Vec<(VariableId, VariableState)>>,
+}
+
+impl<VarType, View> EqualConstant<VarType, View>
+where
+    View: VariableContainerView,
+    VarType: Clone,
+{
+    pub fn new(var: View, value: VarType) -> EqualConstant<VarType, View> {
+        EqualConstant {
+            var,
+            value,
+            output: None,
+        }
+    }
+}
+
+impl<Var, VarType, View, Handler> Constraint<Handler> for EqualConstant<VarType, View>
+where
+    Handler: VariablesHandler + VariableContainerHandler<Var, View = View> + Clone,
+    View: VariableContainerView<Container = Var> + 'static,
+    Var: PrunableDomain<Type = VarType>,
+    VarType: Eq + Clone + 'static,
+{
+    fn box_clone(&self) -> Box<Constraint<Handler>> {
+        let ref_self: &EqualConstant<VarType, View> = self;
+        let cloned: EqualConstant<VarType, View> =
+            <EqualConstant<VarType, View> as Clone>::clone(ref_self);
+
+        Box::new(cloned) as Box<Constraint<Handler>>
+    }
+    fn propagate(
+        &mut self,
+        variables_handler: &mut Handler,
+    ) -> Result<PropagationState, VariableError> {
+        self.output = None;
+        let var = variables_handler.get_mut(&self.var);
+        match var.in_values(Some(self.value.clone()))? {
+            VariableState::NoChange => Ok(PropagationState::NoChange),
+            state => {
+                self.output = Some(vec![(var.id(), state)]);
+                Ok(PropagationState::FixPoint)
+            }
+        }
+    }
+    #[allow(unused)]
+    fn prepare(&mut self, states: Box<Iterator<Item = VariableId>>) {
+        // Do nothing
+    }
+    fn result(&mut self) -> Box<Iterator<Item = (VariableId, VariableState)>> {
+        use std::mem;
+        let mut res = None;
+        mem::swap(&mut self.output, &mut res);
+        match res {
+            None => Box::new(vec![].into_iter()),
+            Some(changes) => Box::new(changes.into_iter()),
+        }
+    }
+    fn dependencies(
+        &self,
+        variables: &Handler,
+    ) -> Box<Iterator<Item = (VariableId, VariableState)>> {
+        Box::new(
+            vec![(variables.get(&self.var).id(), VariableState::ValuesChange)]
+                .into_iter(),
+        )
+    }
+    fn initialise(
+        &mut self,
+        variables_handler: &mut Handler,
+    ) -> Result<PropagationState, VariableError> {
+        self.propagate(variables_handler)
+    }
+}
+
+impl<Var, VarType, View, Handler> Reifiable<Handler> for EqualConstant<VarType, View>
+where
+    Handler: VariablesHandler + VariableContainerHandler<Var, View = View> + Clone,
+    View: VariableContainerView<Container = Var> + 'static,
+    Var: PrunableDomain<Type = VarType> + IterableDomain,
+    VarType: Eq + Clone + 'static,
+{
+    fn box_clone_reifiable(&self) -> Box<Reifiable<Handler>> {
+        let ref_self: &EqualConstant<VarType, View> = self;
+        let cloned: EqualConstant<VarType, View> =
+            <EqualConstant<VarType, View> as Clone>::clone(ref_self);
+
+        Box::new(cloned) as Box<Reifiable<Handler>>
+    }
+    fn entailment(&self, variables: &Handler) -> Option<bool> {
+        let var = variables.get(&self.var);
+        match var.value() {
+            Some(value) => Some(value == self.value),
+            None if var.iter().any(|value| value == self.value) => None,
+            None => Some(false),
+        }
+    }
+    fn propagate_negation(
+        &mut self,
+        variables_handler: &mut Handler,
+    ) -> Result<PropagationState, VariableError> {
+        self.output = None;
+        let var = variables_handler.get_mut(&self.var);
+        match var.remove_value(self.value.clone())? {
+            VariableState::NoChange => Ok(PropagationState::NoChange),
+            state => {
+                self.output = Some(vec![(var.id(), state)]);
+                Ok(PropagationState::FixPoint)
+            }
+        }
+    }
+}
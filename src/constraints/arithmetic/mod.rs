@@ -1,12 +1,28 @@
 mod comparisons;
 pub use self::comparisons::GreaterOrEqualThan;
+pub use self::comparisons::GreaterOrEqualThanConstant;
 pub use self::comparisons::GreaterThan;
+pub use self::comparisons::GreaterThanConstant;
 pub use self::comparisons::LessOrEqualThan;
+pub use self::comparisons::LessOrEqualThanConstant;
 pub use self::comparisons::LessThan;
+pub use self::comparisons::LessThanConstant;
 
 mod equalities;
 pub use self::equalities::Equal;
 pub use self::equalities::EqualBounds;
+pub use self::equalities::EqualConstant;
 
 mod binary_ops;
 pub use self::binary_ops::AddConstant;
+
+mod nonlinear;
+pub use self::nonlinear::Abs;
+pub use self::nonlinear::ArrayMax;
+pub use self::nonlinear::ArrayMin;
+pub use self::nonlinear::Div;
+pub use self::nonlinear::Max;
+pub use self::nonlinear::Min;
+pub use self::nonlinear::Mod;
+pub use self::nonlinear::Power;
+pub use self::nonlinear::Times;
@@ -0,0 +1,1083 @@
+use constraints::linear::{ceil_div, floor_div};
+use constraints::{to_i32, Constraint, PropagationState};
+use std::cmp;
+use std::collections::{BTreeMap, BTreeSet};
+use variables::domains::{IterableDomain, OrderedDomain, PrunableDomain};
+use variables::handlers::{
+    VariableContainerHandler, VariableContainerView, VariablesHandler,
+};
+use variables::{Array, Variable, VariableError, VariableId, VariableState};
+
+// Above this number of pairs of values the constraints only filter the bounds.
+const SUPPORTS_LIMIT: usize = 4096;
+
+fn update<Var>(
+    var: &Var,
+    state: VariableState,
+    output: &mut Vec<(VariableId, VariableState)>,
+) -> bool
+where
+    Var: Variable,
+{
+    match state {
+        VariableState::NoChange => false,
+        state => {
+            output.push((var.id(), state));
+            true
+        }
+    }
+}
+
+fn bounds_of<Var>(var: &Var) -> (i64, i64)
+where
+    Var: OrderedDomain<Type = i32>,
+{
+    (
+        i64::from(var.unchecked_min()),
+        i64::from(var.unchecked_max()),
+    )
+}
+
+fn hull<Values>(values: Values) -> (i64, i64)
+where
+    Values: IntoIterator<Item = i64>,
+{
+    values
+        .into_iter()
+        .fold((i64::MAX, i64::MIN), |(min, max), value| {
+            (cmp::min(min, value), cmp::max(max, value))
+        })
+}
+
+// Restricts the domain of `var` to `[min, max]`.
+fn restrict<Var>(
+    var: &mut Var,
+    min: i64,
+    max: i64,
+    output: &mut Vec<(VariableId, VariableState)>,
+) -> Result<bool, VariableError>
+where
+    Var: OrderedDomain<Type = i32>,
+{
+    if min > max {
+        return Err(VariableError::DomainWipeout);
+    }
+    let state = var.weak_lowerbound(to_i32(min))?;
+    let lower = update(var, state, output);
+    let state = var.weak_upperbound(to_i32(max))?;
+    let upper = update(var, state, output);
+    Ok(lower || upper)
+}
+
+// Removes `0` from the domain of a divisor.
+fn remove_zero<Var>(
+    var: &mut Var,
+    output: &mut Vec<(VariableId, VariableState)>,
+) -> Result<bool, VariableError>
+where
+    Var: PrunableDomain<Type = i32>,
+{
+    let state = var.remove_value(0)?;
+    Ok(update(var, state, output))
+}
+
+// Splits the bounds of a divisor into its negative and its positive parts.
+fn nonzero_parts(bounds: (i64, i64)) -> Vec<(i64, i64)> {
+    let (min, max) = bounds;
+    let mut parts = vec![];
+    if min <= -1 {
+        parts.push((min, cmp::min(max, -1)));
+    }
+    if max >= 1 {
+        parts.push((cmp::max(min, 1), max));
+    }
+    parts
+}
+
+// Returns the bounds of the `x` such that `x * y = z` for some `y` and `z` in the
+// bounds, or `None` if any `x` is possible.
+fn quotient_bounds(z: (i64, i64), y: (i64, i64)) -> Option<(i64, i64)> {
+    if (y.0 <= 0 && 0 <= y.1) && (z.0 <= 0 && 0 <= z.1) {
+        return None;
+    }
+    let parts = nonzero_parts(y);
+    if parts.is_empty() {
+        return None;
+    }
+    // The quotient is monotone on each part, so its extrema are at the corners.
+    let min = parts
+        .iter()
+        .flat_map(|&(lhs, rhs)| vec![(z.0, lhs), (z.0, rhs), (z.1, lhs), (z.1, rhs)])
+        .map(|(num, den)| ceil_div(num, den))
+        .min()
+        .unwrap();
+    let max = parts
+        .iter()
+        .flat_map(|&(lhs, rhs)| vec![(z.0, lhs), (z.0, rhs), (z.1, lhs), (z.1, rhs)])
+        .map(|(num, den)| floor_div(num, den))
+        .max()
+        .unwrap();
+    Some((min, max))
+}
+
+// Returns `base` to the power `exp`, or `None` on overflow or for a negative `exp`.
+fn power(base: i64, exp: i64) -> Option<i64> {
+    if exp < 0 {
+        return None;
+    }
+    let mut result: i64 = 1;
+    let mut base = base;
+    let mut exp = exp;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result.checked_mul(base)?;
+        }
+        exp >>= 1;
+        if exp > 0 {
+            base = base.checked_mul(base)?;
+        }
+    }
+    Some(result)
+}
+
+fn saturating_power(base: i64, exp: i64) -> i64 {
+    match power(base, exp) {
+        Some(result) => result,
+        None if base < 0 && exp % 2 == 1 => i64::MIN,
+        None => i64::MAX,
+    }
+}
+
+// Keeps the values of `x`, `y` and `z` which belong to a triple with
+// `function(x, y) = z`. The filtering is domain consistent, also when the same
+// variable is passed for several arguments.
+fn filter_supports<Var, Function>(
+    x: &mut Var,
+    y: &mut Var,
+    z: &mut Var,
+    function: Function,
+    output: &mut Vec<(VariableId, VariableState)>,
+) -> Result<(), VariableError>
+where
+    Var: PrunableDomain<Type = i32> + IterableDomain,
+    Function: Fn(i64, i64) -> Option<i64>,
+{
+    let (x_is_y, z_is_x, z_is_y) = (x.id() == y.id(), z.id() == x.id(), z.id() == y.id());
+    let ys: Vec<i64> = y.iter().map(i64::from).collect();
+    // The domain of `z` can be much larger than the number of pairs, so the pairs are
+    // indexed by their result instead.
+    let mut pairs: BTreeMap<i32, Vec<(i32, i32)>> = BTreeMap::new();
+    for lhs in x.iter().map(i64::from) {
+        for &rhs in ys.iter().filter(|&&rhs| !x_is_y || rhs == lhs) {
+            match function(lhs, rhs) {
+                Some(res)
+                    if (!z_is_x || res == lhs)
+                        && (!z_is_y || res == rhs)
+                        && i64::from(to_i32(res)) == res =>
+                {
+                    pairs
+                        .entry(res as i32)
+                        .or_default()
+                        .push((lhs as i32, rhs as i32));
+                }
+                _ => {}
+            }
+        }
+    }
+    if pairs.is_empty() {
+        return Err(VariableError::DomainWipeout);
+    }
+    let state = z.in_values(pairs.keys().cloned())?;
+    update(z, state, output);
+    let mut x_supports = BTreeSet::new();
+    let mut y_supports = BTreeSet::new();
+    for res in z.iter() {
+        for &(lhs, rhs) in pairs[&res].iter() {
+            x_supports.insert(lhs);
+            y_supports.insert(rhs);
+        }
+    }
+    let state = x.in_values(x_supports)?;
+    update(x, state, output);
+    let state = y.in_values(y_supports)?;
+    update(y, state, output);
+    Ok(())
+}
+
+// Filters `z = function(x, y)` with the supports if the domains are small enough
+// and with the bounds until the fixpoint otherwise.
+fn filter_function<Var, Function, Bounds>(
+    x: &mut Var,
+    y: &mut Var,
+    z: &mut Var,
+    function: Function,
+    bounds: Bounds,
+    output: &mut Vec<(VariableId, VariableState)>,
+) -> Result<(), VariableError>
+where
+    Var: PrunableDomain<Type = i32> + OrderedDomain + IterableDomain,
+    Function: Fn(i64, i64) -> Option<i64>,
+    Bounds: Fn(
+        &mut Var,
+        &mut Var,
+        &mut Var,
+        &mut Vec<(VariableId, VariableState)>,
+    ) -> Result<bool, VariableError>,
+{
+    if x.size().saturating_mul(y.size()) <= SUPPORTS_LIMIT {
+        filter_supports(x, y, z, function, output)
+    } else {
+        while bounds(x, y, z, output)? {}
+        Ok(())
+    }
+}
+
+// Returns the largest integer whose square is at most `value`.
+fn floor_sqrt(value: i64) -> i64 {
+    if value < 0 {
+        return -1;
+    }
+    let mut root = (value as f64).sqrt() as i64;
+    while root * root > value {
+        root -= 1;
+    }
+    while (root + 1) * (root + 1) <= value {
+        root += 1;
+    }
+    root
+}
+
+// Filters the bounds of `z = x * x`. The product of the bounds of `x` would allow
+// negative values and is not tight when `x` contains `0`.
+fn square_bounds<Var>(
+    x: &mut Var,
+    z: &mut Var,
+    output: &mut Vec<(VariableId, VariableState)>,
+) -> Result<bool, VariableError>
+where
+    Var: PrunableDomain<Type = i32> + OrderedDomain,
+{
+    let xb = bounds_of(x);
+    let (min, max) = if xb.0 >= 0 {
+        (xb.0 * xb.0, xb.1 * xb.1)
+    } else if xb.1 <= 0 {
+        (xb.1 * xb.1, xb.0 * xb.0)
+    } else {
+        (0, cmp::max(xb.0 * xb.0, xb.1 * xb.1))
+    };
+    let mut changed = restrict(z, min, max, output)?;
+    let zb = bounds_of(z);
+    let root = floor_sqrt(zb.1);
+    changed |= restrict(x, -root, root, output)?;
+    // The values strictly between the opposite of the smallest root and the smallest
+    // root are removed when they are at a bound of `x`.
+    let root = floor_sqrt(zb.0 - 1) + 1;
+    let xb = bounds_of(x);
+    if xb.0 > -root {
+        changed |= restrict(x, root, xb.1, output)?;
+    } else if xb.1 < root {
+        changed |= restrict(x, xb.0, -root, output)?;
+    }
+    Ok(changed)
+}
+
+fn times_bounds<Var>(
+    x: &mut Var,
+    y: &mut Var,
+    z: &mut Var,
+    output: &mut Vec<(VariableId, VariableState)>,
+) -> Result<bool, VariableError>
+where
+    Var: PrunableDomain<Type = i32> + OrderedDomain,
+{
+    if x.id() == y.id() {
+        return square_bounds(x, z, output);
+    }
+    let (xb, yb) = (bounds_of(x), bounds_of(y));
+    let (min, max) = hull(vec![xb.0 * yb.0, xb.0 * yb.1, xb.1 * yb.0, xb.1 * yb.1]);
+    let mut changed = restrict(z, min, max, output)?;
+    let zb = bounds_of(z);
+    if let Some((min, max)) = quotient_bounds(zb, bounds_of(y)) {
+        changed |= restrict(x, min, max, output)?;
+    }
+    if let Some((min, max)) = quotient_bounds(zb, bounds_of(x)) {
+        changed |= restrict(y, min, max, output)?;
+    }
+    Ok(changed)
+}
+
+fn div_bounds<Var>(
+    x: &mut Var,
+    y: &mut Var,
+    z: &mut Var,
+    output: &mut Vec<(VariableId, VariableState)>,
+) -> Result<bool, VariableError>
+where
+    Var: PrunableDomain<Type = i32> + OrderedDomain,
+{
+    let mut changed = remove_zero(y, output)?;
+    let (xb, yb) = (bounds_of(x), bounds_of(y));
+    // The truncated division is monotone on each part of the divisor.
+    let (min, max) = hull(
+        nonzero_parts(yb)
+            .into_iter()
+            .flat_map(|(lhs, rhs)| vec![xb.0 / lhs, xb.0 / rhs, xb.1 / lhs, xb.1 / rhs]),
+    );
+    changed |= restrict(z, min, max, output)?;
+    let zb = bounds_of(z);
+    let quotient = cmp::max(zb.0.abs(), zb.1.abs()) + 1;
+    let divisor = cmp::max(yb.0.abs(), yb.1.abs());
+    let max = quotient * divisor - 1;
+    changed |= restrict(x, -max, max, output)?;
+    Ok(changed)
+}
+
+fn mod_bounds<Var>(
+    x: &mut Var,
+    y: &mut Var,
+    z: &mut Var,
+    output: &mut Vec<(VariableId, VariableState)>,
+) -> Result<bool, VariableError>
+where
+    Var: PrunableDomain<Type = i32> + OrderedDomain,
+{
+    let mut changed = remove_zero(y, output)?;
+    let (xb, yb) = (bounds_of(x), bounds_of(y));
+    // The remainder has the sign of `x` and is smaller than `y` in absolute value.
+    let max = cmp::min(
+        cmp::max(yb.0.abs(), yb.1.abs()) - 1,
+        cmp::max(xb.0.abs(), xb.1.abs()),
+    );
+    let min = if xb.0 >= 0 { 0 } else { -max };
+    let max = if xb.1 <= 0 { 0 } else { max };
+    changed |= restrict(z, min, max, output)?;
+    let zb = bounds_of(z);
+    let min = if zb.0 > 0 { zb.0 } else { xb.0 };
+    let max = if zb.1 < 0 { zb.1 } else { xb.1 };
+    changed |= restrict(x, min, max, output)?;
+    Ok(changed)
+}
+
+fn min_bounds<Var>(
+    x: &mut Var,
+    y: &mut Var,
+    z: &mut Var,
+    output: &mut Vec<(VariableId, VariableState)>,
+) -> Result<bool, VariableError>
+where
+    Var: PrunableDomain<Type = i32> + OrderedDomain,
+{
+    let (xb, yb) = (bounds_of(x), bounds_of(y));
+    let mut changed = restrict(z, cmp::min(xb.0, yb.0), cmp::min(xb.1, yb.1), output)?;
+    let zb = bounds_of(z);
+    // The variable which can not be the minimum is above it.
+    let x_max = if yb.0 > zb.1 { zb.1 } else { xb.1 };
+    let y_max = if xb.0 > zb.1 { zb.1 } else { yb.1 };
+    changed |= restrict(x, zb.0, x_max, output)?;
+    changed |= restrict(y, zb.0, y_max, output)?;
+    Ok(changed)
+}
+
+fn max_bounds<Var>(
+    x: &mut Var,
+    y: &mut Var,
+    z: &mut Var,
+    output: &mut Vec<(VariableId, VariableState)>,
+) -> Result<bool, VariableError>
+where
+    Var: PrunableDomain<Type = i32> + OrderedDomain,
+{
+    let (xb, yb) = (bounds_of(x), bounds_of(y));
+    let mut changed = restrict(z, cmp::max(xb.0, yb.0), cmp::max(xb.1, yb.1), output)?;
+    let zb = bounds_of(z);
+    // The variable which can not be the maximum is below it.
+    let x_min = if yb.1 < zb.0 { zb.0 } else { xb.0 };
+    let y_min = if xb.1 < zb.0 { zb.0 } else { yb.0 };
+    changed |= restrict(x, x_min, zb.1, output)?;
+    changed |= restrict(y, y_min, zb.1, output)?;
+    Ok(changed)
+}
+
+fn power_bounds<Var>(
+    x: &mut Var,
+    y: &mut Var,
+    z: &mut Var,
+    output: &mut Vec<(VariableId, VariableState)>,
+) -> Result<bool, VariableError>
+where
+    Var: PrunableDomain<Type = i32> + OrderedDomain,
+{
+    let yb = bounds_of(y);
+    let mut changed = restrict(y, 0, yb.1, output)?;
+    let (xb, yb) = (bounds_of(x), bounds_of(y));
+    // For a given exponent the extrema are at the bounds of `x` or at `0`, and for a
+    // given base they are at the largest or smallest exponents of each parity.
+    let bases: Vec<_> = vec![xb.0, xb.1, 0]
+        .into_iter()
+        .filter(|&base| xb.0 <= base && base <= xb.1)
+        .collect();
+    let exps: Vec<_> = vec![yb.0, yb.0 + 1, yb.1 - 1, yb.1]
+        .into_iter()
+        .filter(|&exp| yb.0 <= exp && exp <= yb.1)
+        .collect();
+    let (min, max) = hull(
+        bases
+            .iter()
+            .flat_map(|&base| exps.iter().map(move |&exp| saturating_power(base, exp))),
+    );
+    changed |= restrict(z, min, max, output)?;
+    Ok(changed)
+}
+
+macro_rules! function_constraint_impl {
+    ($(#[$attr:meta])* $name:ident; $function:expr; $bounds:ident) => {
+        $(#[$attr])*
+        #[derive(Clone)]
+        pub struct $name<View>
+        where
+            View: VariableContainerView,
+        {
+            x: View,
+            y: View,
+            z: View,
+            output: Option<Vec<(VariableId, VariableState)>>,
+        }
+
+        impl<View> $name<View>
+        where
+            View: VariableContainerView,
+        {
+            pub fn new(x: View, y: View, z: View) -> $name<View> {
+                $name {
+                    x,
+                    y,
+                    z,
+                    output: None,
+                }
+            }
+        }
+
+        impl<Var, View, Handler> Constraint<Handler> for $name<View>
+        where
+            Handler: VariablesHandler + VariableContainerHandler<Var, View = View> + Clone,
+            View: VariableContainerView<Container = Var> + 'static,
+            Var: PrunableDomain<Type = i32> + OrderedDomain + IterableDomain,
+        {
+            fn box_clone(&self) -> Box<Constraint<Handler>> {
+                let ref_self: &$name<View> = &self;
+                let cloned: $name<View> = <$name<View> as Clone>::clone(ref_self);
+
+                Box::new(cloned) as Box<Constraint<Handler>>
+            }
+            fn propagate(
+                &mut self,
+                variables_handler: &mut Handler,
+            ) -> Result<PropagationState, VariableError> {
+                let mut output = vec![];
+                self.output = None;
+
+                unsafe {
+                    let x: &mut Var =
+                        unsafe_from_raw_point!(variables_handler.get_mut(&self.x));
+                    let y: &mut Var =
+                        unsafe_from_raw_point!(variables_handler.get_mut(&self.y));
+                    let z: &mut Var =
+                        unsafe_from_raw_point!(variables_handler.get_mut(&self.z));
+                    filter_function(x, y, z, $function, $bounds, &mut output)?;
+                }
+
+                if !output.is_empty() {
+                    self.output = Some(output);
+                    Ok(PropagationState::FixPoint)
+                } else {
+                    Ok(PropagationState::NoChange)
+                }
+            }
+            #[allow(unused)]
+            fn prepare(&mut self, states: Box<Iterator<Item = VariableId>>) {
+                // Do nothing
+            }
+            fn result(&mut self) -> Box<Iterator<Item = (VariableId, VariableState)>> {
+                use std::mem;
+                let mut res = None;
+                mem::swap(&mut self.output, &mut res);
+                match res {
+                    None => Box::new(vec![].into_iter()),
+                    Some(changes) => Box::new(changes.into_iter()),
+                }
+            }
+            fn dependencies(
+                &self,
+                variables_handler: &Handler,
+            ) -> Box<Iterator<Item = (VariableId, VariableState)>> {
+                Box::new(
+                    vec![
+                        (
+                            variables_handler.get(&self.x).id(),
+                            VariableState::ValuesChange,
+                        ),
+                        (
+                            variables_handler.get(&self.y).id(),
+                            VariableState::ValuesChange,
+                        ),
+                        (
+                            variables_handler.get(&self.z).id(),
+                            VariableState::ValuesChange,
+                        ),
+                    ]
+                    .into_iter(),
+                )
+            }
+            fn initialise(
+                &mut self,
+                variables_handler: &mut Handler,
+            ) -> Result<PropagationState, VariableError> {
+                self.propagate(variables_handler)
+            }
+        }
+    };
+}
+
+function_constraint_impl!(
+    /// Enforces `z = x * y`. The same variable can be passed for `x` and `y` to
+    /// enforce `z = x * x`.
+    Times; |x, y| Some(x * y); times_bounds
+);
+function_constraint_impl!(
+    /// Enforces `z = x / y` where the division is rounded toward zero.
+    Div; |x, y| if y != 0 { Some(x / y) } else { None }; div_bounds
+);
+function_constraint_impl!(
+    /// Enforces `z = x % y` where the remainder has the sign of `x`.
+    Mod; |x, y| if y != 0 { Some(x % y) } else { None }; mod_bounds
+);
+function_constraint_impl!(
+    /// Enforces `z = min(x, y)`.
+    Min; |x, y| Some(cmp::min(x, y)); min_bounds
+);
+function_constraint_impl!(
+    /// Enforces `z = max(x, y)`.
+    Max; |x, y| Some(cmp::max(x, y)); max_bounds
+);
+function_constraint_impl!(
+    /// Enforces `z = x ^ y` where `y` is not negative.
+    Power; power; power_bounds
+);
+
+// Filters `y = |x|`, with the domains if they are small enough and with the bounds
+// otherwise.
+fn filter_abs<Var>(
+    x: &mut Var,
+    y: &mut Var,
+    output: &mut Vec<(VariableId, VariableState)>,
+) -> Result<(), VariableError>
+where
+    Var: PrunableDomain<Type = i32> + OrderedDomain + IterableDomain,
+{
+    if x.size() <= SUPPORTS_LIMIT && y.size() <= SUPPORTS_LIMIT {
+        let xs: BTreeSet<i64> = x.iter().map(i64::from).collect();
+        let state = y.retains_if(|&value| {
+            let value = i64::from(value);
+            value >= 0 && (xs.contains(&value) || xs.contains(&-value))
+        })?;
+        update(y, state, output);
+        let ys: BTreeSet<i64> = y.iter().map(i64::from).collect();
+        let state = x.retains_if(|&value| ys.contains(&i64::from(value).abs()))?;
+        update(x, state, output);
+    } else {
+        loop {
+            let xb = bounds_of(x);
+            let (min, max) = if xb.0 >= 0 {
+                xb
+            } else if xb.1 <= 0 {
+                (-xb.1, -xb.0)
+            } else {
+                (0, cmp::max(-xb.0, xb.1))
+            };
+            let mut changed = restrict(y, min, max, output)?;
+            let yb = bounds_of(y);
+            changed |= restrict(x, -yb.1, yb.1, output)?;
+            if !changed {
+                break;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Enforces `y = |x|`.
+#[derive(Clone)]
+pub struct Abs<View>
+where
+    View: VariableContainerView,
+{
+    x: View,
+    y: View,
+    output: Option<Vec<(VariableId, VariableState)>>,
+}
+
+impl<View> Abs<View>
+where
+    View: VariableContainerView,
+{
+    pub fn new(x: View, y: View) -> Abs<View> {
+        Abs {
+            x,
+            y,
+            output: None,
+        }
+    }
+}
+
+impl<Var, View, Handler> Constraint<Handler> for Abs<View>
+where
+    Handler: VariablesHandler + VariableContainerHandler<Var, View = View> + Clone,
+    View: VariableContainerView<Container = Var> + 'static,
+    Var: PrunableDomain<Type = i32> + OrderedDomain + IterableDomain,
+{
+    fn box_clone(&self) -> Box<Constraint<Handler>> {
+        let ref_self: &Abs<View> = self;
+        let cloned: Abs<View> = <Abs<View> as Clone>::clone(ref_self);
+
+        Box::new(cloned) as Box<Constraint<Handler>>
+    }
+    fn propagate(
+        &mut self,
+        variables_handler: &mut Handler,
+    ) -> Result<PropagationState, VariableError> {
+        let mut output = vec![];
+        self.output = None;
+
+        unsafe {
+            let x: &mut Var = unsafe_from_raw_point!(variables_handler.get_mut(&self.x));
+            let y: &mut Var = unsafe_from_raw_point!(variables_handler.get_mut(&self.y));
+            filter_abs(x, y, &mut output)?;
+        }
+
+        if !output.is_empty() {
+            self.output = Some(output);
+            Ok(PropagationState::FixPoint)
+        } else {
+            Ok(PropagationState::NoChange)
+        }
+    }
+    #[allow(unused)]
+    fn prepare(&mut self, states: Box<Iterator<Item = VariableId>>) {
+        // Do nothing
+    }
+    fn result(&mut self) -> Box<Iterator<Item = (VariableId, VariableState)>> {
+        use std::mem;
+        let mut res = None;
+        mem::swap(&mut self.output, &mut res);
+        match res {
+            None => Box::new(vec![].into_iter()),
+            Some(changes) => Box::new(changes.into_iter()),
+        }
+    }
+    fn dependencies(
+        &self,
+        variables_handler: &Handler,
+    ) -> Box<Iterator<Item = (VariableId, VariableState)>> {
+        Box::new(
+            vec![
+                (
+                    variables_handler.get(&self.x).id(),
+                    VariableState::ValuesChange,
+                ),
+                (
+                    variables_handler.get(&self.y).id(),
+                    VariableState::ValuesChange,
+                ),
+            ]
+            .into_iter(),
+        )
+    }
+    fn initialise(
+        &mut self,
+        variables_handler: &mut Handler,
+    ) -> Result<PropagationState, VariableError> {
+        self.propagate(variables_handler)
+    }
+}
+
+// Filters the bounds of `value = min(array)`. The maximum is the minimum of the
+// opposite values, so it is filtered with `opposite` set to `true`.
+fn filter_array_min<Var, VarArray>(
+    value: &mut Var,
+    array: &mut VarArray,
+    opposite: bool,
+    output: &mut Vec<(VariableId, VariableState)>,
+) -> Result<(), VariableError>
+where
+    Var: OrderedDomain<Type = i32>,
+    VarArray: Array<Variable = Var>,
+{
+    let oriented =
+        |(min, max): (i64, i64)| if opposite { (-max, -min) } else { (min, max) };
+    loop {
+        let bounds: Vec<_> = array.iter().map(|var| oriented(bounds_of(var))).collect();
+        let min = bounds.iter().map(|&(min, _)| min).min().unwrap();
+        let max = bounds.iter().map(|&(_, max)| max).min().unwrap();
+        let (min, max) = oriented((min, max));
+        let mut changed = restrict(value, min, max, output)?;
+
+        let (value_min, value_max) = oriented(bounds_of(value));
+        // Only the variables which can be below the maximum of `value` can be its
+        // minimum, and a single one has to be equal to it.
+        let candidates: Vec<_> = (0..bounds.len())
+            .filter(|&pos| bounds[pos].0 <= value_max)
+            .collect();
+        for (pos, var) in array.iter_mut().enumerate() {
+            let (min, max) = bounds[pos];
+            let max = if candidates == [pos] {
+                cmp::min(max, value_max)
+            } else {
+                max
+            };
+            let (min, max) = oriented((cmp::max(min, value_min), max));
+            changed |= restrict(var, min, max, output)?;
+        }
+        if !changed {
+            return Ok(());
+        }
+    }
+}
+
+macro_rules! array_function_constraint_impl {
+    ($(#[$attr:meta])* $name:ident; $opposite:expr) => {
+        $(#[$attr])*
+        #[derive(Clone)]
+        pub struct $name<View, Views>
+        where
+            View: VariableContainerView,
+            Views: VariableContainerView,
+        {
+            value: View,
+            array: Views,
+            output: Option<Vec<(VariableId, VariableState)>>,
+        }
+
+        impl<View, Views> $name<View, Views>
+        where
+            View: VariableContainerView,
+            Views: VariableContainerView,
+        {
+            pub fn new(value: View, array: Views) -> $name<View, Views> {
+                $name {
+                    value,
+                    array,
+                    output: None,
+                }
+            }
+        }
+
+        impl<Var, VarArray, View, Views, Handler> Constraint<Handler> for $name<View, Views>
+        where
+            Handler: VariablesHandler
+                + VariableContainerHandler<Var, View = View>
+                + VariableContainerHandler<VarArray, View = Views>
+                + Clone,
+            View: VariableContainerView<Container = Var> + 'static,
+            Views: VariableContainerView<Container = VarArray> + 'static,
+            Var: OrderedDomain<Type = i32>,
+            VarArray: Array<Variable = Var>,
+        {
+            fn box_clone(&self) -> Box<Constraint<Handler>> {
+                let ref_self: &$name<View, Views> = &self;
+                let cloned: $name<View, Views> =
+                    <$name<View, Views> as Clone>::clone(ref_self);
+
+                Box::new(cloned) as Box<Constraint<Handler>>
+            }
+            fn propagate(
+                &mut self,
+                variables_handler: &mut Handler,
+            ) -> Result<PropagationState, VariableError> {
+                let mut output = vec![];
+                self.output = None;
+
+                unsafe {
+                    let value: &mut Var = unsafe_from_raw_point!(
+                        VariableContainerHandler::<Var>::get_mut(variables_handler, &self.value)
+                    );
+                    let array: &mut VarArray =
+                        unsafe_from_raw_point!(VariableContainerHandler::<VarArray>::get_mut(
+                            variables_handler,
+                            &self.array
+                        ));
+                    filter_array_min(value, array, $opposite, &mut output)?;
+                }
+
+                if !output.is_empty() {
+                    self.output = Some(output);
+                    Ok(PropagationState::FixPoint)
+                } else {
+                    Ok(PropagationState::NoChange)
+                }
+            }
+            #[allow(unused)]
+            fn prepare(&mut self, states: Box<Iterator<Item = VariableId>>) {
+                // Do nothing
+            }
+            fn result(&mut self) -> Box<Iterator<Item = (VariableId, VariableState)>> {
+                use std::mem;
+                let mut res = None;
+                mem::swap(&mut self.output, &mut res);
+                match res {
+                    None => Box::new(vec![].into_iter()),
+                    Some(changes) => Box::new(changes.into_iter()),
+                }
+            }
+            fn dependencies(
+                &self,
+                variables_handler: &Handler,
+            ) -> Box<Iterator<Item = (VariableId, VariableState)>> {
+                let value = VariableContainerHandler::<Var>::get(variables_handler, &self.value);
+                let array =
+                    VariableContainerHandler::<VarArray>::get(variables_handler, &self.array);
+                let deps: Vec<_> = array
+                    .iter()
+                    .map(|var| (var.id(), VariableState::BoundsChange))
+                    .chain(Some((value.id(), VariableState::BoundsChange)))
+                    .collect();
+                Box::new(deps.into_iter())
+            }
+            fn initialise(
+                &mut self,
+                variables_handler: &mut Handler,
+            ) -> Result<PropagationState, VariableError> {
+                let len = VariableContainerHandler::<VarArray>::get(variables_handler, &self.array)
+                    .len();
+                assert!(len > 0, concat!("Error ", stringify!($name), " empty array."));
+                self.propagate(variables_handler)
+            }
+        }
+    };
+}
+
+array_function_constraint_impl!(
+    /// Enforces `value = min(array)`. The bounds of the variables are filtered.
+    ArrayMin; false
+);
+array_function_constraint_impl!(
+    /// Enforces `value = max(array)`. The bounds of the variables are filtered.
+    ArrayMax; true
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use branchers::values_selector::MinValueSelector;
+    use branchers::variables_selector::SequentialVariableSelector;
+    use model::tests::bounds;
+    use model::Model;
+    use search::Solver;
+    use variables::handlers::macros::VarView;
+    use variables::handlers::DefaultVariablesHandler;
+    use variables::int_var::IntVarValues;
+
+    type View = VarView<IntVarValues>;
+
+    // Counts the solutions of the constraint built by `new` over `x` in `xs`, `y` in
+    // `ys` and `z` in `zs`.
+    fn solutions<C, New>(
+        xs: (i32, i32),
+        ys: (i32, i32),
+        zs: (i32, i32),
+        new: New,
+    ) -> usize
+    where
+        C: Constraint<DefaultVariablesHandler> + 'static,
+        New: Fn(View, View, View) -> C,
+    {
+        let mut model = Model::new();
+        let x = model.new_int_var(xs.0, xs.1).unwrap();
+        let y = model.new_int_var(ys.0, ys.1).unwrap();
+        let z = model.new_int_var(zs.0, zs.1).unwrap();
+        model.post(new(x, y, z));
+        model.branch(
+            SequentialVariableSelector::new(vec![x, y, z].into_iter()).unwrap(),
+            MinValueSelector::new(),
+        );
+        match model.build() {
+            Ok(space) => Solver::new(space).count_solutions(),
+            Err(_) => 0,
+        }
+    }
+
+    fn brute_force<Function>(
+        xs: (i32, i32),
+        ys: (i32, i32),
+        zs: (i32, i32),
+        function: Function,
+    ) -> usize
+    where
+        Function: Fn(i64, i64) -> Option<i64>,
+    {
+        let mut count = 0;
+        for x in xs.0..=xs.1 {
+            for y in ys.0..=ys.1 {
+                match function(i64::from(x), i64::from(y)) {
+                    Some(z) if i64::from(zs.0) <= z && z <= i64::from(zs.1) => count += 1,
+                    _ => {}
+                }
+            }
+        }
+        count
+    }
+
+    #[test]
+    fn function_solutions() {
+        let (xs, ys, zs) = ((-3, 3), (-3, 3), (-6, 6));
+        assert_eq!(
+            solutions(xs, ys, zs, Times::new),
+            brute_force(xs, ys, zs, |x, y| Some(x * y))
+        );
+        assert_eq!(
+            solutions(xs, ys, zs, Div::new),
+            brute_force(xs, ys, zs, |x, y| if y != 0 { Some(x / y) } else { None })
+        );
+        assert_eq!(
+            solutions(xs, ys, zs, Mod::new),
+            brute_force(xs, ys, zs, |x, y| if y != 0 { Some(x % y) } else { None })
+        );
+        assert_eq!(
+            solutions(xs, ys, zs, Min::new),
+            brute_force(xs, ys, zs, |x, y| Some(cmp::min(x, y)))
+        );
+        assert_eq!(
+            solutions(xs, ys, zs, Max::new),
+            brute_force(xs, ys, zs, |x, y| Some(cmp::max(x, y)))
+        );
+        let (ys, zs) = ((0, 3), (-30, 30));
+        assert_eq!(
+            solutions(xs, ys, zs, Power::new),
+            brute_force(xs, ys, zs, power)
+        );
+    }
+
+    #[test]
+    fn function_infeasible() {
+        assert_eq!(solutions((2, 3), (2, 3), (10, 20), Times::new), 0);
+        assert_eq!(solutions((1, 5), (0, 0), (-5, 5), Div::new), 0);
+        assert_eq!(solutions((7, 9), (10, 12), (1, 5), Mod::new), 0);
+        assert_eq!(solutions((4, 5), (6, 9), (0, 3), Min::new), 0);
+        assert_eq!(solutions((2, 3), (0, 2), (10, 20), Power::new), 0);
+    }
+
+    #[test]
+    fn times_prunes() {
+        let mut model = Model::new();
+        let x = model.new_int_var(0, 3).unwrap();
+        let y = model.new_int_var(0, 3).unwrap();
+        // The domain of `z` is much larger than the number of pairs.
+        let z = model.new_int_var(-1_000_000, 1_000_000).unwrap();
+        model.post(Times::new(x, y, z));
+        let mut space = model.build().unwrap();
+        space.run().unwrap();
+        let var: &IntVarValues = space.get_variable(&z);
+        assert_eq!(var.iter().collect::<Vec<_>>(), vec![0, 1, 2, 3, 4, 6, 9]);
+    }
+
+    #[test]
+    fn times_bounds_prunes() {
+        let mut model = Model::new();
+        let x = model.new_int_var(-100_000, 100_000).unwrap();
+        let y = model.new_int_var(2, 100_000).unwrap();
+        let z = model.new_int_var(-10, 30).unwrap();
+        model.post(Times::new(x, y, z));
+        let mut space = model.build().unwrap();
+        space.run().unwrap();
+        assert_eq!(bounds(&space, &x), (-5, 15));
+        assert_eq!(bounds(&space, &y), (2, 100_000));
+    }
+
+    #[test]
+    fn square_solutions() {
+        let mut model = Model::new();
+        let x = model.new_int_var(-3, 3).unwrap();
+        let z = model.new_int_var(2, 5).unwrap();
+        model.post(Times::new(x, x, z));
+        let mut space = model.build().unwrap();
+        space.run().unwrap();
+        let var: &IntVarValues = space.get_variable(&x);
+        assert_eq!(var.iter().collect::<Vec<_>>(), vec![-2, 2]);
+        let var: &IntVarValues = space.get_variable(&z);
+        assert_eq!(var.value(), Some(4));
+        assert_eq!(
+            solutions((-3, 3), (0, 0), (0, 9), |x, _, z| Times::new(x, x, z)),
+            7
+        );
+    }
+
+    #[test]
+    fn square_bounds_prunes() {
+        let mut model = Model::new();
+        let x = model.new_int_var(-100_000, 100_000).unwrap();
+        let z = model.new_int_var(5, 30).unwrap();
+        model.post(Times::new(x, x, z));
+        let mut space = model.build().unwrap();
+        space.run().unwrap();
+        // The bounds of `x` are filtered first, then its values once it is small.
+        assert_eq!(bounds(&space, &x), (-5, 5));
+        assert_eq!(bounds(&space, &z), (9, 25));
+
+        let mut model = Model::new();
+        let x = model.new_int_var(0, 100_000).unwrap();
+        let z = model.new_int_var(5, 30).unwrap();
+        model.post(Times::new(x, x, z));
+        let mut space = model.build().unwrap();
+        space.run().unwrap();
+        assert_eq!(bounds(&space, &x), (3, 5));
+        assert_eq!(bounds(&space, &z), (9, 25));
+        assert_eq!(floor_sqrt(24), 4);
+        assert_eq!(floor_sqrt(25), 5);
+    }
+
+    #[test]
+    fn abs_prunes() {
+        let mut model = Model::new();
+        let x = model.new_int_var(-3, 5).unwrap();
+        let y = model.new_int_var(4, 10).unwrap();
+        model.post(Abs::new(x, y));
+        let mut space = model.build().unwrap();
+        space.run().unwrap();
+        let var: &IntVarValues = space.get_variable(&x);
+        assert_eq!(var.iter().collect::<Vec<_>>(), vec![4, 5]);
+        let var: &IntVarValues = space.get_variable(&y);
+        assert_eq!(var.iter().collect::<Vec<_>>(), vec![4, 5]);
+
+        let mut model = Model::new();
+        let x = model.new_int_var(-3, 3).unwrap();
+        let y = model.new_int_var(4, 10).unwrap();
+        model.post(Abs::new(x, y));
+        assert!(model.build().is_err());
+    }
+
+    #[test]
+    fn array_min_prunes() {
+        let mut model = Model::new();
+        let value = model.new_int_var(0, 10).unwrap();
+        let x = model.new_int_var(3, 8).unwrap();
+        let y = model.new_int_var(5, 9).unwrap();
+        let array = model.new_array_of_refs(vec![x, y]).unwrap();
+        model.post(ArrayMin::new(value, array));
+        let other = model.new_int_var(0, 4).unwrap();
+        let array = model.new_array_of_refs(vec![x, y]).unwrap();
+        model.post(ArrayMax::new(other, array));
+        assert!(model.build().is_err());
+
+        let mut model = Model::new();
+        let value = model.new_int_var(0, 4).unwrap();
+        let x = model.new_int_var(3, 8).unwrap();
+        let y = model.new_int_var(5, 9).unwrap();
+        let array = model.new_array_of_refs(vec![x, y]).unwrap();
+        model.post(ArrayMin::new(value, array));
+        let mut space = model.build().unwrap();
+        space.run().unwrap();
+        // Only `x` can be the minimum.
+        assert_eq!(bounds(&space, &value), (3, 4));
+        assert_eq!(bounds(&space, &x), (3, 4));
+        assert_eq!(bounds(&space, &y), (5, 9));
+    }
+}
@@ -0,0 +1,190 @@
+use constraints::{Constraint, PropagationState};
+use variables::bool_var::BoolVar;
+use variables::domains::{IterableDomain, PrunableDomain};
+use variables::handlers::macros::VarView;
+use variables::handlers::{VariableContainerHandler, VariablesHandler};
+use variables::{Variable, VariableError, VariableId, VariableState};
+
+/// Links a Boolean variable to an integer variable: the integer variable is equal to `1`
+/// if the Boolean variable is `true` and to `0` if it is `false`.
+#[derive(Clone)]
+pub struct Channel<Var>
+where
+    Var: Variable,
+{
+    boolean: VarView<BoolVar>,
+    integer: VarView<Var>,
+    output: Option<Vec<(VariableId, VariableState)>>,
+}
+
+impl<Var> Channel<Var>
+where
+    Var: Variable,
+{
+    pub fn new(boolean: VarView<BoolVar>, integer: VarView<Var>) -> Channel<Var> {
+        Channel {
+            boolean,
+            integer,
+            output: None,
+        }
+    }
+}
+
+impl<Var, Handler> Constraint<Handler> for Channel<Var>
+where
+    Handler: VariablesHandler
+        + VariableContainerHandler<BoolVar, View = VarView<BoolVar>>
+        + VariableContainerHandler<Var, View = VarView<Var>>
+        + Clone,
+    Var: PrunableDomain<Type = i32> + IterableDomain + 'static,
+{
+    fn box_clone(&self) -> Box<Constraint<Handler>> {
+        let ref_self: &Channel<Var> = self;
+        let cloned: Channel<Var> = <Channel<Var> as Clone>::clone(ref_self);
+
+        Box::new(cloned) as Box<Constraint<Handler>>
+    }
+    fn propagate(
+        &mut self,
+        variables_handler: &mut Handler,
+    ) -> Result<PropagationState, VariableError> {
+        let mut output = vec![];
+        self.output = None;
+
+        unsafe {
+            let boolean: &mut BoolVar =
+                unsafe_from_raw_point!(VariableContainerHandler::<BoolVar>::get_mut(
+                    variables_handler,
+                    &self.boolean
+                ));
+            let integer: &mut Var =
+                unsafe_from_raw_point!(VariableContainerHandler::<Var>::get_mut(
+                    variables_handler,
+                    &self.integer
+                ));
+            let domain: Vec<_> = boolean.iter().map(|value| value as i32).collect();
+            let state = integer.in_values(domain)?;
+            match state {
+                VariableState::NoChange => {}
+                state => {
+                    output.push((integer.id(), state));
+                }
+            }
+            let domain: Vec<_> = integer.iter().map(|value| value == 1).collect();
+            let state = boolean.in_values(domain)?;
+            match state {
+                VariableState::NoChange => {}
+                state => {
+                    output.push((boolean.id(), state));
+                }
+            }
+        }
+
+        if !output.is_empty() {
+            self.output = Some(output);
+            Ok(PropagationState::FixPoint)
+        } else {
+            Ok(PropagationState::NoChange)
+        }
+    }
+    #[allow(unused)]
+    fn prepare(&mut self, states: Box<Iterator<Item = VariableId>>) {
+        // Do nothing
+    }
+    fn result(&mut self) -> Box<Iterator<Item = (VariableId, VariableState)>> {
+        use std::mem;
+        let mut res = None;
+        mem::swap(&mut self.output, &mut res);
+        match res {
+            None => Box::new(vec![].into_iter()),
+            Some(changes) => Box::new(changes.into_iter()),
+        }
+    }
+    fn dependencies(
+        &self,
+        variables: &Handler,
+    ) -> Box<Iterator<Item = (VariableId, VariableState)>> {
+        let boolean = VariableContainerHandler::<BoolVar>::get(variables, &self.boolean);
+        let integer = VariableContainerHandler::<Var>::get(variables, &self.integer);
+        Box::new(
+            vec![
+                (boolean.id(), VariableState::ValuesChange),
+                (integer.id(), VariableState::ValuesChange),
+            ]
+            .into_iter(),
+        )
+    }
+    fn initialise(
+        &mut self,
+        variables_handler: &mut Handler,
+    ) -> Result<PropagationState, VariableError> {
+        self.propagate(variables_handler)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use branchers::values_selector::MinValueSelector;
+    use branchers::variables_selector::SequentialVariableSelector;
+    use model::Model;
+    use search::Solver;
+    use variables::domains::AssignableDomain;
+    use variables::int_var::IntVarValues;
+
+    #[test]
+    fn boolean_fixes_integer() {
+        let mut model = Model::new();
+        let b = model.new_bool_var();
+        let x = model.new_int_var(-2, 5).unwrap();
+        model.post(Channel::new(b, x));
+        let mut space = model.build().unwrap();
+        assert!(space.run().is_ok());
+        let var: &IntVarValues = space.get_variable(&x);
+        assert_eq!(var.iter().collect::<Vec<_>>(), vec![0, 1]);
+
+        let mut space = space.clone();
+        {
+            let var: &mut BoolVar = space.variables.get_mut(&b);
+            var.set_value(true).unwrap();
+        }
+        assert!(space.run().is_ok());
+        let var: &IntVarValues = space.get_variable(&x);
+        assert_eq!(var.value(), Some(1));
+    }
+
+    #[test]
+    fn integer_fixes_boolean() {
+        let mut model = Model::new();
+        let b = model.new_bool_var();
+        let x = model.new_int_var_from_values(vec![0, 3, 4]).unwrap();
+        model.post(Channel::new(b, x));
+        let mut space = model.build().unwrap();
+        assert!(space.run().is_ok());
+        let var: &BoolVar = space.get_variable(&b);
+        assert_eq!(var.value(), Some(false));
+    }
+
+    #[test]
+    fn infeasible() {
+        let mut model = Model::new();
+        let b = model.new_bool_var();
+        let x = model.new_int_var(2, 5).unwrap();
+        model.post(Channel::new(b, x));
+        assert!(model.build().is_err());
+    }
+
+    #[test]
+    fn count_solutions() {
+        let mut model = Model::new();
+        let b = model.new_bool_var();
+        let x = model.new_int_var(-3, 3).unwrap();
+        model.post(Channel::new(b, x));
+        model.branch(
+            SequentialVariableSelector::new(vec![x].into_iter()).unwrap(),
+            MinValueSelector::new(),
+        );
+        let solver = Solver::new(model.build().unwrap());
+        assert_eq!(solver.count_solutions(), 2);
+    }
+}
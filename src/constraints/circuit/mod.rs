@@ -0,0 +1,502 @@
+use constraints::{Constraint, PropagationState};
+use graph::scc::strongly_connected_components;
+use variables::domains::{IterableDomain, PrunableDomain};
+use variables::handlers::{
+    VariableContainerHandler, VariableContainerView, VariablesHandler,
+};
+use variables::{Array, VariableError, VariableId, VariableState};
+
+// Keeps in `succs[node]` only `node`, or fails if `node` can not loop on itself.
+fn force_self_loop(succs: &mut [Vec<usize>], node: usize) -> Result<(), VariableError> {
+    if succs[node].contains(&node) {
+        succs[node] = vec![node];
+        Ok(())
+    } else {
+        Err(VariableError::DomainWipeout)
+    }
+}
+
+// Filters the possible successors of the nodes. When `subcircuit` is `true` the nodes
+// looping on themselves are not part of the circuit.
+fn filter_successors(
+    succs: &mut [Vec<usize>],
+    subcircuit: bool,
+) -> Result<(), VariableError> {
+    let len = succs.len();
+    if !subcircuit && len > 1 {
+        for (node, node_succs) in succs.iter_mut().enumerate() {
+            node_succs.retain(|&succ| succ != node);
+        }
+    }
+
+    // The successors are all different.
+    let mut assigned = vec![false; len];
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for node in 0..len {
+            if !assigned[node] && succs[node].len() == 1 {
+                assigned[node] = true;
+                changed = true;
+                let succ = succs[node][0];
+                for (other, other_succs) in succs.iter_mut().enumerate() {
+                    if other != node {
+                        other_succs.retain(|&value| value != succ);
+                    }
+                }
+            }
+        }
+    }
+    if succs.iter().any(|node_succs| node_succs.is_empty()) {
+        return Err(VariableError::DomainWipeout);
+    }
+
+    // Subtour elimination: a chain of assigned successors can only be closed if it
+    // visits all the nodes of the circuit.
+    let mut next = vec![None; len];
+    let mut has_pred = vec![false; len];
+    for node in 0..len {
+        if succs[node].len() == 1 && succs[node][0] != node {
+            next[node] = Some(succs[node][0]);
+            has_pred[succs[node][0]] = true;
+        }
+    }
+    let mut in_chain = vec![false; len];
+    for start in (0..len).filter(|&node| next[node].is_some() && !has_pred[node]) {
+        let mut chain = vec![false; len];
+        let mut end = start;
+        chain[end] = true;
+        while let Some(succ) = next[end] {
+            end = succ;
+            chain[end] = true;
+        }
+        for (node, &in_this_chain) in chain.iter().enumerate() {
+            in_chain[node] |= in_this_chain;
+        }
+        let can_close = (0..len)
+            .all(|other| chain[other] || (subcircuit && succs[other].contains(&other)));
+        if !can_close {
+            succs[end].retain(|&succ| succ != start);
+        }
+    }
+    for start in 0..len {
+        if in_chain[start] || next[start].is_none() {
+            continue;
+        }
+        // `start` belongs to a closed cycle.
+        let mut cycle = vec![false; len];
+        let mut node = start;
+        while !cycle[node] {
+            cycle[node] = true;
+            in_chain[node] = true;
+            node = next[node].unwrap();
+        }
+        for other in (0..len).filter(|&other| !cycle[other]) {
+            if !subcircuit {
+                return Err(VariableError::DomainWipeout);
+            }
+            force_self_loop(succs, other)?;
+        }
+    }
+
+    // Reachability: an edge between two strongly connected components is not part of
+    // any cycle.
+    let graph: Vec<Vec<usize>> = succs
+        .iter()
+        .enumerate()
+        .map(|(node, node_succs)| {
+            node_succs
+                .iter()
+                .cloned()
+                .filter(|&succ| succ != node)
+                .collect()
+        })
+        .collect();
+    let components = strongly_connected_components(&graph);
+    for (node, node_succs) in succs.iter_mut().enumerate() {
+        node_succs.retain(|&succ| succ == node || components[succ] == components[node]);
+    }
+    if subcircuit {
+        // The nodes which can not loop on themselves are in the same component and
+        // the other components loop on themselves.
+        let mandatory: Vec<_> = (0..len)
+            .filter(|&node| !succs[node].contains(&node))
+            .map(|node| components[node])
+            .collect();
+        if let Some(&component) = mandatory.first() {
+            if mandatory.iter().any(|&other| other != component) {
+                return Err(VariableError::DomainWipeout);
+            }
+            for node in (0..len).filter(|&node| components[node] != component) {
+                force_self_loop(succs, node)?;
+            }
+        }
+    } else if components
+        .iter()
+        .any(|&component| component != components[0])
+    {
+        return Err(VariableError::DomainWipeout);
+    }
+    if succs.iter().any(|node_succs| node_succs.is_empty()) {
+        return Err(VariableError::DomainWipeout);
+    }
+    Ok(())
+}
+
+fn propagate_successors<Var, VarArray>(
+    array: &mut VarArray,
+    subcircuit: bool,
+    output: &mut Vec<(VariableId, VariableState)>,
+) -> Result<(), VariableError>
+where
+    Var: PrunableDomain<Type = i32> + IterableDomain,
+    VarArray: Array<Variable = Var>,
+{
+    let len = array.len() as i32;
+    loop {
+        let mut succs: Vec<Vec<usize>> = array
+            .iter()
+            .map(|var| {
+                var.iter()
+                    .filter(|&succ| succ >= 0 && succ < len)
+                    .map(|succ| succ as usize)
+                    .collect()
+            })
+            .collect();
+        filter_successors(&mut succs, subcircuit)?;
+
+        let mut changed = false;
+        for (var, node_succs) in array.iter_mut().zip(succs) {
+            let state = var.in_values(node_succs.into_iter().map(|succ| succ as i32))?;
+            match state {
+                VariableState::NoChange => {}
+                state => {
+                    changed = true;
+                    output.push((var.id(), state));
+                }
+            }
+        }
+        if !changed {
+            return Ok(());
+        }
+    }
+}
+
+/// Enforces that the successors form a single cycle visiting all the nodes. The
+/// nodes are numbered from `0`, and `array[i]` is the node visited after `i`.
+#[derive(Clone)]
+pub struct Circuit<Views>
+where
+    Views: VariableContainerView,
+{
+    array: Views,
+    output: Option<Vec<(VariableId, VariableState)>>,
+}
+
+impl<Views> Circuit<Views>
+where
+    Views: VariableContainerView,
+{
+    pub fn new(array: Views) -> Circuit<Views> {
+        Circuit {
+            array,
+            output: None,
+        }
+    }
+}
+
+impl<Var, VarArray, Views, Handler> Constraint<Handler> for Circuit<Views>
+where
+    Handler: VariablesHandler + VariableContainerHandler<VarArray, View = Views> + Clone,
+    Views: VariableContainerView<Container = VarArray> + 'static,
+    Var: PrunableDomain<Type = i32> + IterableDomain,
+    VarArray: Array<Variable = Var>,
+{
+    fn box_clone(&self) -> Box<Constraint<Handler>> {
+        let ref_self: &Circuit<Views> = self;
+        let cloned: Circuit<Views> = <Circuit<Views> as Clone>::clone(ref_self);
+
+        Box::new(cloned) as Box<Constraint<Handler>>
+    }
+    fn propagate(
+        &mut self,
+        variables_handler: &mut Handler,
+    ) -> Result<PropagationState, VariableError> {
+        let mut output = vec![];
+        self.output = None;
+
+        propagate_successors(variables_handler.get_mut(&self.array), false, &mut output)?;
+
+        if !output.is_empty() {
+            self.output = Some(output);
+            Ok(PropagationState::FixPoint)
+        } else {
+            Ok(PropagationState::NoChange)
+        }
+    }
+    #[allow(unused)]
+    fn prepare(&mut self, states: Box<Iterator<Item = VariableId>>) {
+        // Do nothing
+    }
+    fn result(&mut self) -> Box<Iterator<Item = (VariableId, VariableState)>> {
+        use std::mem;
+        let mut res = None;
+        mem::swap(&mut self.output, &mut res);
+        match res {
+            None => Box::new(vec![].into_iter()),
+            Some(changes) => Box::new(changes.into_iter()),
+        }
+    }
+    fn dependencies(
+        &self,
+        variables_handler: &Handler,
+    ) -> Box<Iterator<Item = (VariableId, VariableState)>> {
+        let deps: Vec<_> = variables_handler
+            .get(&self.array)
+            .iter()
+            .map(|var| (var.id(), VariableState::ValuesChange))
+            .collect();
+        Box::new(deps.into_iter())
+    }
+    fn initialise(
+        &mut self,
+        variables_handler: &mut Handler,
+    ) -> Result<PropagationState, VariableError> {
+        self.propagate(variables_handler)
+    }
+}
+
+/// Enforces that the successors of the nodes which do not loop on themselves form a
+/// single cycle. The nodes are numbered from `0`, and `array[i]` is the node visited
+/// after `i`, or `i` if the node is not visited.
+#[derive(Clone)]
+pub struct SubCircuit<Views>
+where
+    Views: VariableContainerView,
+{
+    array: Views,
+    output: Option<Vec<(VariableId, VariableState)>>,
+}
+
+impl<Views> SubCircuit<Views>
+where
+    Views: VariableContainerView,
+{
+    pub fn new(array: Views) -> SubCircuit<Views> {
+        SubCircuit {
+            array,
+            output: None,
+        }
+    }
+}
+
+impl<Var, VarArray, Views, Handler> Constraint<Handler> for SubCircuit<Views>
+where
+    Handler: VariablesHandler + VariableContainerHandler<VarArray, View = Views> + Clone,
+    Views: VariableContainerView<Container = VarArray> + 'static,
+    Var: PrunableDomain<Type = i32> + IterableDomain,
+    VarArray: Array<Variable = Var>,
+{
+    fn box_clone(&self) -> Box<Constraint<Handler>> {
+        let ref_self: &SubCircuit<Views> = self;
+        let cloned: SubCircuit<Views> = <SubCircuit<Views> as Clone>::clone(ref_self);
+
+        Box::new(cloned) as Box<Constraint<Handler>>
+    }
+    fn propagate(
+        &mut self,
+        variables_handler: &mut Handler,
+    ) -> Result<PropagationState, VariableError> {
+        let mut output = vec![];
+        self.output = None;
+
+        propagate_successors(variables_handler.get_mut(&self.array), true, &mut output)?;
+
+        if !output.is_empty() {
+            self.output = Some(output);
+            Ok(PropagationState::FixPoint)
+        } else {
+            Ok(PropagationState::NoChange)
+        }
+    }
+    #[allow(unused)]
+    fn prepare(&mut self, states: Box<Iterator<Item = VariableId>>) {
+        // Do nothing
+    }
+    fn result(&mut self) -> Box<Iterator<Item = (VariableId, VariableState)>> {
+        use std::mem;
+        let mut res = None;
+        mem::swap(&mut self.output, &mut res);
+        match res {
+            None => Box::new(vec![].into_iter()),
+            Some(changes) => Box::new(changes.into_iter()),
+        }
+    }
+    fn dependencies(
+        &self,
+        variables_handler: &Handler,
+    ) -> Box<Iterator<Item = (VariableId, VariableState)>> {
+        let deps: Vec<_> = variables_handler
+            .get(&self.array)
+            .iter()
+            .map(|var| (var.id(), VariableState::ValuesChange))
+            .collect();
+        Box::new(deps.into_iter())
+    }
+    fn initialise(
+        &mut self,
+        variables_handler: &mut Handler,
+    ) -> Result<PropagationState, VariableError> {
+        self.propagate(variables_handler)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use branchers::values_selector::MinValueSelector;
+    use branchers::variables_selector::SequentialVariableSelector;
+    use model::Model;
+    use search::Solver;
+    use variables::domains::AssignableDomain;
+    use variables::int_var::IntVarValues;
+    use variables::Variable;
+
+    // Returns `true` if the successors of the nodes not looping on themselves form a
+    // single cycle, which visits all the nodes when `subcircuit` is `false`.
+    fn is_circuit(succs: &[usize], subcircuit: bool) -> bool {
+        let visited: Vec<_> = (0..succs.len())
+            .filter(|&node| succs[node] != node)
+            .collect();
+        if visited.is_empty() {
+            return subcircuit || succs.len() == 1;
+        }
+        if !subcircuit && visited.len() != succs.len() {
+            return false;
+        }
+        let start = visited[0];
+        let mut node = succs[start];
+        let mut len = 1;
+        while node != start && len <= visited.len() {
+            node = succs[node];
+            len += 1;
+        }
+        node == start && len == visited.len()
+    }
+
+    fn brute_force(len: usize, subcircuit: bool) -> usize {
+        let mut count = 0;
+        let mut succs = vec![0; len];
+        loop {
+            if is_circuit(&succs, subcircuit) {
+                count += 1;
+            }
+            let mut pos = 0;
+            while pos < len && succs[pos] == len - 1 {
+                succs[pos] = 0;
+                pos += 1;
+            }
+            if pos == len {
+                return count;
+            }
+            succs[pos] += 1;
+        }
+    }
+
+    fn count(len: usize, subcircuit: bool) -> usize {
+        let mut model = Model::new();
+        let vars: Vec<_> = (0..len)
+            .map(|_| model.new_int_var(0, len as i32 - 1).unwrap())
+            .collect();
+        let array = model.new_array_of_refs(vars.clone()).unwrap();
+        if subcircuit {
+            model.post(SubCircuit::new(array));
+        } else {
+            model.post(Circuit::new(array));
+        }
+        model.branch(
+            SequentialVariableSelector::new(vars.into_iter()).unwrap(),
+            MinValueSelector::new(),
+        );
+        Solver::new(model.build().unwrap()).count_solutions()
+    }
+
+    #[test]
+    fn circuit_solutions() {
+        // (n - 1)! hamiltonian cycles.
+        assert_eq!(count(4, false), 6);
+        assert_eq!(count(5, false), 24);
+        assert_eq!(brute_force(5, false), 24);
+    }
+
+    #[test]
+    fn subcircuit_solutions() {
+        for len in 2..6 {
+            assert_eq!(count(len, true), brute_force(len, true));
+        }
+    }
+
+    #[test]
+    fn circuit_prunes() {
+        let mut model = Model::new();
+        let vars: Vec<_> = (0..4).map(|_| model.new_int_var(0, 3).unwrap()).collect();
+        let array = model.new_array_of_refs(vars.clone()).unwrap();
+        model.post(Circuit::new(array));
+        let mut space = model.build().unwrap();
+        space.run().unwrap();
+        // No node loops on itself.
+        let var: &IntVarValues = space.get_variable(&vars[0]);
+        assert_eq!(var.iter().collect::<Vec<_>>(), vec![1, 2, 3]);
+        // 0 -> 1 -> 2 can not be closed before visiting 3.
+        {
+            let var: &mut IntVarValues = space.variables.get_mut(&vars[0]);
+            var.set_value(1).unwrap();
+            let var: &mut IntVarValues = space.variables.get_mut(&vars[1]);
+            var.set_value(2).unwrap();
+        }
+        space.run().unwrap();
+        let var: &IntVarValues = space.get_variable(&vars[2]);
+        assert_eq!(var.value(), Some(3));
+        let var: &IntVarValues = space.get_variable(&vars[3]);
+        assert_eq!(var.value(), Some(0));
+    }
+
+    #[test]
+    fn subcircuit_prunes() {
+        // 0 -> 1 -> 0 is closed, so 2 and 3 loop on themselves.
+        let mut model = Model::new();
+        let x = model.new_int_var(1, 1).unwrap();
+        let y = model.new_int_var(0, 0).unwrap();
+        let z = model.new_int_var(0, 3).unwrap();
+        let t = model.new_int_var(0, 3).unwrap();
+        let array = model.new_array_of_refs(vec![x, y, z, t]).unwrap();
+        model.post(SubCircuit::new(array));
+        let mut space = model.build().unwrap();
+        space.run().unwrap();
+        let var: &IntVarValues = space.get_variable(&z);
+        assert_eq!(var.value(), Some(2));
+        let var: &IntVarValues = space.get_variable(&t);
+        assert_eq!(var.value(), Some(3));
+    }
+
+    #[test]
+    fn infeasible_without_search() {
+        // Two disjoint cycles 0 <-> 1 and 2 <-> 3.
+        let mut model = Model::new();
+        let vars: Vec<_> = vec![(1, 1), (0, 0), (3, 3), (2, 2)]
+            .into_iter()
+            .map(|(min, max)| model.new_int_var(min, max).unwrap())
+            .collect();
+        let array = model.new_array_of_refs(vars.clone()).unwrap();
+        model.post(Circuit::new(array));
+        assert!(model.build().is_err());
+        let mut model = Model::new();
+        let vars: Vec<_> = vec![(1, 1), (0, 0), (3, 3), (2, 2)]
+            .into_iter()
+            .map(|(min, max)| model.new_int_var(min, max).unwrap())
+            .collect();
+        let array = model.new_array_of_refs(vars).unwrap();
+        model.post(SubCircuit::new(array));
+        assert!(model.build().is_err());
+    }
+}
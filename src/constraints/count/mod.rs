@@ -0,0 +1,531 @@
+use constraints::{Constraint, PropagationState};
+use std::cmp;
+use std::sync::Arc;
+use variables::domains::{IterableDomain, OrderedDomain, PrunableDomain};
+use variables::handlers::{
+    VariableContainerHandler, VariableContainerView, VariablesHandler,
+};
+use variables::{Array, VariableError, VariableId, VariableState};
+
+// Filters the variables of the array such that between `min` and `max` of them take
+// a value of `values`, which is sorted. Returns the number of variables which are sure
+// to take a value of `values` and the number of variables which can take one.
+fn filter_occurrences<Var, VarArray>(
+    array: &mut VarArray,
+    values: &[i32],
+    min: usize,
+    max: usize,
+    output: &mut Vec<(VariableId, VariableState)>,
+) -> Result<(usize, usize), VariableError>
+where
+    Var: PrunableDomain<Type = i32> + IterableDomain,
+    VarArray: Array<Variable = Var>,
+{
+    let contains = |value: &i32| values.binary_search(value).is_ok();
+    let mut sure = 0;
+    let mut undecided = vec![];
+    for (pos, var) in array.iter().enumerate() {
+        let inside = var.iter().filter(|value| contains(value)).count();
+        if inside == var.size() {
+            sure += 1;
+        } else if inside > 0 {
+            undecided.push(pos);
+        }
+    }
+    let possible = sure + undecided.len();
+    if sure > max || possible < min {
+        return Err(VariableError::DomainWipeout);
+    }
+
+    // Once a bound is reached the undecided variables are all outside or all inside.
+    if sure == max || possible == min {
+        for pos in undecided {
+            let var = array.get_unchecked_mut(pos);
+            let state = if sure == max {
+                var.remove_if(|value| contains(value))?
+            } else {
+                var.retains_if(|value| contains(value))?
+            };
+            match state {
+                VariableState::NoChange => {}
+                state => {
+                    output.push((var.id(), state));
+                }
+            }
+        }
+        if sure == max {
+            return Ok((sure, sure));
+        } else {
+            return Ok((possible, possible));
+        }
+    }
+    Ok((sure, possible))
+}
+
+// Filters the variables of the array and `count` until the fixpoint.
+fn filter_count<Var, VarArray>(
+    array: &mut VarArray,
+    values: &[i32],
+    count: &mut Var,
+    output: &mut Vec<(VariableId, VariableState)>,
+) -> Result<(), VariableError>
+where
+    Var: PrunableDomain<Type = i32> + OrderedDomain + IterableDomain,
+    VarArray: Array<Variable = Var>,
+{
+    let len = array.len() as i32;
+    loop {
+        if count.unchecked_max() < 0 || count.unchecked_min() > len {
+            return Err(VariableError::DomainWipeout);
+        }
+        let min = cmp::max(count.unchecked_min(), 0) as usize;
+        let max = cmp::min(count.unchecked_max(), len) as usize;
+        let (sure, possible) = filter_occurrences(array, values, min, max, output)?;
+
+        let mut changed = false;
+        for state in [
+            count.weak_lowerbound(sure as i32)?,
+            count.weak_upperbound(possible as i32)?,
+        ] {
+            match state {
+                VariableState::NoChange => {}
+                state => {
+                    changed = true;
+                    output.push((count.id(), state));
+                }
+            }
+        }
+        if !changed {
+            return Ok(());
+        }
+    }
+}
+
+macro_rules! count_constraint_impl {
+    ($name:ident) => {
+        impl<Var, VarArray, View, Views, Handler> Constraint<Handler>
+            for $name<View, Views>
+        where
+            Handler: VariablesHandler
+                + VariableContainerHandler<Var, View = View>
+                + VariableContainerHandler<VarArray, View = Views>
+                + Clone,
+            View: VariableContainerView<Container = Var> + 'static,
+            Views: VariableContainerView<Container = VarArray> + 'static,
+            Var: PrunableDomain<Type = i32> + OrderedDomain + IterableDomain,
+            VarArray: Array<Variable = Var>,
+        {
+            fn box_clone(&self) -> Box<Constraint<Handler>> {
+                let ref_self: &$name<View, Views> = &self;
+                let cloned: $name<View, Views> =
+                    <$name<View, Views> as Clone>::clone(ref_self);
+
+                Box::new(cloned) as Box<Constraint<Handler>>
+            }
+            fn propagate(
+                &mut self,
+                variables_handler: &mut Handler,
+            ) -> Result<PropagationState, VariableError> {
+                let mut output = vec![];
+                self.output = None;
+
+                unsafe {
+                    let count: &mut Var =
+                        unsafe_from_raw_point!(VariableContainerHandler::<Var>::get_mut(
+                            variables_handler,
+                            &self.count
+                        ));
+                    let array: &mut VarArray = unsafe_from_raw_point!(
+                        VariableContainerHandler::<VarArray>::get_mut(
+                            variables_handler,
+                            &self.array
+                        )
+                    );
+                    filter_count(array, &self.values, count, &mut output)?;
+                }
+
+                if !output.is_empty() {
+                    self.output = Some(output);
+                    Ok(PropagationState::FixPoint)
+                } else {
+                    Ok(PropagationState::NoChange)
+                }
+            }
+            #[allow(unused)]
+            fn prepare(&mut self, states: Box<Iterator<Item = VariableId>>) {
+                // Do nothing
+            }
+            fn result(&mut self) -> Box<Iterator<Item = (VariableId, VariableState)>> {
+                use std::mem;
+                let mut res = None;
+                mem::swap(&mut self.output, &mut res);
+                match res {
+                    None => Box::new(vec![].into_iter()),
+                    Some(changes) => Box::new(changes.into_iter()),
+                }
+            }
+            fn dependencies(
+                &self,
+                variables_handler: &Handler,
+            ) -> Box<Iterator<Item = (VariableId, VariableState)>> {
+                let count =
+                    VariableContainerHandler::<Var>::get(variables_handler, &self.count);
+                let array = VariableContainerHandler::<VarArray>::get(
+                    variables_handler,
+                    &self.array,
+                );
+                let deps: Vec<_> = array
+                    .iter()
+                    .map(|var| (var.id(), VariableState::ValuesChange))
+                    .chain(Some((count.id(), VariableState::BoundsChange)))
+                    .collect();
+                Box::new(deps.into_iter())
+            }
+            fn initialise(
+                &mut self,
+                variables_handler: &mut Handler,
+            ) -> Result<PropagationState, VariableError> {
+                self.propagate(variables_handler)
+            }
+        }
+    };
+}
+
+/// Enforces that `count` is the number of variables of the array equal to `value`.
+#[derive(Clone)]
+pub struct Count<View, Views>
+where
+    View: VariableContainerView,
+    Views: VariableContainerView,
+{
+    array: Views,
+    values: Arc<Vec<i32>>,
+    count: View,
+    output: Option<Vec<(VariableId, VariableState)>>,
+}
+
+impl<View, Views> Count<View, Views>
+where
+    View: VariableContainerView,
+    Views: VariableContainerView,
+{
+    pub fn new(array: Views, value: i32, count: View) -> Count<View, Views> {
+        Count {
+            array,
+            values: Arc::new(vec![value]),
+            count,
+            output: None,
+        }
+    }
+}
+
+count_constraint_impl!(Count);
+
+/// Enforces that `count` is the number of variables of the array taking a value of
+/// `values`.
+#[derive(Clone)]
+pub struct Among<View, Views>
+where
+    View: VariableContainerView,
+    Views: VariableContainerView,
+{
+    array: Views,
+    values: Arc<Vec<i32>>,
+    count: View,
+    output: Option<Vec<(VariableId, VariableState)>>,
+}
+
+impl<View, Views> Among<View, Views>
+where
+    View: VariableContainerView,
+    Views: VariableContainerView,
+{
+    pub fn new(array: Views, values: Vec<i32>, count: View) -> Among<View, Views> {
+        let mut values = values;
+        values.sort();
+        values.dedup();
+        Among {
+            array,
+            values: Arc::new(values),
+            count,
+            output: None,
+        }
+    }
+}
+
+count_constraint_impl!(Among);
+
+macro_rules! limit_constraint_impl {
+    ($(#[$attr:meta])* $name:ident; $bounds:expr) => {
+        $(#[$attr])*
+        #[derive(Clone)]
+        pub struct $name<Views>
+        where
+            Views: VariableContainerView,
+        {
+            array: Views,
+            values: Arc<Vec<i32>>,
+            limit: usize,
+            output: Option<Vec<(VariableId, VariableState)>>,
+        }
+
+        impl<Views> $name<Views>
+        where
+            Views: VariableContainerView,
+        {
+            pub fn new(limit: usize, array: Views, value: i32) -> $name<Views> {
+                $name {
+                    array,
+                    values: Arc::new(vec![value]),
+                    limit,
+                    output: None,
+                }
+            }
+        }
+
+        impl<Var, VarArray, Views, Handler> Constraint<Handler> for $name<Views>
+        where
+            Handler: VariablesHandler + VariableContainerHandler<VarArray, View = Views> + Clone,
+            Views: VariableContainerView<Container = VarArray> + 'static,
+            Var: PrunableDomain<Type = i32> + IterableDomain,
+            VarArray: Array<Variable = Var>,
+        {
+            fn box_clone(&self) -> Box<Constraint<Handler>> {
+                let ref_self: &$name<Views> = &self;
+                let cloned: $name<Views> = <$name<Views> as Clone>::clone(ref_self);
+
+                Box::new(cloned) as Box<Constraint<Handler>>
+            }
+            fn propagate(
+                &mut self,
+                variables_handler: &mut Handler,
+            ) -> Result<PropagationState, VariableError> {
+                let mut output = vec![];
+                self.output = None;
+
+                let array = variables_handler.get_mut(&self.array);
+                let (min, max) = $bounds(self.limit, array.len());
+                filter_occurrences(array, &self.values, min, max, &mut output)?;
+
+                if !output.is_empty() {
+                    self.output = Some(output);
+                    Ok(PropagationState::FixPoint)
+                } else {
+                    Ok(PropagationState::NoChange)
+                }
+            }
+            #[allow(unused)]
+            fn prepare(&mut self, states: Box<Iterator<Item = VariableId>>) {
+                // Do nothing
+            }
+            fn result(&mut self) -> Box<Iterator<Item = (VariableId, VariableState)>> {
+                use std::mem;
+                let mut res = None;
+                mem::swap(&mut self.output, &mut res);
+                match res {
+                    None => Box::new(vec![].into_iter()),
+                    Some(changes) => Box::new(changes.into_iter()),
+                }
+            }
+            fn dependencies(
+                &self,
+                variables_handler: &Handler,
+            ) -> Box<Iterator<Item = (VariableId, VariableState)>> {
+                let deps: Vec<_> = variables_handler
+                    .get(&self.array)
+                    .iter()
+                    .map(|var| (var.id(), VariableState::ValuesChange))
+                    .collect();
+                Box::new(deps.into_iter())
+            }
+            fn initialise(
+                &mut self,
+                variables_handler: &mut Handler,
+            ) -> Result<PropagationState, VariableError> {
+                self.propagate(variables_handler)
+            }
+        }
+    };
+}
+
+limit_constraint_impl!(
+    /// Enforces that at most `limit` variables of the array are equal to `value`.
+    AtMost; |limit, _| (0, limit)
+);
+limit_constraint_impl!(
+    /// Enforces that at least `limit` variables of the array are equal to `value`.
+    AtLeast; |limit, len| (limit, len)
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use branchers::values_selector::MinValueSelector;
+    use branchers::variables_selector::SequentialVariableSelector;
+    use model::tests::values;
+    use model::Model;
+    use search::Solver;
+    use variables::domains::AssignableDomain;
+    use variables::int_var::IntVarValues;
+    use variables::Variable;
+
+    // Counts the assignments of three variables in `0..=2` whose number of values in
+    // `values` satisfies `predicate`.
+    fn brute_force<Predicate>(values: &[i32], predicate: Predicate) -> usize
+    where
+        Predicate: Fn(usize) -> bool,
+    {
+        let mut count = 0;
+        for x in 0..3 {
+            for y in 0..3 {
+                for z in 0..3 {
+                    let inside = vec![x, y, z]
+                        .into_iter()
+                        .filter(|value| values.contains(value))
+                        .count();
+                    if predicate(inside) {
+                        count += 1;
+                    }
+                }
+            }
+        }
+        count
+    }
+
+    #[test]
+    fn count_solutions() {
+        let mut model = Model::new();
+        let vars: Vec<_> = (0..3).map(|_| model.new_int_var(0, 2).unwrap()).collect();
+        let n = model.new_int_var(1, 2).unwrap();
+        let array = model.new_array_of_refs(vars.clone()).unwrap();
+        model.post(Count::new(array, 1, n));
+        model.branch(
+            SequentialVariableSelector::new(vars.into_iter().chain(Some(n))).unwrap(),
+            MinValueSelector::new(),
+        );
+        let space = model.build().unwrap();
+        assert_eq!(
+            Solver::new(space).count_solutions(),
+            brute_force(&[1], |inside| inside == 1 || inside == 2)
+        );
+    }
+
+    #[test]
+    fn among_solutions() {
+        let mut model = Model::new();
+        let vars: Vec<_> = (0..3).map(|_| model.new_int_var(0, 2).unwrap()).collect();
+        let n = model.new_int_var(2, 2).unwrap();
+        let array = model.new_array_of_refs(vars.clone()).unwrap();
+        model.post(Among::new(array, vec![2, 0, 2], n));
+        model.branch(
+            SequentialVariableSelector::new(vars.into_iter().chain(Some(n))).unwrap(),
+            MinValueSelector::new(),
+        );
+        let space = model.build().unwrap();
+        assert_eq!(
+            Solver::new(space).count_solutions(),
+            brute_force(&[0, 2], |inside| inside == 2)
+        );
+    }
+
+    #[test]
+    fn limit_solutions() {
+        for limit in 0..4 {
+            let mut model = Model::new();
+            let vars: Vec<_> = (0..3).map(|_| model.new_int_var(0, 2).unwrap()).collect();
+            let array = model.new_array_of_refs(vars.clone()).unwrap();
+            model.post(AtMost::new(limit, array, 2));
+            let array = model.new_array_of_refs(vars.clone()).unwrap();
+            model.post(AtLeast::new(1, array, 0));
+            model.branch(
+                SequentialVariableSelector::new(vars.into_iter()).unwrap(),
+                MinValueSelector::new(),
+            );
+            let space = model.build().unwrap();
+            let expected = (0..3)
+                .flat_map(|x| {
+                    (0..3).flat_map(move |y| (0..3).map(move |z| vec![x, y, z]))
+                })
+                .filter(|values| {
+                    values.iter().filter(|&&value| value == 2).count() <= limit
+                        && values.contains(&0)
+                })
+                .count();
+            assert_eq!(Solver::new(space).count_solutions(), expected);
+        }
+    }
+
+    #[test]
+    fn count_prunes() {
+        let mut model = Model::new();
+        let x = model.new_int_var(1, 1).unwrap();
+        let y = model.new_int_var(0, 2).unwrap();
+        let z = model.new_int_var(0, 2).unwrap();
+        let n = model.new_int_var(0, 1).unwrap();
+        let array = model.new_array_of_refs(vec![x, y, z]).unwrap();
+        model.post(Count::new(array, 1, n));
+        let mut space = model.build().unwrap();
+        space.run().unwrap();
+        // `x` is equal to the value, so no other variable can be.
+        assert_eq!(space.get_variable(&n).value(), Some(1));
+        assert_eq!(values(&space, &y), vec![0, 2]);
+        assert_eq!(values(&space, &z), vec![0, 2]);
+
+        let mut model = Model::new();
+        let x = model.new_int_var(0, 2).unwrap();
+        let y = model.new_int_var(0, 2).unwrap();
+        let n = model.new_int_var(0, 2).unwrap();
+        let array = model.new_array_of_refs(vec![x, y]).unwrap();
+        model.post(Count::new(array, 1, n));
+        let mut space = model.build().unwrap();
+        space.run().unwrap();
+        {
+            let var: &mut IntVarValues = space.variables.get_mut(&n);
+            var.set_value(2).unwrap();
+        }
+        space.run().unwrap();
+        assert_eq!(space.get_variable(&x).value(), Some(1));
+        assert_eq!(space.get_variable(&y).value(), Some(1));
+    }
+
+    #[test]
+    fn limit_prunes() {
+        let mut model = Model::new();
+        let x = model.new_int_var(0, 0).unwrap();
+        let y = model.new_int_var(0, 2).unwrap();
+        let z = model.new_int_var(0, 2).unwrap();
+        let array = model.new_array_of_refs(vec![x, y, z]).unwrap();
+        model.post(AtMost::new(1, array, 0));
+        let mut space = model.build().unwrap();
+        space.run().unwrap();
+        assert_eq!(values(&space, &y), vec![1, 2]);
+        assert_eq!(values(&space, &z), vec![1, 2]);
+
+        let mut model = Model::new();
+        let x = model.new_int_var(1, 2).unwrap();
+        let y = model.new_int_var(0, 2).unwrap();
+        let array = model.new_array_of_refs(vec![x, y]).unwrap();
+        model.post(AtLeast::new(1, array, 0));
+        let mut space = model.build().unwrap();
+        space.run().unwrap();
+        assert_eq!(space.get_variable(&y).value(), Some(0));
+    }
+
+    #[test]
+    fn count_infeasible() {
+        let mut model = Model::new();
+        let x = model.new_int_var(1, 1).unwrap();
+        let y = model.new_int_var(1, 1).unwrap();
+        let n = model.new_int_var(0, 1).unwrap();
+        let array = model.new_array_of_refs(vec![x, y]).unwrap();
+        model.post(Count::new(array, 1, n));
+        assert!(model.build().is_err());
+
+        let mut model = Model::new();
+        let x = model.new_int_var(0, 1).unwrap();
+        let y = model.new_int_var(2, 3).unwrap();
+        let array = model.new_array_of_refs(vec![x, y]).unwrap();
+        model.post(AtLeast::new(2, array, 0));
+        assert!(model.build().is_err());
+    }
+}
@@ -0,0 +1,476 @@
+use constraints::{to_i32, Constraint, PropagationState};
+use std::cmp;
+use std::sync::Arc;
+use variables::domains::OrderedDomain;
+use variables::handlers::{
+    VariableContainerHandler, VariableContainerView, VariablesHandler,
+};
+use variables::{Array, VariableError, VariableId, VariableState};
+
+// The times are computed in `i64`, so the completion times and the mirrored tasks
+// do not overflow near the bounds of `i32`.
+#[derive(Clone, Copy, Debug)]
+struct Task {
+    est: i64,
+    lct: i64,
+    duration: i64,
+    demand: i64,
+}
+
+impl Task {
+    fn lst(&self) -> i64 {
+        self.lct - self.duration
+    }
+
+    fn ect(&self) -> i64 {
+        self.est + self.duration
+    }
+
+    fn energy(&self) -> i64 {
+        self.duration * self.demand
+    }
+
+    // The task seen with the time reversed, so the filtering rules of the earliest
+    // start times also filter the latest completion times.
+    fn mirror(&self) -> Task {
+        Task {
+            est: -self.lct,
+            lct: -self.est,
+            duration: self.duration,
+            demand: self.demand,
+        }
+    }
+}
+
+// Returns the earliest start times deduced from the compulsory parts of the other
+// tasks, i.e. the intervals `[lst, ect)` where the tasks run whatever their start.
+fn time_tabling(tasks: &[Task], capacity: i64) -> Vec<i64> {
+    tasks
+        .iter()
+        .enumerate()
+        .map(|(idx, task)| {
+            let mut events = vec![];
+            for (other, part) in tasks.iter().enumerate() {
+                if other != idx && part.lst() < part.ect() {
+                    events.push((part.lst(), part.demand));
+                    events.push((part.ect(), -part.demand));
+                }
+            }
+            events.sort();
+
+            // The profile is swept by increasing time, so pushing the task after a
+            // conflicting segment never has to look back.
+            let mut est = task.est;
+            let mut height = 0;
+            for (pos, &(time, delta)) in events.iter().enumerate() {
+                height += delta;
+                let end = match events.get(pos + 1) {
+                    Some(&(next, _)) => next,
+                    None => break,
+                };
+                if time < end
+                    && height + task.demand > capacity
+                    && time < est + task.duration
+                    && end > est
+                {
+                    est = end;
+                }
+            }
+            est
+        })
+        .collect()
+}
+
+// Returns the earliest start times deduced by the edge-finding rule: if a set of
+// tasks `omega` and a task `i` can not all end before `lct(omega)`, then `i` ends
+// after all the tasks of `omega`. The sets considered are the task intervals, the
+// tasks which windows are included in `[est, lct)` for an earliest start time and a
+// latest completion time of the tasks.
+fn edge_finding(tasks: &[Task], capacity: i64) -> Result<Vec<i64>, VariableError> {
+    let mut ests: Vec<_> = tasks.iter().map(|task| task.est).collect();
+    let mut lcts: Vec<_> = tasks.iter().map(|task| task.lct).collect();
+    ests.sort();
+    ests.dedup();
+    lcts.sort();
+    lcts.dedup();
+
+    // energies[a][b] is the energy of the tasks included in `[ests[a], lcts[b])`.
+    let mut energies = vec![vec![0i64; lcts.len()]; ests.len()];
+    for task in tasks.iter() {
+        for (a, &est) in ests.iter().enumerate() {
+            if est > task.est {
+                break;
+            }
+            for (b, &lct) in lcts.iter().enumerate().rev() {
+                if lct < task.lct {
+                    break;
+                }
+                energies[a][b] += task.energy();
+            }
+        }
+    }
+    for (a, &est) in ests.iter().enumerate() {
+        for (b, &lct) in lcts.iter().enumerate() {
+            if est < lct && energies[a][b] > capacity * (lct - est) {
+                return Err(VariableError::DomainWipeout);
+            }
+        }
+    }
+
+    let mut res = vec![];
+    for task in tasks.iter() {
+        let demand = task.demand;
+        let mut est = task.est;
+        // min_est[b] is the smallest start of a detected set ending after lcts[b], so
+        // the task intervals included in a detected set are easy to enumerate.
+        let mut min_est = vec![i64::MAX; lcts.len()];
+        for (b, &lct) in lcts.iter().enumerate() {
+            if lct >= task.lct {
+                break;
+            }
+            for (a, &omega_est) in ests.iter().enumerate() {
+                let energy = energies[a][b];
+                let start = cmp::min(omega_est, task.est);
+                if omega_est < lct
+                    && energy > 0
+                    && energy + task.energy() > capacity * (lct - start)
+                {
+                    for bound in min_est[..b + 1].iter_mut() {
+                        *bound = cmp::min(*bound, omega_est);
+                    }
+                }
+            }
+        }
+        for (b, &lct) in lcts.iter().enumerate() {
+            if min_est[b] == i64::MAX {
+                continue;
+            }
+            for (a, &omega_est) in ests.iter().enumerate() {
+                if omega_est < min_est[b] || omega_est >= lct {
+                    continue;
+                }
+                let rest = energies[a][b] - (capacity - demand) * (lct - omega_est);
+                if rest > 0 {
+                    est = cmp::max(est, omega_est + (rest + demand - 1) / demand);
+                }
+            }
+        }
+        res.push(est);
+    }
+    Ok(res)
+}
+
+/// Enforces that the tasks never use more than `capacity` units of a resource. The
+/// task `i` starts at `starts[i]`, runs during `durations[i]` and uses `demands[i]`
+/// units of the resource.
+///
+/// The bounds of the starts are filtered with time-tabling, which reasons on the
+/// compulsory parts of the tasks, and edge-finding, which reasons on the energy of
+/// sets of tasks.
+#[derive(Clone)]
+pub struct Cumulative<Views>
+where
+    Views: VariableContainerView,
+{
+    starts: Views,
+    durations: Arc<Vec<i32>>,
+    demands: Arc<Vec<i32>>,
+    capacity: i32,
+    output: Option<Vec<(VariableId, VariableState)>>,
+}
+
+impl<Views> Cumulative<Views>
+where
+    Views: VariableContainerView,
+{
+    pub fn new(
+        starts: Views,
+        durations: Vec<i32>,
+        demands: Vec<i32>,
+        capacity: i32,
+    ) -> Cumulative<Views> {
+        Cumulative {
+            starts,
+            durations: Arc::new(durations),
+            demands: Arc::new(demands),
+            capacity,
+            output: None,
+        }
+    }
+
+    // The tasks without duration or demand do not use the resource.
+    fn tasks<Var, VarArray>(&self, starts: &VarArray) -> Vec<(usize, Task)>
+    where
+        Var: OrderedDomain<Type = i32>,
+        VarArray: Array<Variable = Var>,
+    {
+        starts
+            .iter()
+            .zip(self.durations.iter().zip(self.demands.iter()))
+            .enumerate()
+            .filter(|&(_, (_, (&duration, &demand)))| duration > 0 && demand > 0)
+            .map(|(idx, (start, (&duration, &demand)))| {
+                let task = Task {
+                    est: i64::from(start.unchecked_min()),
+                    lct: i64::from(start.unchecked_max()) + i64::from(duration),
+                    duration: i64::from(duration),
+                    demand: i64::from(demand),
+                };
+                (idx, task)
+            })
+            .collect()
+    }
+
+    fn filter<Var, VarArray>(
+        &self,
+        starts: &mut VarArray,
+        output: &mut Vec<(VariableId, VariableState)>,
+    ) -> Result<(), VariableError>
+    where
+        Var: OrderedDomain<Type = i32>,
+        VarArray: Array<Variable = Var>,
+    {
+        loop {
+            let (indexes, tasks): (Vec<_>, Vec<_>) =
+                self.tasks(starts).into_iter().unzip();
+            let capacity = i64::from(self.capacity);
+            if tasks.iter().any(|task| task.demand > capacity) {
+                return Err(VariableError::DomainWipeout);
+            }
+            let mirrored: Vec<_> = tasks.iter().map(|task| task.mirror()).collect();
+            let ests = time_tabling(&tasks, capacity)
+                .into_iter()
+                .zip(edge_finding(&tasks, capacity)?)
+                .map(|(lhs, rhs)| cmp::max(lhs, rhs));
+            let lcts = time_tabling(&mirrored, capacity)
+                .into_iter()
+                .zip(edge_finding(&mirrored, capacity)?)
+                .map(|(lhs, rhs)| -cmp::max(lhs, rhs));
+
+            let mut changed = false;
+            for (((idx, task), est), lct) in
+                indexes.into_iter().zip(tasks).zip(ests).zip(lcts)
+            {
+                let start = starts.get_unchecked_mut(idx);
+                for state in [
+                    start.weak_lowerbound(to_i32(est))?,
+                    start.weak_upperbound(to_i32(lct - task.duration))?,
+                ] {
+                    match state {
+                        VariableState::NoChange => {}
+                        state => {
+                            changed = true;
+                            output.push((start.id(), state));
+                        }
+                    }
+                }
+            }
+            if !changed {
+                return Ok(());
+            }
+        }
+    }
+}
+
+impl<Var, VarArray, Views, Handler> Constraint<Handler> for Cumulative<Views>
+where
+    Handler: VariablesHandler + VariableContainerHandler<VarArray, View = Views> + Clone,
+    Views: VariableContainerView<Container = VarArray> + 'static,
+    Var: OrderedDomain<Type = i32>,
+    VarArray: Array<Variable = Var>,
+{
+    fn box_clone(&self) -> Box<Constraint<Handler>> {
+        let ref_self: &Cumulative<Views> = self;
+        let cloned: Cumulative<Views> = <Cumulative<Views> as Clone>::clone(ref_self);
+
+        Box::new(cloned) as Box<Constraint<Handler>>
+    }
+    fn propagate(
+        &mut self,
+        variables_handler: &mut Handler,
+    ) -> Result<PropagationState, VariableError> {
+        let mut output = vec![];
+        self.output = None;
+
+        self.filter(variables_handler.get_mut(&self.starts), &mut output)?;
+
+        if !output.is_empty() {
+            self.output = Some(output);
+            Ok(PropagationState::FixPoint)
+        } else {
+            Ok(PropagationState::NoChange)
+        }
+    }
+    #[allow(unused)]
+    fn prepare(&mut self, states: Box<Iterator<Item = VariableId>>) {
+        // Do nothing
+    }
+    fn result(&mut self) -> Box<Iterator<Item = (VariableId, VariableState)>> {
+        use std::mem;
+        let mut res = None;
+        mem::swap(&mut self.output, &mut res);
+        match res {
+            None => Box::new(vec![].into_iter()),
+            Some(changes) => Box::new(changes.into_iter()),
+        }
+    }
+    fn dependencies(
+        &self,
+        variables_handler: &Handler,
+    ) -> Box<Iterator<Item = (VariableId, VariableState)>> {
+        let deps: Vec<_> = variables_handler
+            .get(&self.starts)
+            .iter()
+            .map(|var| (var.id(), VariableState::BoundsChange))
+            .collect();
+        Box::new(deps.into_iter())
+    }
+    fn initialise(
+        &mut self,
+        variables_handler: &mut Handler,
+    ) -> Result<PropagationState, VariableError> {
+        let len = variables_handler.get(&self.starts).len();
+        assert!(
+            self.durations.len() == len && self.demands.len() == len,
+            "Error Cumulative durations, demands and starts of different lengths."
+        );
+        self.propagate(variables_handler)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use branchers::values_selector::MinValueSelector;
+    use branchers::variables_selector::SequentialVariableSelector;
+    use model::Model;
+    use search::Solver;
+    use variables::int_var::IntVarValues;
+    use variables::Variable;
+
+    fn brute_force(
+        horizons: &[i32],
+        durations: &[i32],
+        demands: &[i32],
+        capacity: i32,
+    ) -> usize {
+        let mut count = 0;
+        let mut starts = vec![0; horizons.len()];
+        loop {
+            let end = (0..starts.len())
+                .map(|idx| starts[idx] + durations[idx])
+                .max();
+            let feasible = (0..end.unwrap_or(0)).all(|time| {
+                let used: i32 = (0..starts.len())
+                    .filter(|&idx| {
+                        starts[idx] <= time && time < starts[idx] + durations[idx]
+                    })
+                    .map(|idx| demands[idx])
+                    .sum();
+                used <= capacity
+            });
+            if feasible {
+                count += 1;
+            }
+            // Next combination of starts.
+            let mut pos = 0;
+            while pos < starts.len() && starts[pos] == horizons[pos] {
+                starts[pos] = 0;
+                pos += 1;
+            }
+            if pos == starts.len() {
+                return count;
+            }
+            starts[pos] += 1;
+        }
+    }
+
+    #[test]
+    fn solutions() {
+        // A small RCPSP: 4 tasks sharing 3 units of a resource.
+        let horizons = [3, 4, 3, 5];
+        let durations = vec![2, 1, 3, 2];
+        let demands = vec![2, 1, 1, 2];
+        let mut model = Model::new();
+        let starts: Vec<_> = horizons
+            .iter()
+            .map(|&horizon| model.new_int_var(0, horizon).unwrap())
+            .collect();
+        let array = model.new_array_of_refs(starts.clone()).unwrap();
+        model.post(Cumulative::new(
+            array,
+            durations.clone(),
+            demands.clone(),
+            3,
+        ));
+        model.branch(
+            SequentialVariableSelector::new(starts.into_iter()).unwrap(),
+            MinValueSelector::new(),
+        );
+        let space = model.build().unwrap();
+        let expected = brute_force(&horizons, &durations, &demands, 3);
+        assert!(expected > 0);
+        assert_eq!(Solver::new(space).count_solutions(), expected);
+    }
+
+    #[test]
+    fn time_tabling_prunes() {
+        // The first task uses the whole resource during [2, 5).
+        let mut model = Model::new();
+        let x = model.new_int_var(2, 2).unwrap();
+        let y = model.new_int_var(1, 8).unwrap();
+        let array = model.new_array_of_refs(vec![x, y]).unwrap();
+        model.post(Cumulative::new(array, vec![3, 2], vec![2, 1], 2));
+        let mut space = model.build().unwrap();
+        space.run().unwrap();
+        let var: &IntVarValues = space.get_variable(&y);
+        assert_eq!(var.unchecked_min(), 5);
+    }
+
+    #[test]
+    fn edge_finding_prunes() {
+        // x and y fill [0, 4), so z starts after them.
+        let mut model = Model::new();
+        let x = model.new_int_var(0, 2).unwrap();
+        let y = model.new_int_var(0, 2).unwrap();
+        let z = model.new_int_var(0, 6).unwrap();
+        let array = model.new_array_of_refs(vec![x, y, z]).unwrap();
+        model.post(Cumulative::new(array, vec![2, 2, 2], vec![1, 1, 1], 1));
+        let mut space = model.build().unwrap();
+        space.run().unwrap();
+        let var: &IntVarValues = space.get_variable(&z);
+        assert_eq!(var.unchecked_min(), 4);
+    }
+
+    #[test]
+    fn infeasible_without_search() {
+        // The energy of the tasks exceeds the capacity of [0, 4).
+        let mut model = Model::new();
+        let starts: Vec<_> = (0..3).map(|_| model.new_int_var(0, 2).unwrap()).collect();
+        let array = model.new_array_of_refs(starts).unwrap();
+        model.post(Cumulative::new(array, vec![2, 2, 2], vec![1, 1, 1], 1));
+        assert!(model.build().is_err());
+        // A task uses more than the capacity.
+        let mut model = Model::new();
+        let x = model.new_int_var(0, 2).unwrap();
+        let array = model.new_array_of_refs(vec![x]).unwrap();
+        model.post(Cumulative::new(array, vec![1], vec![3], 2));
+        assert!(model.build().is_err());
+    }
+
+    #[test]
+    fn large_times() {
+        let max = i32::MAX;
+        let mut model = Model::new();
+        let x = model
+            .new_int_var_from_values(vec![max - 30, max - 3])
+            .unwrap();
+        let y = model.new_int_var(max - 30, max - 30).unwrap();
+        let array = model.new_array_of_refs(vec![x, y]).unwrap();
+        model.post(Cumulative::new(array, vec![10, 10], vec![1, 1], 1));
+        let mut space = model.build().unwrap();
+        space.run().unwrap();
+        let var: &IntVarValues = space.get_variable(&x);
+        assert_eq!(var.value(), Some(max - 3));
+    }
+}
@@ -0,0 +1,416 @@
+use constraints::{to_i32, Constraint, PropagationState};
+use std::cmp;
+use std::sync::Arc;
+use variables::domains::OrderedDomain;
+use variables::handlers::{
+    VariableContainerHandler, VariableContainerView, VariablesHandler,
+};
+use variables::{Array, VariableError, VariableId, VariableState};
+
+// The times are computed in `i64`, so the completion times and the mirrored tasks
+// do not overflow near the bounds of `i32`.
+#[derive(Clone, Copy, Debug)]
+struct Task {
+    est: i64,
+    lct: i64,
+    duration: i64,
+}
+
+impl Task {
+    fn lst(&self) -> i64 {
+        self.lct - self.duration
+    }
+
+    fn ect(&self) -> i64 {
+        self.est + self.duration
+    }
+
+    // The task seen with the time reversed, so the filtering rules of the earliest
+    // start times also filter the latest completion times.
+    fn mirror(&self) -> Task {
+        Task {
+            est: -self.lct,
+            lct: -self.est,
+            duration: self.duration,
+        }
+    }
+}
+
+// Returns the earliest completion time of a set of tasks processed one at a time.
+fn ect<'a, Tasks>(tasks: Tasks) -> i64
+where
+    Tasks: IntoIterator<Item = &'a Task>,
+{
+    let mut tasks: Vec<_> = tasks.into_iter().collect();
+    tasks.sort_by_key(|task| task.est);
+    tasks.into_iter().fold(i64::MIN, |time, task| {
+        cmp::max(time, task.est) + task.duration
+    })
+}
+
+// Returns the latest start time of a set of tasks processed one at a time.
+fn lst<'a, Tasks>(tasks: Tasks) -> i64
+where
+    Tasks: IntoIterator<Item = &'a Task>,
+{
+    let mut tasks: Vec<_> = tasks.into_iter().collect();
+    tasks.sort_by_key(|task| -task.lct);
+    tasks.into_iter().fold(i64::MAX, |time, task| {
+        cmp::min(time, task.lct) - task.duration
+    })
+}
+
+// Fails if the tasks ending before a latest completion time can not be processed
+// before it.
+fn overload_checking(tasks: &[Task]) -> Result<(), VariableError> {
+    for task in tasks.iter() {
+        let omega = tasks.iter().filter(|other| other.lct <= task.lct);
+        if ect(omega) > task.lct {
+            return Err(VariableError::DomainWipeout);
+        }
+    }
+    Ok(())
+}
+
+// A task `j` has to precede `i` if `i` can not end before `j` starts, so `i` starts
+// after the earliest completion of these tasks.
+fn detectable_precedences(tasks: &[Task]) -> Vec<i64> {
+    tasks
+        .iter()
+        .enumerate()
+        .map(|(idx, task)| {
+            let omega = tasks
+                .iter()
+                .enumerate()
+                .filter(|&(other, before)| other != idx && task.ect() > before.lst())
+                .map(|(_, before)| before);
+            cmp::max(task.est, ect(omega))
+        })
+        .collect()
+}
+
+// A task `i` can not start first among the tasks ending after its start if they can
+// not all start after its end, so it starts after the end of one of them.
+fn not_first(tasks: &[Task]) -> Vec<i64> {
+    tasks
+        .iter()
+        .enumerate()
+        .map(|(idx, task)| {
+            let omega: Vec<_> = tasks
+                .iter()
+                .enumerate()
+                .filter(|&(other, after)| other != idx && after.ect() > task.est)
+                .map(|(_, after)| after)
+                .collect();
+            if !omega.is_empty() && lst(omega.iter().cloned()) < task.ect() {
+                let first_end = omega.iter().map(|after| after.ect()).min().unwrap();
+                cmp::max(task.est, first_end)
+            } else {
+                task.est
+            }
+        })
+        .collect()
+}
+
+// If a task `i` can not be processed with the tasks ending before a latest completion
+// time `lct` without ending after it, then `i` is processed after all these tasks.
+fn edge_finding(tasks: &[Task]) -> Vec<i64> {
+    let mut ests: Vec<_> = tasks.iter().map(|task| task.est).collect();
+    for cut in tasks.iter() {
+        let omega: Vec<_> = tasks.iter().filter(|task| task.lct <= cut.lct).collect();
+        let omega_ect = ect(omega.iter().cloned());
+        for (idx, task) in tasks.iter().enumerate() {
+            if task.lct <= cut.lct {
+                continue;
+            }
+            let with_task = ect(omega.iter().cloned().chain(Some(task)));
+            if with_task > cut.lct {
+                ests[idx] = cmp::max(ests[idx], omega_ect);
+            }
+        }
+    }
+    ests
+}
+
+// Returns the earliest start times deduced by all the filtering rules.
+fn filter_ests(tasks: &[Task]) -> Result<Vec<i64>, VariableError> {
+    overload_checking(tasks)?;
+    let ests = detectable_precedences(tasks)
+        .into_iter()
+        .zip(not_first(tasks))
+        .zip(edge_finding(tasks))
+        .map(|((lhs, mid), rhs)| cmp::max(lhs, cmp::max(mid, rhs)))
+        .collect();
+    Ok(ests)
+}
+
+/// Enforces that the tasks are processed one at a time. The task `i` starts at
+/// `starts[i]` and runs during `durations[i]`.
+///
+/// The bounds of the starts are filtered with overload checking, detectable
+/// precedences, edge-finding and not-first/not-last rules. The rules are stated on
+/// the earliest start times and applied to the latest completion times by reversing
+/// the time.
+#[derive(Clone)]
+pub struct Disjunctive<Views>
+where
+    Views: VariableContainerView,
+{
+    starts: Views,
+    durations: Arc<Vec<i32>>,
+    output: Option<Vec<(VariableId, VariableState)>>,
+}
+
+impl<Views> Disjunctive<Views>
+where
+    Views: VariableContainerView,
+{
+    pub fn new(starts: Views, durations: Vec<i32>) -> Disjunctive<Views> {
+        Disjunctive {
+            starts,
+            durations: Arc::new(durations),
+            output: None,
+        }
+    }
+
+    // The tasks without duration do not use the resource.
+    fn tasks<Var, VarArray>(&self, starts: &VarArray) -> Vec<(usize, Task)>
+    where
+        Var: OrderedDomain<Type = i32>,
+        VarArray: Array<Variable = Var>,
+    {
+        starts
+            .iter()
+            .zip(self.durations.iter())
+            .enumerate()
+            .filter(|&(_, (_, &duration))| duration > 0)
+            .map(|(idx, (start, &duration))| {
+                let task = Task {
+                    est: i64::from(start.unchecked_min()),
+                    lct: i64::from(start.unchecked_max()) + i64::from(duration),
+                    duration: i64::from(duration),
+                };
+                (idx, task)
+            })
+            .collect()
+    }
+
+    fn filter<Var, VarArray>(
+        &self,
+        starts: &mut VarArray,
+        output: &mut Vec<(VariableId, VariableState)>,
+    ) -> Result<(), VariableError>
+    where
+        Var: OrderedDomain<Type = i32>,
+        VarArray: Array<Variable = Var>,
+    {
+        loop {
+            let (indexes, tasks): (Vec<_>, Vec<_>) =
+                self.tasks(starts).into_iter().unzip();
+            let mirrored: Vec<_> = tasks.iter().map(|task| task.mirror()).collect();
+            let ests = filter_ests(&tasks)?;
+            let lcts = filter_ests(&mirrored)?.into_iter().map(|est| -est);
+
+            let mut changed = false;
+            for (((idx, task), est), lct) in
+                indexes.into_iter().zip(tasks).zip(ests).zip(lcts)
+            {
+                let start = starts.get_unchecked_mut(idx);
+                for state in [
+                    start.weak_lowerbound(to_i32(est))?,
+                    start.weak_upperbound(to_i32(lct - task.duration))?,
+                ] {
+                    match state {
+                        VariableState::NoChange => {}
+                        state => {
+                            changed = true;
+                            output.push((start.id(), state));
+                        }
+                    }
+                }
+            }
+            if !changed {
+                return Ok(());
+            }
+        }
+    }
+}
+
+impl<Var, VarArray, Views, Handler> Constraint<Handler> for Disjunctive<Views>
+where
+    Handler: VariablesHandler + VariableContainerHandler<VarArray, View = Views> + Clone,
+    Views: VariableContainerView<Container = VarArray> + 'static,
+    Var: OrderedDomain<Type = i32>,
+    VarArray: Array<Variable = Var>,
+{
+    fn box_clone(&self) -> Box<Constraint<Handler>> {
+        let ref_self: &Disjunctive<Views> = self;
+        let cloned: Disjunctive<Views> = <Disjunctive<Views> as Clone>::clone(ref_self);
+
+        Box::new(cloned) as Box<Constraint<Handler>>
+    }
+    fn propagate(
+        &mut self,
+        variables_handler: &mut Handler,
+    ) -> Result<PropagationState, VariableError> {
+        let mut output = vec![];
+        self.output = None;
+
+        self.filter(variables_handler.get_mut(&self.starts), &mut output)?;
+
+        if !output.is_empty() {
+            self.output = Some(output);
+            Ok(PropagationState::FixPoint)
+        } else {
+            Ok(PropagationState::NoChange)
+        }
+    }
+    #[allow(unused)]
+    fn prepare(&mut self, states: Box<Iterator<Item = VariableId>>) {
+        // Do nothing
+    }
+    fn result(&mut self) -> Box<Iterator<Item = (VariableId, VariableState)>> {
+        use std::mem;
+        let mut res = None;
+        mem::swap(&mut self.output, &mut res);
+        match res {
+            None => Box::new(vec![].into_iter()),
+            Some(changes) => Box::new(changes.into_iter()),
+        }
+    }
+    fn dependencies(
+        &self,
+        variables_handler: &Handler,
+    ) -> Box<Iterator<Item = (VariableId, VariableState)>> {
+        let deps: Vec<_> = variables_handler
+            .get(&self.starts)
+            .iter()
+            .map(|var| (var.id(), VariableState::BoundsChange))
+            .collect();
+        Box::new(deps.into_iter())
+    }
+    fn initialise(
+        &mut self,
+        variables_handler: &mut Handler,
+    ) -> Result<PropagationState, VariableError> {
+        let len = variables_handler.get(&self.starts).len();
+        assert!(
+            self.durations.len() == len,
+            "Error Disjunctive durations and starts of different lengths."
+        );
+        self.propagate(variables_handler)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use branchers::values_selector::MinValueSelector;
+    use branchers::variables_selector::SequentialVariableSelector;
+    use constraints::arithmetic::{AddConstant, LessOrEqualThan};
+    use model::Model;
+    use search::Solver;
+    use variables::int_var::IntVarValues;
+    use variables::Variable;
+
+    fn overlap(start: i32, duration: i32, other: i32, other_duration: i32) -> bool {
+        start < other + other_duration && other < start + duration
+    }
+
+    #[test]
+    fn job_shop() {
+        // Two jobs on two machines with a makespan of at most 4: the first job runs
+        // 2 on m0 then 1 on m1, the second one 2 on m1 then 1 on m0.
+        let mut model = Model::new();
+        let a0 = model.new_int_var(0, 2).unwrap();
+        let a1 = model.new_int_var(0, 3).unwrap();
+        let b1 = model.new_int_var(0, 2).unwrap();
+        let b0 = model.new_int_var(0, 3).unwrap();
+        // The second task of a job starts once the first one is over.
+        let end_a0 = model.new_int_var(2, 4).unwrap();
+        model.post(AddConstant::new(end_a0, a0, 2));
+        model.post(LessOrEqualThan::new(end_a0, a1));
+        let end_b1 = model.new_int_var(2, 4).unwrap();
+        model.post(AddConstant::new(end_b1, b1, 2));
+        model.post(LessOrEqualThan::new(end_b1, b0));
+        let machine = model.new_array_of_refs(vec![a0, b0]).unwrap();
+        model.post(Disjunctive::new(machine, vec![2, 1]));
+        let machine = model.new_array_of_refs(vec![a1, b1]).unwrap();
+        model.post(Disjunctive::new(machine, vec![1, 2]));
+        model.branch(
+            SequentialVariableSelector::new(vec![a0, a1, b1, b0].into_iter()).unwrap(),
+            MinValueSelector::new(),
+        );
+        let space = model.build().unwrap();
+
+        let mut expected = 0;
+        for a0 in 0..3 {
+            for a1 in a0 + 2..4 {
+                for b1 in 0..3 {
+                    for b0 in b1 + 2..4 {
+                        if !overlap(a0, 2, b0, 1) && !overlap(a1, 1, b1, 2) {
+                            expected += 1;
+                        }
+                    }
+                }
+            }
+        }
+        assert!(expected > 0);
+        assert_eq!(Solver::new(space).count_solutions(), expected);
+    }
+
+    #[test]
+    fn detectable_precedences_prune() {
+        // x runs during [1, 3) whatever its start, so y starts after it.
+        let mut model = Model::new();
+        let x = model.new_int_var(0, 1).unwrap();
+        let y = model.new_int_var(0, 10).unwrap();
+        let array = model.new_array_of_refs(vec![x, y]).unwrap();
+        model.post(Disjunctive::new(array, vec![3, 2]));
+        let mut space = model.build().unwrap();
+        space.run().unwrap();
+        let var: &IntVarValues = space.get_variable(&y);
+        assert_eq!(var.unchecked_min(), 3);
+    }
+
+    #[test]
+    fn edge_finding_prunes() {
+        // x and y fill [0, 4), so z starts after them.
+        let mut model = Model::new();
+        let x = model.new_int_var(0, 2).unwrap();
+        let y = model.new_int_var(0, 2).unwrap();
+        let z = model.new_int_var(0, 6).unwrap();
+        let array = model.new_array_of_refs(vec![x, y, z]).unwrap();
+        model.post(Disjunctive::new(array, vec![2, 2, 2]));
+        let mut space = model.build().unwrap();
+        space.run().unwrap();
+        let var: &IntVarValues = space.get_variable(&z);
+        assert_eq!(var.unchecked_min(), 4);
+    }
+
+    #[test]
+    fn infeasible_without_search() {
+        // Three tasks of 2 can not fit in [0, 5).
+        let mut model = Model::new();
+        let starts: Vec<_> = (0..3).map(|_| model.new_int_var(0, 3).unwrap()).collect();
+        let array = model.new_array_of_refs(starts).unwrap();
+        model.post(Disjunctive::new(array, vec![2, 2, 2]));
+        assert!(model.build().is_err());
+    }
+
+    #[test]
+    fn large_times() {
+        let max = i32::MAX;
+        let mut model = Model::new();
+        let x = model
+            .new_int_var_from_values(vec![max - 30, max - 3])
+            .unwrap();
+        let y = model.new_int_var(max - 30, max - 30).unwrap();
+        let array = model.new_array_of_refs(vec![x, y]).unwrap();
+        model.post(Disjunctive::new(array, vec![10, 10]));
+        let mut space = model.build().unwrap();
+        space.run().unwrap();
+        let var: &IntVarValues = space.get_variable(&x);
+        assert_eq!(var.value(), Some(max - 3));
+    }
+}
@@ -0,0 +1,396 @@
+use constraints::{Constraint, PropagationState};
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use variables::domains::{IterableDomain, PrunableDomain};
+use variables::handlers::{
+    VariableContainerHandler, VariableContainerView, VariablesHandler,
+};
+use variables::{Array, VariableError, VariableId, VariableState};
+
+/// Enforces `value = values[index]` where `values` is an array of constants. The
+/// positions of the array start at 0.
+#[derive(Clone)]
+pub struct ConstantElement<View>
+where
+    View: VariableContainerView,
+{
+    value: View,
+    index: View,
+    values: Arc<Vec<i32>>,
+    output: Option<Vec<(VariableId, VariableState)>>,
+}
+
+impl<View> ConstantElement<View>
+where
+    View: VariableContainerView,
+{
+    pub fn new(value: View, index: View, values: Vec<i32>) -> ConstantElement<View> {
+        ConstantElement {
+            value,
+            index,
+            values: Arc::new(values),
+            output: None,
+        }
+    }
+}
+
+impl<Var, View, Handler> Constraint<Handler> for ConstantElement<View>
+where
+    Handler: VariablesHandler + VariableContainerHandler<Var, View = View> + Clone,
+    View: VariableContainerView<Container = Var> + 'static,
+    Var: PrunableDomain<Type = i32> + IterableDomain,
+{
+    fn box_clone(&self) -> Box<Constraint<Handler>> {
+        let ref_self: &ConstantElement<View> = self;
+        let cloned: ConstantElement<View> =
+            <ConstantElement<View> as Clone>::clone(ref_self);
+
+        Box::new(cloned) as Box<Constraint<Handler>>
+    }
+    fn propagate(
+        &mut self,
+        variables_handler: &mut Handler,
+    ) -> Result<PropagationState, VariableError> {
+        let mut output = vec![];
+        self.output = None;
+
+        unsafe {
+            let value: &mut Var =
+                unsafe_from_raw_point!(variables_handler.get_mut(&self.value));
+            let index: &mut Var =
+                unsafe_from_raw_point!(variables_handler.get_mut(&self.index));
+            let values = &self.values;
+
+            // An index is valid if its value belongs to the domain of `value`.
+            let domain: BTreeSet<_> = value.iter().collect();
+            let state = index.retains_if(|&idx| {
+                idx >= 0
+                    && (idx as usize) < values.len()
+                    && domain.contains(&values[idx as usize])
+            })?;
+            match state {
+                VariableState::NoChange => {}
+                state => {
+                    output.push((index.id(), state));
+                }
+            }
+            let domain: Vec<_> = index.iter().map(|idx| values[idx as usize]).collect();
+            let state = value.in_values(domain)?;
+            match state {
+                VariableState::NoChange => {}
+                state => {
+                    output.push((value.id(), state));
+                }
+            }
+        }
+
+        if !output.is_empty() {
+            self.output = Some(output);
+            Ok(PropagationState::FixPoint)
+        } else {
+            Ok(PropagationState::NoChange)
+        }
+    }
+    #[allow(unused)]
+    fn prepare(&mut self, states: Box<Iterator<Item = VariableId>>) {
+        // Do nothing
+    }
+    fn result(&mut self) -> Box<Iterator<Item = (VariableId, VariableState)>> {
+        use std::mem;
+        let mut res = None;
+        mem::swap(&mut self.output, &mut res);
+        match res {
+            None => Box::new(vec![].into_iter()),
+            Some(changes) => Box::new(changes.into_iter()),
+        }
+    }
+    fn dependencies(
+        &self,
+        variables: &Handler,
+    ) -> Box<Iterator<Item = (VariableId, VariableState)>> {
+        Box::new(
+            vec![
+                (variables.get(&self.value).id(), VariableState::ValuesChange),
+                (variables.get(&self.index).id(), VariableState::ValuesChange),
+            ]
+            .into_iter(),
+        )
+    }
+    fn initialise(
+        &mut self,
+        variables_handler: &mut Handler,
+    ) -> Result<PropagationState, VariableError> {
+        self.propagate(variables_handler)
+    }
+}
+
+/// Enforces `value = array[index]` where `array` is an array of variables. The
+/// positions of the array start at 0.
+#[derive(Clone)]
+pub struct Element<View, Views>
+where
+    View: VariableContainerView,
+    Views: VariableContainerView,
+{
+    value: View,
+    index: View,
+    array: Views,
+    output: Option<Vec<(VariableId, VariableState)>>,
+}
+
+impl<View, Views> Element<View, Views>
+where
+    View: VariableContainerView,
+    Views: VariableContainerView,
+{
+    pub fn new(value: View, index: View, array: Views) -> Element<View, Views> {
+        Element {
+            value,
+            index,
+            array,
+            output: None,
+        }
+    }
+}
+
+impl<Var, VarArray, View, Views, Handler> Constraint<Handler> for Element<View, Views>
+where
+    Handler: VariablesHandler
+        + VariableContainerHandler<Var, View = View>
+        + VariableContainerHandler<VarArray, View = Views>
+        + Clone,
+    View: VariableContainerView<Container = Var> + 'static,
+    Views: VariableContainerView<Container = VarArray> + 'static,
+    Var: PrunableDomain<Type = i32> + IterableDomain,
+    VarArray: Array<Variable = Var>,
+{
+    fn box_clone(&self) -> Box<Constraint<Handler>> {
+        let ref_self: &Element<View, Views> = self;
+        let cloned: Element<View, Views> =
+            <Element<View, Views> as Clone>::clone(ref_self);
+
+        Box::new(cloned) as Box<Constraint<Handler>>
+    }
+    fn propagate(
+        &mut self,
+        variables_handler: &mut Handler,
+    ) -> Result<PropagationState, VariableError> {
+        let mut output = vec![];
+        self.output = None;
+
+        unsafe {
+            let value: &mut Var = unsafe_from_raw_point!(
+                VariableContainerHandler::<Var>::get_mut(variables_handler, &self.value)
+            );
+            let index: &mut Var = unsafe_from_raw_point!(
+                VariableContainerHandler::<Var>::get_mut(variables_handler, &self.index)
+            );
+            let array: &mut VarArray =
+                unsafe_from_raw_point!(VariableContainerHandler::<VarArray>::get_mut(
+                    variables_handler,
+                    &self.array
+                ));
+
+            // An index is valid if the domain of the variable at this position
+            // intersects the domain of `value`.
+            let domain: BTreeSet<_> = value.iter().collect();
+            let len = array.len();
+            let state = index.retains_if(|&idx| {
+                idx >= 0
+                    && (idx as usize) < len
+                    && array
+                        .get_unchecked(idx as usize)
+                        .iter()
+                        .any(|val| domain.contains(&val))
+            })?;
+            match state {
+                VariableState::NoChange => {}
+                state => {
+                    output.push((index.id(), state));
+                }
+            }
+            let domain: BTreeSet<_> = index
+                .iter()
+                .flat_map(|idx| array.get_unchecked(idx as usize).iter())
+                .collect();
+            let state = value.in_values(domain)?;
+            match state {
+                VariableState::NoChange => {}
+                state => {
+                    output.push((value.id(), state));
+                }
+            }
+            // Once the index is known the variable at this position is equal to `value`,
+            // unless it is `value` itself.
+            if let Some(idx) = index.value() {
+                let var = array.get_unchecked_mut(idx as usize);
+                let (var_state, value_state) = if var.id() != value.id() {
+                    var.equal(value)?
+                } else {
+                    (VariableState::NoChange, VariableState::NoChange)
+                };
+                match var_state {
+                    VariableState::NoChange => {}
+                    state => {
+                        output.push((var.id(), state));
+                    }
+                }
+                match value_state {
+                    VariableState::NoChange => {}
+                    state => {
+                        output.push((value.id(), state));
+                    }
+                }
+            }
+        }
+
+        if !output.is_empty() {
+            self.output = Some(output);
+            Ok(PropagationState::FixPoint)
+        } else {
+            Ok(PropagationState::NoChange)
+        }
+    }
+    #[allow(unused)]
+    fn prepare(&mut self, states: Box<Iterator<Item = VariableId>>) {
+        // Do nothing
+    }
+    fn result(&mut self) -> Box<Iterator<Item = (VariableId, VariableState)>> {
+        use std::mem;
+        let mut res = None;
+        mem::swap(&mut self.output, &mut res);
+        match res {
+            None => Box::new(vec![].into_iter()),
+            Some(changes) => Box::new(changes.into_iter()),
+        }
+    }
+    fn dependencies(
+        &self,
+        variables: &Handler,
+    ) -> Box<Iterator<Item = (VariableId, VariableState)>> {
+        let value = VariableContainerHandler::<Var>::get(variables, &self.value);
+        let index = VariableContainerHandler::<Var>::get(variables, &self.index);
+        let array = VariableContainerHandler::<VarArray>::get(variables, &self.array);
+        let deps: Vec<_> = array
+            .iter()
+            .chain(vec![value, index])
+            .map(|var| (var.id(), VariableState::ValuesChange))
+            .collect();
+        Box::new(deps.into_iter())
+    }
+    fn initialise(
+        &mut self,
+        variables_handler: &mut Handler,
+    ) -> Result<PropagationState, VariableError> {
+        self.propagate(variables_handler)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use branchers::values_selector::MinValueSelector;
+    use branchers::variables_selector::SequentialVariableSelector;
+    use model::tests::values;
+    use model::Model;
+    use search::Solver;
+    use variables::domains::AssignableDomain;
+    use variables::int_var::IntVarValues;
+    use variables::Variable;
+
+    #[test]
+    fn constant_element_prunes() {
+        let mut model = Model::new();
+        let value = model.new_int_var(2, 3).unwrap();
+        let index = model.new_int_var(-1, 5).unwrap();
+        model.post(ConstantElement::new(value, index, vec![1, 3, 2, 5, 3]));
+        let mut space = model.build().unwrap();
+        space.run().unwrap();
+        assert_eq!(values(&space, &index), vec![1, 2, 4]);
+        assert_eq!(values(&space, &value), vec![2, 3]);
+    }
+
+    #[test]
+    fn constant_element_infeasible() {
+        let mut model = Model::new();
+        let value = model.new_int_var(4, 4).unwrap();
+        let index = model.new_int_var(0, 2).unwrap();
+        model.post(ConstantElement::new(value, index, vec![1, 3, 2]));
+        assert!(model.build().is_err());
+    }
+
+    #[test]
+    fn element_solutions() {
+        let mut model = Model::new();
+        let value = model.new_int_var(0, 2).unwrap();
+        let index = model.new_int_var(0, 1).unwrap();
+        let x = model.new_int_var(0, 2).unwrap();
+        let y = model.new_int_var(1, 1).unwrap();
+        let array = model.new_array_of_refs(vec![x, y]).unwrap();
+        model.post(Element::new(value, index, array));
+        model.branch(
+            SequentialVariableSelector::new(vec![index, value, x].into_iter()).unwrap(),
+            MinValueSelector::new(),
+        );
+        let space = model.build().unwrap();
+        // index = 0 with value = x in 0..=2, index = 1 with value = 1 and x free.
+        assert_eq!(Solver::new(space).count_solutions(), 6);
+    }
+
+    #[test]
+    fn element_prunes() {
+        let mut model = Model::new();
+        let value = model.new_int_var(5, 6).unwrap();
+        let index = model.new_int_var(0, 2).unwrap();
+        let x = model.new_int_var(0, 2).unwrap();
+        let y = model.new_int_var(4, 5).unwrap();
+        let z = model.new_int_var(6, 8).unwrap();
+        let array = model.new_array_of_refs(vec![x, y, z]).unwrap();
+        model.post(Element::new(value, index, array));
+        let mut space = model.build().unwrap();
+        space.run().unwrap();
+        assert_eq!(values(&space, &index), vec![1, 2]);
+        assert_eq!(values(&space, &value), vec![5, 6]);
+        // Once the index is fixed, the selected variable is equal to `value`.
+        {
+            let var: &mut IntVarValues = space.variables.get_mut(&index);
+            var.set_value(2).unwrap();
+        }
+        space.run().unwrap();
+        let var: &IntVarValues = space.get_variable(&value);
+        assert_eq!(var.value(), Some(6));
+        let var: &IntVarValues = space.get_variable(&z);
+        assert_eq!(var.value(), Some(6));
+    }
+
+    #[test]
+    fn element_infeasible() {
+        let mut model = Model::new();
+        let value = model.new_int_var(3, 3).unwrap();
+        let index = model.new_int_var(0, 1).unwrap();
+        let x = model.new_int_var(0, 2).unwrap();
+        let y = model.new_int_var(4, 5).unwrap();
+        let array = model.new_array_of_refs(vec![x, y]).unwrap();
+        model.post(Element::new(value, index, array));
+        assert!(model.build().is_err());
+    }
+
+    #[test]
+    fn element_aliased_value() {
+        // value = [x, value][index], the second position is always satisfied.
+        let mut model = Model::new();
+        let value = model.new_int_var(0, 2).unwrap();
+        let index = model.new_int_var(1, 1).unwrap();
+        let x = model.new_int_var(5, 6).unwrap();
+        let array = model.new_array_of_refs(vec![x, value]).unwrap();
+        model.post(Element::new(value, index, array));
+        model.branch(
+            SequentialVariableSelector::new(vec![value, x].into_iter()).unwrap(),
+            MinValueSelector::new(),
+        );
+        let mut space = model.build().unwrap();
+        space.run().unwrap();
+        assert_eq!(values(&space, &value), vec![0, 1, 2]);
+        assert_eq!(Solver::new(space).count_solutions(), 6);
+    }
+}
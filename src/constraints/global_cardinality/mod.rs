@@ -0,0 +1,629 @@
+use constraints::{Constraint, PropagationState};
+use graph::scc::strongly_connected_components;
+use std::cmp;
+use std::collections::HashMap;
+use std::sync::Arc;
+use variables::domains::{IterableDomain, OrderedDomain, PrunableDomain};
+use variables::handlers::{
+    VariableContainerHandler, VariableContainerView, VariablesHandler,
+};
+use variables::{Array, VariableError, VariableId, VariableState};
+
+// Flow from the variables to the values where each variable takes one value and each
+// value `v` is taken between `lower[v]` and `upper[v]` times.
+struct Flow<'a> {
+    domains: &'a [Vec<usize>],
+    lower: &'a [usize],
+    upper: &'a [usize],
+    assignment: Vec<Option<usize>>,
+    takers: Vec<Vec<usize>>,
+}
+
+impl<'a> Flow<'a> {
+    fn new(
+        domains: &'a [Vec<usize>],
+        lower: &'a [usize],
+        upper: &'a [usize],
+    ) -> Flow<'a> {
+        Flow {
+            domains,
+            lower,
+            upper,
+            assignment: vec![None; domains.len()],
+            takers: vec![vec![]; lower.len()],
+        }
+    }
+
+    // Looks for an augmenting path from `var` to a value taken less than its capacity.
+    // The path alternates between unused edges from the variables to the values and
+    // used edges from the values to the variables.
+    fn augment(&mut self, var: usize, capacity: &[usize]) -> bool {
+        let mut visited = vec![false; capacity.len()];
+        let mut parent = vec![None; capacity.len()];
+        let mut queue = vec![(var, None)];
+        let mut pos = 0;
+        while pos < queue.len() {
+            let (current, from) = queue[pos];
+            pos += 1;
+            for &value in self.domains[current].iter() {
+                if visited[value] || self.assignment[current] == Some(value) {
+                    continue;
+                }
+                visited[value] = true;
+                parent[value] = Some((current, from));
+                if self.takers[value].len() < capacity[value] {
+                    self.apply(value, &parent);
+                    return true;
+                }
+                for &next in self.takers[value].iter() {
+                    queue.push((next, Some(value)));
+                }
+            }
+        }
+        false
+    }
+
+    fn apply(&mut self, mut value: usize, parent: &[Option<(usize, Option<usize>)>]) {
+        while let Some((var, from)) = parent[value] {
+            if let Some(previous) = self.assignment[var] {
+                self.takers[previous].retain(|&taker| taker != var);
+            }
+            self.assignment[var] = Some(value);
+            self.takers[value].push(var);
+            match from {
+                Some(previous) => value = previous,
+                None => break,
+            }
+        }
+    }
+
+    // Computes a flow meeting first the lower bounds then the upper bounds. The
+    // augmenting paths never decrease the flow taken by a value, so the lower bounds
+    // stay satisfied.
+    fn solve(&mut self) -> bool {
+        let lower = self.lower.to_vec();
+        for var in 0..self.domains.len() {
+            self.augment(var, &lower);
+        }
+        if (0..lower.len()).any(|value| self.takers[value].len() < lower[value]) {
+            return false;
+        }
+        let upper = self.upper.to_vec();
+        (0..self.domains.len())
+            .all(|var| self.assignment[var].is_some() || self.augment(var, &upper))
+    }
+
+    // An edge of the flow is part of a solution if it is used by the flow or if its
+    // ends are in the same strongly connected component of the residual graph.
+    fn consistent_domains(&self) -> Vec<Vec<usize>> {
+        let nb_vars = self.domains.len();
+        let nb_values = self.lower.len();
+        let sink = nb_vars + nb_values;
+        let mut residual = vec![vec![]; sink + 1];
+        for (var, domain) in self.domains.iter().enumerate() {
+            for &value in domain.iter() {
+                if self.assignment[var] == Some(value) {
+                    residual[nb_vars + value].push(var);
+                } else {
+                    residual[var].push(nb_vars + value);
+                }
+            }
+        }
+        for value in 0..nb_values {
+            let taken = self.takers[value].len();
+            if taken < self.upper[value] {
+                residual[nb_vars + value].push(sink);
+            }
+            if taken > self.lower[value] {
+                residual[sink].push(nb_vars + value);
+            }
+        }
+        let components = strongly_connected_components(&residual);
+        self.domains
+            .iter()
+            .enumerate()
+            .map(|(var, domain)| {
+                domain
+                    .iter()
+                    .cloned()
+                    .filter(|&value| {
+                        self.assignment[var] == Some(value)
+                            || components[var] == components[nb_vars + value]
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/// Returns the values of `domains` which belong to an assignment of the variables
+/// where each value `v` is taken between `lower[v]` and `upper[v]` times, or an error
+/// if there is no such assignment. The variables and the values are numbered from `0`.
+pub fn consistent_domains(
+    domains: &[Vec<usize>],
+    lower: &[usize],
+    upper: &[usize],
+) -> Result<Vec<Vec<usize>>, VariableError> {
+    let mut flow = Flow::new(domains, lower, upper);
+    if flow.solve() {
+        Ok(flow.consistent_domains())
+    } else {
+        Err(VariableError::DomainWipeout)
+    }
+}
+
+// Filters the variables of the array given the bounds on the number of occurrences
+// of the values. The values not in `values` can be taken any number of times.
+fn filter_cardinalities<Var, VarArray>(
+    array: &mut VarArray,
+    values: &[i32],
+    bounds: &[(usize, usize)],
+    output: &mut Vec<(VariableId, VariableState)>,
+) -> Result<(), VariableError>
+where
+    Var: PrunableDomain<Type = i32> + IterableDomain,
+    VarArray: Array<Variable = Var>,
+{
+    let nb_vars = array.len();
+    let mut indexes: HashMap<i32, usize> = HashMap::new();
+    let mut lower = vec![];
+    let mut upper = vec![];
+    for (&value, &(min, max)) in values.iter().zip(bounds.iter()) {
+        indexes.insert(value, lower.len());
+        lower.push(min);
+        upper.push(max);
+    }
+    let mut all_values = values.to_vec();
+    let domains: Vec<Vec<usize>> = array
+        .iter()
+        .map(|var| {
+            var.iter()
+                .map(|value| {
+                    let len = lower.len();
+                    let index = *indexes.entry(value).or_insert(len);
+                    if index == len {
+                        lower.push(0);
+                        upper.push(nb_vars);
+                        all_values.push(value);
+                    }
+                    index
+                })
+                .collect()
+        })
+        .collect();
+
+    let domains = consistent_domains(&domains, &lower, &upper)?;
+    for (var, domain) in array.iter_mut().zip(domains) {
+        let state = var.in_values(domain.into_iter().map(|value| all_values[value]))?;
+        match state {
+            VariableState::NoChange => {}
+            state => {
+                output.push((var.id(), state));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Enforces that each value `values[i]` is taken by a number of variables of the
+/// array between `bounds[i].0` and `bounds[i].1`. The other values can be taken any
+/// number of times.
+///
+/// The filtering is domain consistent: it computes a flow from the variables to the
+/// values and keeps the edges which belong to a strongly connected component of its
+/// residual graph.
+#[derive(Clone)]
+pub struct GlobalCardinality<Views>
+where
+    Views: VariableContainerView,
+{
+    array: Views,
+    values: Arc<Vec<i32>>,
+    bounds: Arc<Vec<(usize, usize)>>,
+    output: Option<Vec<(VariableId, VariableState)>>,
+}
+
+impl<Views> GlobalCardinality<Views>
+where
+    Views: VariableContainerView,
+{
+    pub fn new(
+        array: Views,
+        values: Vec<i32>,
+        bounds: Vec<(usize, usize)>,
+    ) -> GlobalCardinality<Views> {
+        GlobalCardinality {
+            array,
+            values: Arc::new(values),
+            bounds: Arc::new(bounds),
+            output: None,
+        }
+    }
+}
+
+impl<Var, VarArray, Views, Handler> Constraint<Handler> for GlobalCardinality<Views>
+where
+    Handler: VariablesHandler + VariableContainerHandler<VarArray, View = Views> + Clone,
+    Views: VariableContainerView<Container = VarArray> + 'static,
+    Var: PrunableDomain<Type = i32> + IterableDomain,
+    VarArray: Array<Variable = Var>,
+{
+    fn box_clone(&self) -> Box<Constraint<Handler>> {
+        let ref_self: &GlobalCardinality<Views> = self;
+        let cloned: GlobalCardinality<Views> =
+            <GlobalCardinality<Views> as Clone>::clone(ref_self);
+
+        Box::new(cloned) as Box<Constraint<Handler>>
+    }
+    fn propagate(
+        &mut self,
+        variables_handler: &mut Handler,
+    ) -> Result<PropagationState, VariableError> {
+        let mut output = vec![];
+        self.output = None;
+
+        filter_cardinalities(
+            variables_handler.get_mut(&self.array),
+            &self.values,
+            &self.bounds,
+            &mut output,
+        )?;
+
+        if !output.is_empty() {
+            self.output = Some(output);
+            Ok(PropagationState::FixPoint)
+        } else {
+            Ok(PropagationState::NoChange)
+        }
+    }
+    #[allow(unused)]
+    fn prepare(&mut self, states: Box<Iterator<Item = VariableId>>) {
+        // Do nothing
+    }
+    fn result(&mut self) -> Box<Iterator<Item = (VariableId, VariableState)>> {
+        use std::mem;
+        let mut res = None;
+        mem::swap(&mut self.output, &mut res);
+        match res {
+            None => Box::new(vec![].into_iter()),
+            Some(changes) => Box::new(changes.into_iter()),
+        }
+    }
+    fn dependencies(
+        &self,
+        variables_handler: &Handler,
+    ) -> Box<Iterator<Item = (VariableId, VariableState)>> {
+        let deps: Vec<_> = variables_handler
+            .get(&self.array)
+            .iter()
+            .map(|var| (var.id(), VariableState::ValuesChange))
+            .collect();
+        Box::new(deps.into_iter())
+    }
+    fn initialise(
+        &mut self,
+        variables_handler: &mut Handler,
+    ) -> Result<PropagationState, VariableError> {
+        assert!(
+            self.values.len() == self.bounds.len(),
+            "Error GlobalCardinality values and bounds of different lengths."
+        );
+        self.propagate(variables_handler)
+    }
+}
+
+/// Enforces that each value `values[i]` is taken by exactly `counts[i]` variables of
+/// the array. The other values can be taken any number of times.
+///
+/// The variables are filtered as in `GlobalCardinality` with the bounds of the counts,
+/// and the counts are bounded by the number of variables assigned to their value and
+/// the number of variables which can take it.
+#[derive(Clone)]
+pub struct GlobalCardinalityCounts<Views>
+where
+    Views: VariableContainerView,
+{
+    array: Views,
+    values: Arc<Vec<i32>>,
+    counts: Views,
+    output: Option<Vec<(VariableId, VariableState)>>,
+}
+
+impl<Views> GlobalCardinalityCounts<Views>
+where
+    Views: VariableContainerView,
+{
+    pub fn new(
+        array: Views,
+        values: Vec<i32>,
+        counts: Views,
+    ) -> GlobalCardinalityCounts<Views> {
+        GlobalCardinalityCounts {
+            array,
+            values: Arc::new(values),
+            counts,
+            output: None,
+        }
+    }
+}
+
+impl<Var, VarArray, Views, Handler> Constraint<Handler> for GlobalCardinalityCounts<Views>
+where
+    Handler: VariablesHandler + VariableContainerHandler<VarArray, View = Views> + Clone,
+    Views: VariableContainerView<Container = VarArray> + 'static,
+    Var: PrunableDomain<Type = i32> + OrderedDomain + IterableDomain,
+    VarArray: Array<Variable = Var>,
+{
+    fn box_clone(&self) -> Box<Constraint<Handler>> {
+        let ref_self: &GlobalCardinalityCounts<Views> = self;
+        let cloned: GlobalCardinalityCounts<Views> =
+            <GlobalCardinalityCounts<Views> as Clone>::clone(ref_self);
+
+        Box::new(cloned) as Box<Constraint<Handler>>
+    }
+    fn propagate(
+        &mut self,
+        variables_handler: &mut Handler,
+    ) -> Result<PropagationState, VariableError> {
+        let mut output = vec![];
+        self.output = None;
+
+        unsafe {
+            let array: &mut VarArray =
+                unsafe_from_raw_point!(variables_handler.get_mut(&self.array));
+            let counts: &mut VarArray =
+                unsafe_from_raw_point!(variables_handler.get_mut(&self.counts));
+            let nb_vars = array.len() as i32;
+            loop {
+                let bounds: Vec<_> = counts
+                    .iter()
+                    .map(|count| {
+                        let min = cmp::max(count.unchecked_min(), 0);
+                        let max = cmp::min(count.unchecked_max(), nb_vars);
+                        (min as usize, cmp::max(max, 0) as usize)
+                    })
+                    .collect();
+                filter_cardinalities(array, &self.values, &bounds, &mut output)?;
+
+                let mut changed = false;
+                for (count, &value) in counts.iter_mut().zip(self.values.iter()) {
+                    let assigned = array
+                        .iter()
+                        .filter(|var| var.value() == Some(value))
+                        .count() as i32;
+                    let possible = array
+                        .iter()
+                        .filter(|var| var.iter().any(|other| other == value))
+                        .count() as i32;
+                    for state in [
+                        count.weak_lowerbound(assigned)?,
+                        count.weak_upperbound(possible)?,
+                    ] {
+                        match state {
+                            VariableState::NoChange => {}
+                            state => {
+                                changed = true;
+                                output.push((count.id(), state));
+                            }
+                        }
+                    }
+                }
+                if !changed {
+                    break;
+                }
+            }
+        }
+
+        if !output.is_empty() {
+            self.output = Some(output);
+            Ok(PropagationState::FixPoint)
+        } else {
+            Ok(PropagationState::NoChange)
+        }
+    }
+    #[allow(unused)]
+    fn prepare(&mut self, states: Box<Iterator<Item = VariableId>>) {
+        // Do nothing
+    }
+    fn result(&mut self) -> Box<Iterator<Item = (VariableId, VariableState)>> {
+        use std::mem;
+        let mut res = None;
+        mem::swap(&mut self.output, &mut res);
+        match res {
+            None => Box::new(vec![].into_iter()),
+            Some(changes) => Box::new(changes.into_iter()),
+        }
+    }
+    fn dependencies(
+        &self,
+        variables_handler: &Handler,
+    ) -> Box<Iterator<Item = (VariableId, VariableState)>> {
+        let deps: Vec<_> = variables_handler
+            .get(&self.array)
+            .iter()
+            .map(|var| (var.id(), VariableState::ValuesChange))
+            .chain(
+                variables_handler
+                    .get(&self.counts)
+                    .iter()
+                    .map(|count| (count.id(), VariableState::BoundsChange)),
+            )
+            .collect();
+        Box::new(deps.into_iter())
+    }
+    fn initialise(
+        &mut self,
+        variables_handler: &mut Handler,
+    ) -> Result<PropagationState, VariableError> {
+        assert!(
+            self.values.len() == variables_handler.get(&self.counts).len(),
+            "Error GlobalCardinalityCounts values and counts of different lengths."
+        );
+        self.propagate(variables_handler)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use branchers::values_selector::MinValueSelector;
+    use branchers::variables_selector::SequentialVariableSelector;
+    use model::Model;
+    use search::Solver;
+    use variables::int_var::IntVarValues;
+    use variables::Variable;
+
+    // Calls `f` with every assignment of `len` variables in `0..=max`.
+    fn assignments<F>(len: usize, max: i32, mut f: F)
+    where
+        F: FnMut(&[i32]),
+    {
+        let mut values = vec![0; len];
+        loop {
+            f(&values);
+            let mut pos = 0;
+            while pos < len && values[pos] == max {
+                values[pos] = 0;
+                pos += 1;
+            }
+            if pos == len {
+                return;
+            }
+            values[pos] += 1;
+        }
+    }
+
+    fn occurrences(values: &[i32], value: i32) -> usize {
+        values.iter().filter(|&&other| other == value).count()
+    }
+
+    #[test]
+    fn solutions() {
+        let mut model = Model::new();
+        let vars: Vec<_> = (0..4).map(|_| model.new_int_var(0, 2).unwrap()).collect();
+        let array = model.new_array_of_refs(vars.clone()).unwrap();
+        model.post(GlobalCardinality::new(
+            array,
+            vec![0, 1],
+            vec![(1, 2), (0, 1)],
+        ));
+        model.branch(
+            SequentialVariableSelector::new(vars.into_iter()).unwrap(),
+            MinValueSelector::new(),
+        );
+        let mut expected = 0;
+        assignments(4, 2, |values| {
+            let zeros = occurrences(values, 0);
+            if (1..=2).contains(&zeros) && occurrences(values, 1) <= 1 {
+                expected += 1;
+            }
+        });
+        assert_eq!(
+            Solver::new(model.build().unwrap()).count_solutions(),
+            expected
+        );
+    }
+
+    #[test]
+    fn prunes() {
+        // x and y take 0 and 1, so z takes 2.
+        let mut model = Model::new();
+        let x = model.new_int_var(0, 1).unwrap();
+        let y = model.new_int_var(0, 1).unwrap();
+        let z = model.new_int_var(0, 2).unwrap();
+        let array = model.new_array_of_refs(vec![x, y, z]).unwrap();
+        model.post(GlobalCardinality::new(
+            array,
+            vec![0, 1],
+            vec![(0, 1), (0, 1)],
+        ));
+        let mut space = model.build().unwrap();
+        space.run().unwrap();
+        let var: &IntVarValues = space.get_variable(&z);
+        assert_eq!(var.value(), Some(2));
+        // 2 is taken three times, by all the variables which can take it.
+        let mut model = Model::new();
+        let vars: Vec<_> = (0..3).map(|_| model.new_int_var(0, 2).unwrap()).collect();
+        let x = model.new_int_var(0, 1).unwrap();
+        let mut all = vars.clone();
+        all.push(x);
+        let array = model.new_array_of_refs(all).unwrap();
+        model.post(GlobalCardinality::new(array, vec![2], vec![(3, 3)]));
+        let mut space = model.build().unwrap();
+        space.run().unwrap();
+        for view in vars.iter() {
+            let var: &IntVarValues = space.get_variable(view);
+            assert_eq!(var.value(), Some(2));
+        }
+    }
+
+    #[test]
+    fn infeasible_without_search() {
+        // Three variables for two values taken at most once.
+        let mut model = Model::new();
+        let vars: Vec<_> = (0..3).map(|_| model.new_int_var(0, 1).unwrap()).collect();
+        let array = model.new_array_of_refs(vars).unwrap();
+        model.post(GlobalCardinality::new(
+            array,
+            vec![0, 1],
+            vec![(0, 1), (0, 1)],
+        ));
+        assert!(model.build().is_err());
+        // The value is taken at least twice but only one variable can take it.
+        let mut model = Model::new();
+        let x = model.new_int_var(0, 1).unwrap();
+        let y = model.new_int_var(1, 2).unwrap();
+        let array = model.new_array_of_refs(vec![x, y]).unwrap();
+        model.post(GlobalCardinality::new(array, vec![0], vec![(2, 2)]));
+        assert!(model.build().is_err());
+    }
+
+    #[test]
+    fn counts_solutions() {
+        let mut model = Model::new();
+        let vars: Vec<_> = (0..3).map(|_| model.new_int_var(0, 2).unwrap()).collect();
+        let counts: Vec<_> = (0..2).map(|_| model.new_int_var(0, 3).unwrap()).collect();
+        let array = model.new_array_of_refs(vars.clone()).unwrap();
+        let counts_array = model.new_array_of_refs(counts.clone()).unwrap();
+        model.post(GlobalCardinalityCounts::new(
+            array,
+            vec![0, 2],
+            counts_array,
+        ));
+        model.branch(
+            SequentialVariableSelector::new(vars.into_iter().chain(counts)).unwrap(),
+            MinValueSelector::new(),
+        );
+        // The counts are determined by the variables.
+        assert_eq!(Solver::new(model.build().unwrap()).count_solutions(), 27);
+    }
+
+    #[test]
+    fn counts_prune() {
+        let mut model = Model::new();
+        let x = model.new_int_var(0, 0).unwrap();
+        let y = model.new_int_var(0, 1).unwrap();
+        let z = model.new_int_var(1, 2).unwrap();
+        let count = model.new_int_var(0, 3).unwrap();
+        let array = model.new_array_of_refs(vec![x, y, z]).unwrap();
+        let counts = model.new_array_of_refs(vec![count]).unwrap();
+        model.post(GlobalCardinalityCounts::new(array, vec![0], counts));
+        let mut space = model.build().unwrap();
+        space.run().unwrap();
+        // x takes 0, y may take it and z can not.
+        let var: &IntVarValues = space.get_variable(&count);
+        assert_eq!(var.iter().collect::<Vec<_>>(), vec![1, 2]);
+    }
+
+    #[test]
+    fn counts_infeasible_without_search() {
+        let mut model = Model::new();
+        let x = model.new_int_var(0, 0).unwrap();
+        let y = model.new_int_var(0, 0).unwrap();
+        let count = model.new_int_var(0, 1).unwrap();
+        let array = model.new_array_of_refs(vec![x, y]).unwrap();
+        let counts = model.new_array_of_refs(vec![count]).unwrap();
+        model.post(GlobalCardinalityCounts::new(array, vec![0], counts));
+        assert!(model.build().is_err());
+    }
+}
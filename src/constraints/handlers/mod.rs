@@ -1,5 +1,6 @@
-use super::{Constraint, ConstraintBuilder, PropagationState};
-use graph::BipartiteGraph;
+use super::{Constraint, PropagationState};
+use graph::{BipartiteGraph, BipartiteGraphBuilder};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use variables::handlers::VariablesHandler;
 use variables::{VariableError, VariableId, VariableState};
@@ -9,8 +10,7 @@ pub trait ConstraintsHandlerBuilder<
     Constraints: ConstraintsHandler<Variables>,
 >
 {
-    //fn add(&mut self, Box<Constraint<Variables>>);
-    fn add(&mut self, Box<ConstraintBuilder<Variables>>);
+    fn add(&mut self, constraint: Box<Constraint<Variables>>);
     fn finalize(self, variables: &mut Variables) -> Result<Constraints, VariableError>;
 }
 
@@ -19,15 +19,61 @@ pub trait ConstraintsHandler<Variables: VariablesHandler>: Clone {
         &mut self,
         variables: &mut Variables,
     ) -> Result<PropagationState, VariableError>;
+    /// Returns the counters of the calls to the `propagate` method of the constraints.
+    fn propagations(&self) -> &Arc<PropagationCounters>;
+    /// Replaces the counters by new ones set to zero, the previous counters are still
+    /// shared by the former clones of the handler.
+    fn reset_propagations(&mut self);
+}
+
+/// Number of calls to the `propagate` method of each constraint. The counters are
+/// shared by the clones of a constraints handler: the spaces of a search count their
+/// propagations together and cloning a space does not copy them.
+#[derive(Debug)]
+pub struct PropagationCounters {
+    counters: Vec<AtomicUsize>,
+}
+
+impl PropagationCounters {
+    pub fn new(len: usize) -> PropagationCounters {
+        PropagationCounters {
+            counters: (0..len).map(|_| AtomicUsize::new(0)).collect(),
+        }
+    }
+
+    /// Records a call to the `propagate` method of the constraint `idx`.
+    pub fn record(&self, idx: usize) {
+        self.counters[idx].fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Returns the number of calls to the `propagate` method of all the constraints.
+    pub fn total(&self) -> usize {
+        self.counters
+            .iter()
+            .map(|counter| counter.load(Ordering::Relaxed))
+            .sum()
+    }
+
+    /// Returns the number of calls to the `propagate` method of each constraint, in
+    /// the order the constraints have been added.
+    pub fn per_constraint(&self) -> Vec<usize> {
+        self.counters
+            .iter()
+            .map(|counter| counter.load(Ordering::Relaxed))
+            .collect()
+    }
 }
 
 pub struct DefaultConstraintsHandlerBuilder<Variables: VariablesHandler> {
-    //constraints: Vec<Box<Constraint<Variables>>>,
-    //constraints: Vec<Box<ConstraintBuilder<Variables>>>,
-    constraints: Vec<ConstraintBuilder<Variables>>,
+    constraints: Vec<Box<Constraint<Variables>>>,
+}
+
+impl<Variables: VariablesHandler> Default for DefaultConstraintsHandlerBuilder<Variables> {
+    fn default() -> DefaultConstraintsHandlerBuilder<Variables> {
+        DefaultConstraintsHandlerBuilder::new()
+    }
 }
 
-/*
 impl<Variables: VariablesHandler> DefaultConstraintsHandlerBuilder<Variables> {
     pub fn new() -> DefaultConstraintsHandlerBuilder<Variables> {
         DefaultConstraintsHandlerBuilder {
@@ -40,49 +86,42 @@ impl<Variables: VariablesHandler>
     ConstraintsHandlerBuilder<Variables, DefaultConstraintsHandler<Variables>>
     for DefaultConstraintsHandlerBuilder<Variables>
 {
-    //fn add(&mut self, constraint: Box<Constraint<Variables>>) {
-    //fn add(&mut self, constraint: Box<ConstraintBuilder<Variables>>) {
-    fn add(&mut self, constraint: ConstraintBuilder<Variables>) {
+    fn add(&mut self, constraint: Box<Constraint<Variables>>) {
         self.constraints.push(constraint);
     }
 
+    /// Initialises the constraints and registers their dependencies. Fails if one of
+    /// the constraints is violated by the initial domains of the variables.
     fn finalize(
         mut self,
         variables: &mut Variables,
     ) -> Result<DefaultConstraintsHandler<Variables>, VariableError> {
-        //let mut graph: BipartiteGraphBuilder<VariableId, usize, VariableState> =
-        //BipartiteGraphBuilder::new();
-        //for (idx, constraint) in self.constraints.iter().enumerate() {
-        //for (view, state) in constraint.dependencies(&variables) {
-        //graph.insert_node1_to_node2(view, state, idx);
-        //}
-        //}
-        let constraints = self.constraints
-            .into_iter()
-            .map(|cons| finalize(cb, variables))
-            //.map(|cons| cons.finalize(variables).map(Box::new))
-            .collect::<Result<Vec<_>, VariableError>>()?;
+        let mut graph: BipartiteGraphBuilder<VariableId, usize, VariableState> =
+            BipartiteGraphBuilder::new();
         // Sort according to complexity?
-        //for constraint in self.constraints.iter_mut() {
-        //constraint.initialise(variables)?;
-        //}
-        //let len = self.constraints.len();
-        let len = constraints.len();
+        for (idx, constraint) in self.constraints.iter_mut().enumerate() {
+            constraint.initialise(variables)?;
+            // The changes are seen by the first call to propagate_all.
+            let _ = constraint.result();
+            for (view, state) in constraint.dependencies(&*variables) {
+                graph.insert_node1_to_node2(view, state, idx);
+            }
+        }
+        let len = self.constraints.len();
         Ok(DefaultConstraintsHandler {
-            //constraints: self.constraints,
-            constraints: constraints,
+            constraints: self.constraints,
             subsumeds: vec![false; len],
-            //graph: Arc::new(graph.finalize()),
-            graph: Arc::new(BipartiteGraphBuilder::new().finalize()),
+            propagations: Arc::new(PropagationCounters::new(len)),
+            graph: Arc::new(graph.finalize()),
         })
     }
 }
-*/
 
 #[derive(Clone)]
 pub struct DefaultConstraintsHandler<H: VariablesHandler> {
     constraints: Vec<Box<Constraint<H>>>,
     subsumeds: Vec<bool>,
+    propagations: Arc<PropagationCounters>,
     graph: Arc<BipartiteGraph<VariableId, usize, VariableState>>,
 }
 unsafe impl<H: VariablesHandler> Sync for DefaultConstraintsHandler<H> {}
@@ -99,9 +138,10 @@ impl<H: VariablesHandler> ConstraintsHandler<H> for DefaultConstraintsHandler<H>
             .enumerate()
             .zip(self.subsumeds.iter_mut())
             .map(|((a, b), c)| (a, b, c))
-            .filter(|&(_, _, ref subsumed)| !**subsumed)
+            .filter(|(_, _, subsumed)| !**subsumed)
         {
             constraint.prepare(Box::new(vec![].into_iter()));
+            self.propagations.record(idx);
             match constraint.propagate(variables_handler)? {
                 PropagationState::FixPoint => for (view, state) in constraint.result() {
                     events.add_event(view, idx, state);
@@ -126,6 +166,7 @@ impl<H: VariablesHandler> ConstraintsHandler<H> for DefaultConstraintsHandler<H>
                     continue;
                 }
                 constraint.prepare(Box::new(changes.into_iter()));
+                self.propagations.record(idx);
                 match constraint.propagate(variables_handler)? {
                     PropagationState::FixPoint => {
                         for (view, state) in constraint.result() {
@@ -145,4 +186,13 @@ impl<H: VariablesHandler> ConstraintsHandler<H> for DefaultConstraintsHandler<H>
         }
         Ok(PropagationState::FixPoint)
     }
+
+    fn propagations(&self) -> &Arc<PropagationCounters> {
+        &self.propagations
+    }
+
+    fn reset_propagations(&mut self) {
+        let len = self.constraints.len();
+        self.propagations = Arc::new(PropagationCounters::new(len));
+    }
 }
@@ -1,141 +1,240 @@
 use constraints::Constraint;
 use constraints::PropagationState;
-use std::iter::Sum;
-use std::ops::{Add, Div, Mul, Sub};
 use variables::domains::OrderedDomain;
 use variables::handlers::{
     VariableContainerHandler, VariableContainerView, VariablesHandler,
 };
-use variables::{Array, Variable, VariableError, VariableId, VariableState};
+use variables::{Array, VariableError, VariableId, VariableState};
 
-#[derive(Clone)]
-pub struct Increasing<VarType, Views>
-where
-    Views: VariableContainerView,
-    VarType: Ord + Eq + Clone,
-{
-    array: Views,
-    output: Option<Vec<(VariableId, VariableState)>>,
-}
+macro_rules! increasing_constraint_impl {
+    ($(#[$attr:meta])* $name:ident; $method:ident; $op:tt; $lhs_bound:ident; $rhs_bound:ident) => {
+        $(#[$attr])*
+        #[derive(Clone)]
+        pub struct $name<Views>
+        where
+            Views: VariableContainerView,
+        {
+            array: Views,
+            output: Option<Vec<(VariableId, VariableState)>>,
+        }
 
-impl<VarType, Views> Increasing<VarType, Views>
-where
-    Views: VariableContainerView,
-    VarType: Ord + Eq + Clone,
-{
-    pub fn new(array: Views) -> Increasing<VarType, Views>
-where {
-        Increasing {
-            array: array,
-            output: None,
+        impl<Views> $name<Views>
+        where
+            Views: VariableContainerView,
+        {
+            pub fn new(array: Views) -> $name<Views> {
+                $name {
+                    array,
+                    output: None,
+                }
+            }
         }
-    }
-}
 
-impl<Var, ArrayVar, VarType, Views, Handler> Constraint<Handler>
-    for Increasing<VarType, Views>
-where
-    Handler: VariablesHandler + VariableContainerHandler<ArrayVar>,
-    Views: VariableContainerView + 'static,
-    ArrayVar: Array<Variable = Var>,
-    Var: OrderedDomain<Type = VarType>,
-    VarType: Ord
-        + Eq
-        + Add<Output = VarType>
-        + Sub<Output = VarType>
-        + Mul<Output = VarType>
-        + Div<Output = VarType>
-        + Sum<VarType>
-        + Clone
-        + 'static,
-{
-    fn box_clone(&self) -> Box<Constraint<Handler>> {
-        let ref_self: &Increasing<VarType, Views> = &self;
-        let cloned: Increasing<VarType, Views> =
-            <Increasing<VarType, Views> as Clone>::clone(ref_self);
+        impl<Var, ArrayVar, Views, Handler> Constraint<Handler> for $name<Views>
+        where
+            Handler: VariablesHandler + VariableContainerHandler<ArrayVar, View = Views>,
+            Views: VariableContainerView<Container = ArrayVar> + 'static,
+            ArrayVar: Array<Variable = Var>,
+            Var: OrderedDomain,
+            Var::Type: Ord + Eq,
+        {
+            fn box_clone(&self) -> Box<Constraint<Handler>> {
+                let ref_self: &$name<Views> = &self;
+                let cloned: $name<Views> =
+                    <$name<Views> as Clone>::clone(ref_self);
 
-        Box::new(cloned) as Box<Constraint<Handler>>
-    }
+                Box::new(cloned) as Box<Constraint<Handler>>
+            }
 
-    // adding to propagator/constraint information about change view
-    // add iter to array and size => len
-    // [HarveySchimpf02]
-    fn propagate(
-        &mut self,
-        variables_handler: &mut Handler,
-    ) -> Result<PropagationState, VariableError> {
-        use variables::VariableState;
-        self.output = None;
-        let mut output = vec![];
-        let len = { variables_handler.get(&self.array).len() };
-        for i in 0..(len - 1) {
-            unsafe {
-                let array: &mut ArrayVar = variables_handler.get_mut(&self.array);
-                let lhs: &mut Var = unsafe_from_raw_point!(array.get_unchecked_mut(i));
-                let rhs: &mut Var =
-                    unsafe_from_raw_point!(array.get_unchecked_mut(i + 1));
-                let (lhs_state, rhs_state) = lhs.less_than(rhs)?;
-                if lhs_state != VariableState::NoChange {
-                    output.push((lhs.id(), lhs_state));
+            // adding to propagator/constraint information about change view
+            // add iter to array and size => len
+            // [HarveySchimpf02]
+            fn propagate(
+                &mut self,
+                variables_handler: &mut Handler,
+            ) -> Result<PropagationState, VariableError> {
+                use variables::VariableState;
+                self.output = None;
+                let mut output = vec![];
+                let len = { variables_handler.get(&self.array).len() };
+                for i in 0..(len - 1) {
+                    unsafe {
+                        let array: &mut ArrayVar = variables_handler.get_mut(&self.array);
+                        let lhs: &mut Var =
+                            unsafe_from_raw_point!(array.get_unchecked_mut(i));
+                        let rhs: &mut Var =
+                            unsafe_from_raw_point!(array.get_unchecked_mut(i + 1));
+                        let (lhs_state, rhs_state) = lhs.$method(rhs)?;
+                        if lhs_state != VariableState::NoChange {
+                            output.push((lhs.id(), lhs_state));
+                        }
+                        if rhs_state != VariableState::NoChange {
+                            output.push((rhs.id(), rhs_state));
+                        }
+                    };
                 }
-                if rhs_state != VariableState::NoChange {
-                    output.push((rhs.id(), rhs_state));
+                for i in 0..(len - 1) {
+                    unsafe {
+                        let array: &mut ArrayVar = variables_handler.get_mut(&self.array);
+                        let lhs: &mut Var =
+                            unsafe_from_raw_point!(array.get_unchecked_mut(len - 2 - i));
+                        let rhs: &mut Var =
+                            unsafe_from_raw_point!(array.get_unchecked_mut(len - 1 - i));
+                        let (lhs_state, rhs_state) = lhs.$method(rhs)?;
+                        if lhs_state != VariableState::NoChange {
+                            output.push((lhs.id(), lhs_state));
+                        }
+                        if rhs_state != VariableState::NoChange {
+                            output.push((rhs.id(), rhs_state));
+                        }
+                    };
                 }
-            };
-        }
-        for i in 0..(len - 1) {
-            unsafe {
-                let array: &mut ArrayVar = variables_handler.get_mut(&self.array);
-                let lhs: &mut Var =
-                    unsafe_from_raw_point!(array.get_unchecked_mut(len - 2 - i));
-                let rhs: &mut Var =
-                    unsafe_from_raw_point!(array.get_unchecked_mut(len - 1 - i));
-                let (lhs_state, rhs_state) = lhs.less_than(rhs)?;
-                if lhs_state != VariableState::NoChange {
-                    output.push((lhs.id(), lhs_state));
+                // The order holds for any values once the bounds of the consecutive
+                // variables do not overlap.
+                let subsumed = {
+                    let array: &ArrayVar = variables_handler.get(&self.array);
+                    (0..(len - 1)).all(|i| {
+                        array.get_unchecked(i).$lhs_bound()
+                            $op array.get_unchecked(i + 1).$rhs_bound()
+                    })
+                };
+                if subsumed {
+                    self.output = Some(output);
+                    Ok(PropagationState::Subsumed)
+                } else if !output.is_empty() {
+                    self.output = Some(output);
+                    Ok(PropagationState::FixPoint)
+                } else {
+                    Ok(PropagationState::NoChange)
                 }
-                if rhs_state != VariableState::NoChange {
-                    output.push((rhs.id(), rhs_state));
+            }
+            #[allow(unused)]
+            fn prepare(&mut self, states: Box<Iterator<Item = VariableId>>) {
+                // Do nothing.
+            }
+            fn result(&mut self) -> Box<Iterator<Item = (VariableId, VariableState)>> {
+                use std::mem;
+                let mut res = None;
+                mem::swap(&mut self.output, &mut res);
+                match res {
+                    None => Box::new(vec![].into_iter()),
+                    Some(changes) => Box::new(changes.into_iter()),
                 }
-            };
-        }
-        if !output.is_empty() {
-            self.output = Some(output);
-            Ok(PropagationState::FixPoint)
-        } else {
-            Ok(PropagationState::NoChange)
+            }
+            #[allow(unused)]
+            fn dependencies(
+                &self,
+                variables_handler: &Handler,
+            ) -> Box<Iterator<Item = (VariableId, VariableState)>> {
+                let deps: Vec<_> = variables_handler
+                    .get(&self.array)
+                    .iter()
+                    .map(|var| (var.id(), VariableState::ValuesChange))
+                    .collect();
+                Box::new(deps.into_iter())
+            }
+            #[allow(unused)]
+            fn initialise(
+                &mut self,
+                variables_handler: &mut Handler,
+            ) -> Result<PropagationState, VariableError> {
+                self.propagate(variables_handler)
+            }
         }
+    };
+}
+
+increasing_constraint_impl!(
+    /// Enforces that each variable of the array is strictly less than the next one.
+    Increasing; less_than; <; unchecked_max; unchecked_min
+);
+increasing_constraint_impl!(
+    /// Enforces that each variable of the array is less or equal than the next one.
+    NonDecreasing; less_or_equal_than; <=; unchecked_max; unchecked_min
+);
+increasing_constraint_impl!(
+    /// Enforces that each variable of the array is strictly greater than the next one.
+    Decreasing; greater_than; >; unchecked_min; unchecked_max
+);
+increasing_constraint_impl!(
+    /// Enforces that each variable of the array is greater or equal than the next one.
+    NonIncreasing; greater_or_equal_than; >=; unchecked_min; unchecked_max
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use branchers::values_selector::MinValueSelector;
+    use branchers::variables_selector::SequentialVariableSelector;
+    use model::tests::bounds;
+    use model::{Model, ModelError, ModelSpace};
+    use search::Solver;
+    use variables::handlers::macros::{ArrayOfRefsView, VarView};
+    use variables::handlers::DefaultVariablesHandler;
+    use variables::int_var::IntVarValues;
+
+    // Builds an array of three variables in `min..=max` constrained by `new`.
+    fn ordered<C, New>(
+        min: i32,
+        max: i32,
+        new: New,
+    ) -> (Result<ModelSpace, ModelError>, Vec<VarView<IntVarValues>>)
+    where
+        C: Constraint<DefaultVariablesHandler> + 'static,
+        New: Fn(ArrayOfRefsView<IntVarValues>) -> C,
+    {
+        let mut model = Model::new();
+        let vars: Vec<_> = (0..3)
+            .map(|_| model.new_int_var(min, max).unwrap())
+            .collect();
+        let array = model.new_array_of_refs(vars.clone()).unwrap();
+        model.post(new(array));
+        model.branch(
+            SequentialVariableSelector::new(vars.clone().into_iter()).unwrap(),
+            MinValueSelector::new(),
+        );
+        (model.build(), vars)
     }
-    #[allow(unused)]
-    fn prepare(&mut self, states: Box<Iterator<Item = VariableId>>) {
-        // Do nothing.
-    }
-    fn result(&mut self) -> Box<Iterator<Item = (VariableId, VariableState)>> {
-        use std::mem;
-        let mut res = None;
-        mem::swap(&mut self.output, &mut res);
-        match res {
-            None => Box::new(vec![].into_iter()),
-            Some(changes) => Box::new(changes.into_iter()),
-        }
+
+    #[test]
+    fn ordered_solutions() {
+        // The sorted multisets and sets of 3 values out of 4.
+        let count = |space: Result<ModelSpace, ModelError>| {
+            Solver::new(space.unwrap()).count_solutions()
+        };
+        assert_eq!(count(ordered(0, 3, NonDecreasing::new).0), 20);
+        assert_eq!(count(ordered(0, 3, Increasing::new).0), 4);
+        assert_eq!(count(ordered(0, 3, NonIncreasing::new).0), 20);
+        assert_eq!(count(ordered(0, 3, Decreasing::new).0), 4);
     }
-    #[allow(unused)]
-    fn dependencies(
-        &self,
-        variables_handler: &Handler,
-    ) -> Box<Iterator<Item = (VariableId, VariableState)>> {
-        let deps: Vec<_> = variables_handler
-            .get(&self.array)
-            .iter()
-            .map(|var| (var.id(), VariableState::ValuesChange))
-            .collect();
-        Box::new(deps.into_iter())
+
+    #[test]
+    fn ordered_prunes() {
+        let (space, vars) = ordered(0, 5, Increasing::new);
+        let mut space = space.unwrap();
+        space.run().unwrap();
+        let domains: Vec<_> = vars.iter().map(|var| bounds(&space, var)).collect();
+        assert_eq!(domains, vec![(0, 3), (1, 4), (2, 5)]);
+
+        let (space, vars) = ordered(0, 5, Decreasing::new);
+        let mut space = space.unwrap();
+        space.run().unwrap();
+        let domains: Vec<_> = vars.iter().map(|var| bounds(&space, var)).collect();
+        assert_eq!(domains, vec![(2, 5), (1, 4), (0, 3)]);
+
+        let (space, vars) = ordered(0, 5, NonDecreasing::new);
+        let mut space = space.unwrap();
+        space.run().unwrap();
+        let domains: Vec<_> = vars.iter().map(|var| bounds(&space, var)).collect();
+        assert_eq!(domains, vec![(0, 5), (0, 5), (0, 5)]);
     }
-    #[allow(unused)]
-    fn initialise(
-        &mut self,
-        variables_handler: &mut Handler,
-    ) -> Result<PropagationState, VariableError> {
-        self.propagate(variables_handler)
+
+    #[test]
+    fn ordered_infeasible() {
+        assert!(ordered(0, 1, Increasing::new).0.is_err());
+        assert!(ordered(0, 1, Decreasing::new).0.is_err());
+        // A single value satisfies the non-strict orders.
+        assert!(ordered(1, 1, NonDecreasing::new).0.is_ok());
+        assert!(ordered(1, 1, NonIncreasing::new).0.is_ok());
     }
 }
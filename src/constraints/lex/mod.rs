@@ -0,0 +1,456 @@
+use constraints::{Constraint, PropagationState};
+use variables::domains::OrderedDomain;
+use variables::handlers::{
+    VariableContainerHandler, VariableContainerView, VariablesHandler,
+};
+use variables::{Array, VariableError, VariableId, VariableState};
+
+// Returns `true` if `x[from..]` can be lexicographically less (or equal if `strict` is
+// `false`) than `y[from..]`.
+fn suffix_satisfiable<Var, VarArray>(
+    x: &VarArray,
+    y: &VarArray,
+    from: usize,
+    strict: bool,
+) -> bool
+where
+    Var: OrderedDomain,
+    Var::Type: Ord + Eq,
+    VarArray: Array<Variable = Var>,
+{
+    for (lhs, rhs) in x.iter().zip(y.iter()).skip(from) {
+        let (min, max) = (lhs.unchecked_min(), rhs.unchecked_max());
+        if min < max {
+            return true;
+        } else if min > max {
+            return false;
+        }
+    }
+    !strict
+}
+
+// Filters `x <lex y` if `strict` and `x <=lex y` otherwise. Only the first position
+// `alpha` where the arrays are not assigned to the same values is filtered: `x[alpha]`
+// is at most `y[alpha]`, and strictly less if the suffixes can not satisfy the order.
+// The filtering is domain consistent. Returns `true` if the constraint is entailed.
+fn filter_lex<Var, VarArray>(
+    x: &mut VarArray,
+    y: &mut VarArray,
+    strict: bool,
+    output: &mut Vec<(VariableId, VariableState)>,
+) -> Result<bool, VariableError>
+where
+    Var: OrderedDomain,
+    Var::Type: Ord + Eq,
+    VarArray: Array<Variable = Var>,
+{
+    let len = x.len();
+    let mut alpha = 0;
+    loop {
+        while alpha < len && {
+            let (lhs, rhs) = (x.get_unchecked(alpha), y.get_unchecked(alpha));
+            lhs.value().is_some() && lhs.value() == rhs.value()
+        } {
+            alpha += 1;
+        }
+        if alpha == len {
+            if strict {
+                return Err(VariableError::DomainWipeout);
+            } else {
+                return Ok(true);
+            }
+        }
+        if x.get_unchecked(alpha).unchecked_max() < y.get_unchecked(alpha).unchecked_min()
+        {
+            return Ok(true);
+        }
+
+        let satisfiable = suffix_satisfiable(x, y, alpha + 1, strict);
+        let lhs = x.get_unchecked_mut(alpha);
+        let rhs = y.get_unchecked_mut(alpha);
+        let (lhs_state, rhs_state) = if satisfiable {
+            (
+                lhs.weak_upperbound(rhs.unchecked_max())?,
+                rhs.weak_lowerbound(lhs.unchecked_min())?,
+            )
+        } else {
+            (
+                lhs.strict_upperbound(rhs.unchecked_max())?,
+                rhs.strict_lowerbound(lhs.unchecked_min())?,
+            )
+        };
+        if lhs_state != VariableState::NoChange {
+            output.push((lhs.id(), lhs_state));
+        }
+        if rhs_state != VariableState::NoChange {
+            output.push((rhs.id(), rhs_state));
+        }
+        // The next position is filtered only if `x[alpha]` and `y[alpha]` are now equal.
+        if lhs.value().is_none() || lhs.value() != rhs.value() {
+            return Ok(lhs.unchecked_max() < rhs.unchecked_min());
+        }
+    }
+}
+
+macro_rules! lex_constraint_impl {
+    ($(#[$attr:meta])* $name:ident; $strict:expr) => {
+        $(#[$attr])*
+        #[derive(Clone)]
+        pub struct $name<Views>
+        where
+            Views: VariableContainerView,
+        {
+            x: Views,
+            y: Views,
+            output: Option<Vec<(VariableId, VariableState)>>,
+        }
+
+        impl<Views> $name<Views>
+        where
+            Views: VariableContainerView,
+        {
+            pub fn new(x: Views, y: Views) -> $name<Views> {
+                $name {
+                    x,
+                    y,
+                    output: None,
+                }
+            }
+        }
+
+        impl<Var, VarArray, Views, Handler> Constraint<Handler> for $name<Views>
+        where
+            Handler: VariablesHandler + VariableContainerHandler<VarArray, View = Views> + Clone,
+            Views: VariableContainerView<Container = VarArray> + 'static,
+            Var: OrderedDomain,
+            Var::Type: Ord + Eq,
+            VarArray: Array<Variable = Var>,
+        {
+            fn box_clone(&self) -> Box<Constraint<Handler>> {
+                let ref_self: &$name<Views> = &self;
+                let cloned: $name<Views> = <$name<Views> as Clone>::clone(ref_self);
+
+                Box::new(cloned) as Box<Constraint<Handler>>
+            }
+            fn propagate(
+                &mut self,
+                variables_handler: &mut Handler,
+            ) -> Result<PropagationState, VariableError> {
+                let mut output = vec![];
+                self.output = None;
+
+                let entailed = unsafe {
+                    let x: &mut VarArray =
+                        unsafe_from_raw_point!(variables_handler.get_mut(&self.x));
+                    let y: &mut VarArray =
+                        unsafe_from_raw_point!(variables_handler.get_mut(&self.y));
+                    filter_lex(x, y, $strict, &mut output)?
+                };
+
+                if entailed {
+                    self.output = Some(output);
+                    Ok(PropagationState::Subsumed)
+                } else if !output.is_empty() {
+                    self.output = Some(output);
+                    Ok(PropagationState::FixPoint)
+                } else {
+                    Ok(PropagationState::NoChange)
+                }
+            }
+            #[allow(unused)]
+            fn prepare(&mut self, states: Box<Iterator<Item = VariableId>>) {
+                // Do nothing
+            }
+            fn result(&mut self) -> Box<Iterator<Item = (VariableId, VariableState)>> {
+                use std::mem;
+                let mut res = None;
+                mem::swap(&mut self.output, &mut res);
+                match res {
+                    None => Box::new(vec![].into_iter()),
+                    Some(changes) => Box::new(changes.into_iter()),
+                }
+            }
+            fn dependencies(
+                &self,
+                variables_handler: &Handler,
+            ) -> Box<Iterator<Item = (VariableId, VariableState)>> {
+                let deps: Vec<_> = variables_handler
+                    .get(&self.x)
+                    .iter()
+                    .chain(variables_handler.get(&self.y).iter())
+                    .map(|var| (var.id(), VariableState::BoundsChange))
+                    .collect();
+                Box::new(deps.into_iter())
+            }
+            fn initialise(
+                &mut self,
+                variables_handler: &mut Handler,
+            ) -> Result<PropagationState, VariableError> {
+                assert!(
+                    variables_handler.get(&self.x).len() == variables_handler.get(&self.y).len(),
+                    concat!("Error ", stringify!($name), " arrays of different lengths.")
+                );
+                self.propagate(variables_handler)
+            }
+        }
+    };
+}
+
+lex_constraint_impl!(
+    /// Enforces that the array `x` is lexicographically less than the array `y`.
+    LexLess; true
+);
+lex_constraint_impl!(
+    /// Enforces that the array `x` is lexicographically less or equal than the array
+    /// `y`.
+    LexLessEq; false
+);
+
+/// Enforces that each row is lexicographically less or equal than the next one, or
+/// strictly less for `LexChain::strict`. Each pair of consecutive rows is filtered as
+/// in `LexLessEq` until the fixpoint.
+#[derive(Clone)]
+pub struct LexChain<Views>
+where
+    Views: VariableContainerView,
+{
+    rows: Vec<Views>,
+    strict: bool,
+    output: Option<Vec<(VariableId, VariableState)>>,
+}
+
+impl<Views> LexChain<Views>
+where
+    Views: VariableContainerView,
+{
+    pub fn new(rows: Vec<Views>) -> LexChain<Views> {
+        LexChain {
+            rows,
+            strict: false,
+            output: None,
+        }
+    }
+
+    pub fn strict(rows: Vec<Views>) -> LexChain<Views> {
+        LexChain {
+            rows,
+            strict: true,
+            output: None,
+        }
+    }
+}
+
+impl<Var, VarArray, Views, Handler> Constraint<Handler> for LexChain<Views>
+where
+    Handler: VariablesHandler + VariableContainerHandler<VarArray, View = Views> + Clone,
+    Views: VariableContainerView<Container = VarArray> + 'static,
+    Var: OrderedDomain,
+    Var::Type: Ord + Eq,
+    VarArray: Array<Variable = Var>,
+{
+    fn box_clone(&self) -> Box<Constraint<Handler>> {
+        let ref_self: &LexChain<Views> = self;
+        let cloned: LexChain<Views> = <LexChain<Views> as Clone>::clone(ref_self);
+
+        Box::new(cloned) as Box<Constraint<Handler>>
+    }
+    fn propagate(
+        &mut self,
+        variables_handler: &mut Handler,
+    ) -> Result<PropagationState, VariableError> {
+        let mut output = vec![];
+        self.output = None;
+
+        let mut entailed = false;
+        while !entailed {
+            let len = output.len();
+            entailed = true;
+            for pair in self.rows.windows(2) {
+                unsafe {
+                    let x: &mut VarArray =
+                        unsafe_from_raw_point!(variables_handler.get_mut(&pair[0]));
+                    let y: &mut VarArray =
+                        unsafe_from_raw_point!(variables_handler.get_mut(&pair[1]));
+                    entailed &= filter_lex(x, y, self.strict, &mut output)?;
+                }
+            }
+            if output.len() == len {
+                break;
+            }
+        }
+
+        if entailed {
+            self.output = Some(output);
+            Ok(PropagationState::Subsumed)
+        } else if !output.is_empty() {
+            self.output = Some(output);
+            Ok(PropagationState::FixPoint)
+        } else {
+            Ok(PropagationState::NoChange)
+        }
+    }
+    #[allow(unused)]
+    fn prepare(&mut self, states: Box<Iterator<Item = VariableId>>) {
+        // Do nothing
+    }
+    fn result(&mut self) -> Box<Iterator<Item = (VariableId, VariableState)>> {
+        use std::mem;
+        let mut res = None;
+        mem::swap(&mut self.output, &mut res);
+        match res {
+            None => Box::new(vec![].into_iter()),
+            Some(changes) => Box::new(changes.into_iter()),
+        }
+    }
+    fn dependencies(
+        &self,
+        variables_handler: &Handler,
+    ) -> Box<Iterator<Item = (VariableId, VariableState)>> {
+        let deps: Vec<_> = self
+            .rows
+            .iter()
+            .flat_map(|row| variables_handler.get(row).iter())
+            .map(|var| (var.id(), VariableState::BoundsChange))
+            .collect();
+        Box::new(deps.into_iter())
+    }
+    fn initialise(
+        &mut self,
+        variables_handler: &mut Handler,
+    ) -> Result<PropagationState, VariableError> {
+        for pair in self.rows.windows(2) {
+            assert!(
+                variables_handler.get(&pair[0]).len()
+                    == variables_handler.get(&pair[1]).len(),
+                "Error LexChain rows of different lengths."
+            );
+        }
+        self.propagate(variables_handler)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use branchers::values_selector::MinValueSelector;
+    use branchers::variables_selector::SequentialVariableSelector;
+    use model::Model;
+    use search::Solver;
+    use variables::domains::AssignableDomain;
+    use variables::int_var::IntVarValues;
+    use variables::Variable;
+
+    fn lex_solutions(strict: bool) -> usize {
+        let mut model = Model::new();
+        let x: Vec<_> = (0..3).map(|_| model.new_int_var(0, 1).unwrap()).collect();
+        let y: Vec<_> = (0..3).map(|_| model.new_int_var(0, 1).unwrap()).collect();
+        let lhs = model.new_array_of_refs(x.clone()).unwrap();
+        let rhs = model.new_array_of_refs(y.clone()).unwrap();
+        if strict {
+            model.post(LexLess::new(lhs, rhs));
+        } else {
+            model.post(LexLessEq::new(lhs, rhs));
+        }
+        model.branch(
+            SequentialVariableSelector::new(x.into_iter().chain(y)).unwrap(),
+            MinValueSelector::new(),
+        );
+        let space = model.build().unwrap();
+        Solver::new(space).count_solutions()
+    }
+
+    fn chain_solutions(strict: bool) -> usize {
+        let mut model = Model::new();
+        let vars: Vec<_> = (0..6).map(|_| model.new_int_var(0, 1).unwrap()).collect();
+        let rows: Vec<_> = vars
+            .chunks(2)
+            .map(|row| model.new_array_of_refs(row.to_vec()).unwrap())
+            .collect();
+        if strict {
+            model.post(LexChain::strict(rows));
+        } else {
+            model.post(LexChain::new(rows));
+        }
+        model.branch(
+            SequentialVariableSelector::new(vars.into_iter()).unwrap(),
+            MinValueSelector::new(),
+        );
+        let space = model.build().unwrap();
+        Solver::new(space).count_solutions()
+    }
+
+    #[test]
+    fn lex_solutions_count() {
+        // Out of the 64 pairs of binary arrays of length 3, 8 are equal.
+        assert_eq!(lex_solutions(true), 28);
+        assert_eq!(lex_solutions(false), 36);
+    }
+
+    #[test]
+    fn chain_solutions_count() {
+        // The rows are the sorted multisets and sets of 3 values out of 4.
+        assert_eq!(chain_solutions(false), 20);
+        assert_eq!(chain_solutions(true), 4);
+    }
+
+    #[test]
+    fn lex_prunes() {
+        let mut model = Model::new();
+        let x0 = model.new_int_var(0, 2).unwrap();
+        let x1 = model.new_int_var(1, 1).unwrap();
+        let y0 = model.new_int_var(0, 2).unwrap();
+        let y1 = model.new_int_var(0, 0).unwrap();
+        let x = model.new_array_of_refs(vec![x0, x1]).unwrap();
+        let y = model.new_array_of_refs(vec![y0, y1]).unwrap();
+        model.post(LexLessEq::new(x, y));
+        let mut space = model.build().unwrap();
+        space.run().unwrap();
+        // The suffixes can not be ordered, so `x0 < y0`.
+        let var: &IntVarValues = space.get_variable(&x0);
+        assert_eq!((var.unchecked_min(), var.unchecked_max()), (0, 1));
+        let var: &IntVarValues = space.get_variable(&y0);
+        assert_eq!((var.unchecked_min(), var.unchecked_max()), (1, 2));
+        // `y0` stays strictly above `x0` once it is assigned.
+        {
+            let var: &mut IntVarValues = space.variables.get_mut(&x0);
+            var.set_value(1).unwrap();
+        }
+        space.run().unwrap();
+        let var: &IntVarValues = space.get_variable(&y0);
+        assert_eq!(var.value(), Some(2));
+    }
+
+    #[test]
+    fn lex_infeasible() {
+        let mut model = Model::new();
+        let x0 = model.new_int_var(1, 2).unwrap();
+        let x1 = model.new_int_var(0, 2).unwrap();
+        let y0 = model.new_int_var(0, 1).unwrap();
+        let y1 = model.new_int_var(0, 0).unwrap();
+        let x = model.new_array_of_refs(vec![x0, x1]).unwrap();
+        let y = model.new_array_of_refs(vec![y0, y1]).unwrap();
+        model.post(LexLess::new(x, y));
+        assert!(model.build().is_err());
+
+        let mut model = Model::new();
+        let x0 = model.new_int_var(1, 1).unwrap();
+        let y0 = model.new_int_var(1, 1).unwrap();
+        let x = model.new_array_of_refs(vec![x0]).unwrap();
+        let y = model.new_array_of_refs(vec![y0]).unwrap();
+        model.post(LexLess::new(x, y));
+        assert!(model.build().is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "Error LexLess arrays of different lengths.")]
+    fn lex_different_lengths() {
+        let mut model = Model::new();
+        let x0 = model.new_int_var(0, 1).unwrap();
+        let y0 = model.new_int_var(0, 1).unwrap();
+        let y1 = model.new_int_var(0, 1).unwrap();
+        let x = model.new_array_of_refs(vec![x0]).unwrap();
+        let y = model.new_array_of_refs(vec![y0, y1]).unwrap();
+        model.post(LexLess::new(x, y));
+        let _ = model.build();
+    }
+}
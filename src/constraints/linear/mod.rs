@@ -0,0 +1,537 @@
+use constraints::{to_i32, Constraint, PropagationState};
+use std::sync::Arc;
+use variables::domains::{OrderedDomain, PrunableDomain};
+use variables::handlers::{
+    VariableContainerHandler, VariableContainerView, VariablesHandler,
+};
+use variables::{Array, VariableError, VariableId, VariableState};
+
+/// Returns `lhs / rhs` rounded toward negative infinity.
+pub fn floor_div(lhs: i64, rhs: i64) -> i64 {
+    let quotient = lhs / rhs;
+    if (lhs % rhs != 0) && ((lhs < 0) != (rhs < 0)) {
+        quotient - 1
+    } else {
+        quotient
+    }
+}
+
+/// Returns `lhs / rhs` rounded toward positive infinity.
+pub fn ceil_div(lhs: i64, rhs: i64) -> i64 {
+    -floor_div(-lhs, rhs)
+}
+
+// Filters the bounds of the variables of `sum(coefs[i] * array[i]) <= constant`. The
+// smallest value of the sum without `array[i]` bounds the contribution of `array[i]`.
+// Returns `true` if a variable has been modified.
+fn filter_less_or_equal<Var, VarArray>(
+    array: &mut VarArray,
+    coefs: &[i64],
+    constant: i64,
+    output: &mut Vec<(VariableId, VariableState)>,
+) -> Result<bool, VariableError>
+where
+    Var: OrderedDomain<Type = i32>,
+    VarArray: Array<Variable = Var>,
+{
+    let mins: Vec<i64> = array
+        .iter()
+        .zip(coefs.iter())
+        .map(|(var, &coef)| {
+            if coef >= 0 {
+                coef * i64::from(var.unchecked_min())
+            } else {
+                coef * i64::from(var.unchecked_max())
+            }
+        })
+        .collect();
+    let min: i64 = mins.iter().sum();
+    if min > constant {
+        return Err(VariableError::DomainWipeout);
+    }
+
+    let mut changed = false;
+    for ((var, &coef), contribution) in array.iter_mut().zip(coefs.iter()).zip(mins) {
+        let slack = constant - (min - contribution);
+        let state = if coef > 0 {
+            var.weak_upperbound(to_i32(floor_div(slack, coef)))?
+        } else if coef < 0 {
+            var.weak_lowerbound(to_i32(ceil_div(slack, coef)))?
+        } else {
+            VariableState::NoChange
+        };
+        match state {
+            VariableState::NoChange => {}
+            state => {
+                changed = true;
+                output.push((var.id(), state));
+            }
+        }
+    }
+    Ok(changed)
+}
+
+/// The relation between the weighted sum and the constant of a `Linear` constraint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinearRelation {
+    LessOrEqual,
+    GreaterOrEqual,
+    Equal,
+}
+
+/// Enforces that `sum(coefs[i] * array[i])` is less or equal, greater or equal or
+/// equal to `constant`. The coefficients can be negative, so a variable right hand
+/// side is expressed by adding the variable to the array with the coefficient `-1`.
+///
+/// The filtering is bounds consistent for the inequalities.
+#[derive(Clone)]
+pub struct Linear<Views>
+where
+    Views: VariableContainerView,
+{
+    array: Views,
+    coefs: Arc<Vec<i64>>,
+    relation: LinearRelation,
+    constant: i64,
+    output: Option<Vec<(VariableId, VariableState)>>,
+}
+
+impl<Views> Linear<Views>
+where
+    Views: VariableContainerView,
+{
+    pub fn new(
+        array: Views,
+        coefs: Vec<i32>,
+        relation: LinearRelation,
+        constant: i32,
+    ) -> Linear<Views> {
+        Linear {
+            array,
+            coefs: Arc::new(coefs.into_iter().map(i64::from).collect()),
+            relation,
+            constant: i64::from(constant),
+            output: None,
+        }
+    }
+
+    pub fn less_or_equal(array: Views, coefs: Vec<i32>, constant: i32) -> Linear<Views> {
+        Linear::new(array, coefs, LinearRelation::LessOrEqual, constant)
+    }
+
+    pub fn greater_or_equal(
+        array: Views,
+        coefs: Vec<i32>,
+        constant: i32,
+    ) -> Linear<Views> {
+        Linear::new(array, coefs, LinearRelation::GreaterOrEqual, constant)
+    }
+
+    pub fn equal(array: Views, coefs: Vec<i32>, constant: i32) -> Linear<Views> {
+        Linear::new(array, coefs, LinearRelation::Equal, constant)
+    }
+
+    // A greater or equal relation is a less or equal relation on the opposite sum.
+    fn filter<Var, VarArray>(
+        &self,
+        array: &mut VarArray,
+        output: &mut Vec<(VariableId, VariableState)>,
+    ) -> Result<(), VariableError>
+    where
+        Var: OrderedDomain<Type = i32>,
+        VarArray: Array<Variable = Var>,
+    {
+        let opposite: Vec<_> = self.coefs.iter().map(|&coef| -coef).collect();
+        match self.relation {
+            LinearRelation::LessOrEqual => {
+                filter_less_or_equal(array, &self.coefs, self.constant, output)?;
+            }
+            LinearRelation::GreaterOrEqual => {
+                filter_less_or_equal(array, &opposite, -self.constant, output)?;
+            }
+            LinearRelation::Equal => loop {
+                let lower =
+                    filter_less_or_equal(array, &self.coefs, self.constant, output)?;
+                let upper =
+                    filter_less_or_equal(array, &opposite, -self.constant, output)?;
+                if !lower && !upper {
+                    break;
+                }
+            },
+        }
+        Ok(())
+    }
+}
+
+impl<Var, VarArray, Views, Handler> Constraint<Handler> for Linear<Views>
+where
+    Handler: VariablesHandler + VariableContainerHandler<VarArray, View = Views> + Clone,
+    Views: VariableContainerView<Container = VarArray> + 'static,
+    Var: OrderedDomain<Type = i32>,
+    VarArray: Array<Variable = Var>,
+{
+    fn box_clone(&self) -> Box<Constraint<Handler>> {
+        let ref_self: &Linear<Views> = self;
+        let cloned: Linear<Views> = <Linear<Views> as Clone>::clone(ref_self);
+
+        Box::new(cloned) as Box<Constraint<Handler>>
+    }
+    fn propagate(
+        &mut self,
+        variables_handler: &mut Handler,
+    ) -> Result<PropagationState, VariableError> {
+        let mut output = vec![];
+        self.output = None;
+
+        self.filter(variables_handler.get_mut(&self.array), &mut output)?;
+
+        if !output.is_empty() {
+            self.output = Some(output);
+            Ok(PropagationState::FixPoint)
+        } else {
+            Ok(PropagationState::NoChange)
+        }
+    }
+    #[allow(unused)]
+    fn prepare(&mut self, states: Box<Iterator<Item = VariableId>>) {
+        // Do nothing
+    }
+    fn result(&mut self) -> Box<Iterator<Item = (VariableId, VariableState)>> {
+        use std::mem;
+        let mut res = None;
+        mem::swap(&mut self.output, &mut res);
+        match res {
+            None => Box::new(vec![].into_iter()),
+            Some(changes) => Box::new(changes.into_iter()),
+        }
+    }
+    fn dependencies(
+        &self,
+        variables_handler: &Handler,
+    ) -> Box<Iterator<Item = (VariableId, VariableState)>> {
+        let deps: Vec<_> = variables_handler
+            .get(&self.array)
+            .iter()
+            .map(|var| (var.id(), VariableState::BoundsChange))
+            .collect();
+        Box::new(deps.into_iter())
+    }
+    fn initialise(
+        &mut self,
+        variables_handler: &mut Handler,
+    ) -> Result<PropagationState, VariableError> {
+        let len = variables_handler.get(&self.array).len();
+        assert!(
+            self.coefs.len() == len,
+            "Error Linear coefs and array of different lengths."
+        );
+        self.propagate(variables_handler)
+    }
+}
+
+/// Enforces that `sum(coefs[i] * array[i])` is different from `constant`. The value
+/// of the last unassigned variable is removed if it would make the sum equal to
+/// `constant`.
+#[derive(Clone)]
+pub struct LinearNotEqual<Views>
+where
+    Views: VariableContainerView,
+{
+    array: Views,
+    coefs: Arc<Vec<i64>>,
+    constant: i64,
+    output: Option<Vec<(VariableId, VariableState)>>,
+}
+
+impl<Views> LinearNotEqual<Views>
+where
+    Views: VariableContainerView,
+{
+    pub fn new(array: Views, coefs: Vec<i32>, constant: i32) -> LinearNotEqual<Views> {
+        LinearNotEqual {
+            array,
+            coefs: Arc::new(coefs.into_iter().map(i64::from).collect()),
+            constant: i64::from(constant),
+            output: None,
+        }
+    }
+
+    fn filter<Var, VarArray>(
+        &self,
+        array: &mut VarArray,
+        output: &mut Vec<(VariableId, VariableState)>,
+    ) -> Result<(), VariableError>
+    where
+        Var: PrunableDomain<Type = i32> + OrderedDomain,
+        VarArray: Array<Variable = Var>,
+    {
+        let mut sum = 0;
+        let mut unassigned = None;
+        for (pos, (var, &coef)) in array.iter().zip(self.coefs.iter()).enumerate() {
+            match var.value() {
+                Some(value) => sum += coef * i64::from(value),
+                None if coef == 0 => {}
+                None if unassigned.is_none() => unassigned = Some(pos),
+                None => return Ok(()),
+            }
+        }
+        match unassigned {
+            None if sum == self.constant => Err(VariableError::DomainWipeout),
+            None => Ok(()),
+            Some(pos) => {
+                let coef = self.coefs[pos];
+                let rest = self.constant - sum;
+                if rest % coef != 0 {
+                    return Ok(());
+                }
+                let value = rest / coef;
+                if value < i64::from(i32::MIN)
+                    || value > i64::from(i32::MAX)
+                {
+                    return Ok(());
+                }
+                let var = array.get_unchecked_mut(pos);
+                match var.remove_value(value as i32)? {
+                    VariableState::NoChange => {}
+                    state => {
+                        output.push((var.id(), state));
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl<Var, VarArray, Views, Handler> Constraint<Handler> for LinearNotEqual<Views>
+where
+    Handler: VariablesHandler + VariableContainerHandler<VarArray, View = Views> + Clone,
+    Views: VariableContainerView<Container = VarArray> + 'static,
+    Var: PrunableDomain<Type = i32> + OrderedDomain,
+    VarArray: Array<Variable = Var>,
+{
+    fn box_clone(&self) -> Box<Constraint<Handler>> {
+        let ref_self: &LinearNotEqual<Views> = self;
+        let cloned: LinearNotEqual<Views> =
+            <LinearNotEqual<Views> as Clone>::clone(ref_self);
+
+        Box::new(cloned) as Box<Constraint<Handler>>
+    }
+    fn propagate(
+        &mut self,
+        variables_handler: &mut Handler,
+    ) -> Result<PropagationState, VariableError> {
+        let mut output = vec![];
+        self.output = None;
+
+        self.filter(variables_handler.get_mut(&self.array), &mut output)?;
+
+        if !output.is_empty() {
+            self.output = Some(output);
+            Ok(PropagationState::FixPoint)
+        } else {
+            Ok(PropagationState::NoChange)
+        }
+    }
+    #[allow(unused)]
+    fn prepare(&mut self, states: Box<Iterator<Item = VariableId>>) {
+        // Do nothing
+    }
+    fn result(&mut self) -> Box<Iterator<Item = (VariableId, VariableState)>> {
+        use std::mem;
+        let mut res = None;
+        mem::swap(&mut self.output, &mut res);
+        match res {
+            None => Box::new(vec![].into_iter()),
+            Some(changes) => Box::new(changes.into_iter()),
+        }
+    }
+    fn dependencies(
+        &self,
+        variables_handler: &Handler,
+    ) -> Box<Iterator<Item = (VariableId, VariableState)>> {
+        let deps: Vec<_> = variables_handler
+            .get(&self.array)
+            .iter()
+            .map(|var| (var.id(), VariableState::ValuesChange))
+            .collect();
+        Box::new(deps.into_iter())
+    }
+    fn initialise(
+        &mut self,
+        variables_handler: &mut Handler,
+    ) -> Result<PropagationState, VariableError> {
+        let len = variables_handler.get(&self.array).len();
+        assert!(
+            self.coefs.len() == len,
+            "Error LinearNotEqual coefs and array of different lengths."
+        );
+        self.propagate(variables_handler)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use branchers::values_selector::MinValueSelector;
+    use branchers::variables_selector::SequentialVariableSelector;
+    use model::tests::bounds;
+    use model::Model;
+    use search::Solver;
+    use variables::domains::{AssignableDomain, IterableDomain};
+    use variables::int_var::IntVarValues;
+
+    // Counts the pairs of `0..=3` satisfying `predicate`.
+    fn brute_force<F>(predicate: F) -> usize
+    where
+        F: Fn(i32, i32) -> bool,
+    {
+        (0..4)
+            .flat_map(|x| (0..4).map(move |y| (x, y)))
+            .filter(|&(x, y)| predicate(x, y))
+            .count()
+    }
+
+    fn solutions(
+        relation: Option<LinearRelation>,
+        coefs: Vec<i32>,
+        constant: i32,
+    ) -> usize {
+        let mut model = Model::new();
+        let x = model.new_int_var(0, 3).unwrap();
+        let y = model.new_int_var(0, 3).unwrap();
+        let array = model.new_array_of_refs(vec![x, y]).unwrap();
+        match relation {
+            Some(relation) => model.post(Linear::new(array, coefs, relation, constant)),
+            None => model.post(LinearNotEqual::new(array, coefs, constant)),
+        }
+        model.branch(
+            SequentialVariableSelector::new(vec![x, y].into_iter()).unwrap(),
+            MinValueSelector::new(),
+        );
+        match model.build() {
+            Ok(space) => Solver::new(space).count_solutions(),
+            Err(_) => 0,
+        }
+    }
+
+    #[test]
+    fn div_rounding() {
+        assert_eq!(floor_div(7, 2), 3);
+        assert_eq!(floor_div(-7, 2), -4);
+        assert_eq!(floor_div(7, -2), -4);
+        assert_eq!(ceil_div(7, 2), 4);
+        assert_eq!(ceil_div(-7, 2), -3);
+        assert_eq!(to_i32(1 << 40), i32::MAX);
+        assert_eq!(to_i32(-(1 << 40)), i32::MIN);
+    }
+
+    #[test]
+    fn linear_solutions() {
+        use self::LinearRelation::*;
+        assert_eq!(
+            solutions(Some(LessOrEqual), vec![3, 2], 10),
+            brute_force(|x, y| 3 * x + 2 * y <= 10)
+        );
+        assert_eq!(
+            solutions(Some(LessOrEqual), vec![2, -3], -1),
+            brute_force(|x, y| 2 * x - 3 * y <= -1)
+        );
+        assert_eq!(
+            solutions(Some(GreaterOrEqual), vec![-1, 2], 3),
+            brute_force(|x, y| -x + 2 * y >= 3)
+        );
+        assert_eq!(
+            solutions(Some(Equal), vec![2, -1], 1),
+            brute_force(|x, y| 2 * x - y == 1)
+        );
+        assert_eq!(
+            solutions(None, vec![1, 2], 4),
+            brute_force(|x, y| x + 2 * y != 4)
+        );
+    }
+
+    #[test]
+    fn linear_prunes() {
+        let mut model = Model::new();
+        let x = model.new_int_var(0, 5).unwrap();
+        let y = model.new_int_var(0, 5).unwrap();
+        let z = model.new_int_var(0, 3).unwrap();
+        let array = model.new_array_of_refs(vec![x, y]).unwrap();
+        let others = model.new_array_of_refs(vec![y, z]).unwrap();
+        // x - y <= -2
+        model.post(Linear::less_or_equal(array, vec![1, -1], -2));
+        // 2y + 3z >= 14
+        model.post(Linear::greater_or_equal(others, vec![2, 3], 14));
+        let mut space = model.build().unwrap();
+        space.run().unwrap();
+        assert_eq!(bounds(&space, &x), (0, 3));
+        assert_eq!(bounds(&space, &y), (3, 5));
+        assert_eq!(bounds(&space, &z), (2, 3));
+    }
+
+    #[test]
+    fn linear_equal_prunes() {
+        let mut model = Model::new();
+        let x = model.new_int_var(0, 10).unwrap();
+        let y = model.new_int_var(4, 6).unwrap();
+        let array = model.new_array_of_refs(vec![x, y]).unwrap();
+        // 3x - y = 2
+        model.post(Linear::equal(array, vec![3, -1], 2));
+        let mut space = model.build().unwrap();
+        space.run().unwrap();
+        assert_eq!(bounds(&space, &x), (2, 2));
+        assert_eq!(bounds(&space, &y), (4, 4));
+    }
+
+    #[test]
+    fn linear_not_equal_prunes() {
+        let mut model = Model::new();
+        let x = model.new_int_var(0, 3).unwrap();
+        let y = model.new_int_var(0, 3).unwrap();
+        let array = model.new_array_of_refs(vec![x, y]).unwrap();
+        model.post(LinearNotEqual::new(array, vec![1, 2], 4));
+        let mut space = model.build().unwrap();
+        space.run().unwrap();
+        assert_eq!(bounds(&space, &y), (0, 3));
+        {
+            let var: &mut IntVarValues = space.variables.get_mut(&x);
+            var.set_value(2).unwrap();
+        }
+        space.run().unwrap();
+        let var: &IntVarValues = space.get_variable(&y);
+        assert_eq!(var.iter().collect::<Vec<_>>(), vec![0, 2, 3]);
+    }
+
+    #[test]
+    fn linear_infeasible() {
+        use self::LinearRelation::*;
+        assert_eq!(solutions(Some(GreaterOrEqual), vec![1, 1], 7), 0);
+        assert_eq!(solutions(Some(LessOrEqual), vec![-1, 1], -4), 0);
+        assert_eq!(solutions(Some(Equal), vec![2, 2], 5), 0);
+        let mut model = Model::new();
+        let x = model.new_int_var(3, 3).unwrap();
+        let y = model.new_int_var(1, 1).unwrap();
+        let array = model.new_array_of_refs(vec![x, y]).unwrap();
+        model.post(LinearNotEqual::new(array, vec![1, -1], 2));
+        assert!(model.build().is_err());
+    }
+
+    #[test]
+    fn linear_large_coefficients() {
+        let mut model = Model::new();
+        let x = model.new_int_var(-1000, 1000).unwrap();
+        let y = model.new_int_var(-1000, 1000).unwrap();
+        let array = model.new_array_of_refs(vec![x, y]).unwrap();
+        // The sum does not fit in an i32.
+        model.post(Linear::greater_or_equal(
+            array,
+            vec![i32::MAX, i32::MAX],
+            i32::MAX,
+        ));
+        let mut space = model.build().unwrap();
+        space.run().unwrap();
+        assert_eq!(bounds(&space, &x), (-999, 1000));
+        assert_eq!(bounds(&space, &y), (-999, 1000));
+    }
+}
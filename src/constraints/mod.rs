@@ -70,15 +70,72 @@ impl<H: VariablesHandler> Clone for Box<Constraint<H>> {
     }
 }
 
+/// Trait defining a constraint which can be reified, i.e. linked to a Boolean variable
+/// stating whether the constraint holds (see `Reified`).
+pub trait Reifiable<Handler: VariablesHandler>: Constraint<Handler> {
+    /// Same as `box_clone` for the `Reifiable` trait objects.
+    fn box_clone_reifiable(&self) -> Box<Reifiable<Handler>>;
+    /// Returns `Some(true)` if the constraint holds for every assignment of its
+    /// variables (entailment), `Some(false)` if it holds for none of them
+    /// (disentailment) and `None` otherwise.
+    fn entailment(&self, variables_handler: &Handler) -> Option<bool>;
+    /// Propagates the negation of the constraint. As for `propagate`, the modified
+    /// variables are given by `result`.
+    fn propagate_negation(
+        &mut self,
+        variables_handler: &mut Handler,
+    ) -> Result<PropagationState, VariableError>;
+}
+
+impl<H: VariablesHandler> Clone for Box<Reifiable<H>> {
+    fn clone(&self) -> Box<Reifiable<H>> {
+        self.box_clone_reifiable()
+    }
+}
+
 pub trait PropagatorState {}
 pub trait Propagator {}
 
+/// Returns the closest `i32` to `value`.
+pub fn to_i32(value: i64) -> i32 {
+    if value > i64::from(i32::MAX) {
+        i32::MAX
+    } else if value < i64::from(i32::MIN) {
+        i32::MIN
+    } else {
+        value as i32
+    }
+}
+
 mod all_different;
 pub mod handlers;
-pub use self::all_different::AllDifferent;
+pub use self::all_different::{AllDifferent, AllDifferentBounds, AllDifferentDomain};
 pub mod arithmetic;
+mod channel;
+pub use self::channel::Channel;
+mod circuit;
+pub use self::circuit::{Circuit, SubCircuit};
+mod count;
+pub use self::count::{Among, AtLeast, AtMost, Count};
+mod cumulative;
+pub use self::cumulative::Cumulative;
+mod disjunctive;
+pub use self::disjunctive::Disjunctive;
+mod element;
+pub use self::element::{ConstantElement, Element};
+mod global_cardinality;
+pub use self::global_cardinality::{GlobalCardinality, GlobalCardinalityCounts};
 mod increasing;
-pub use self::increasing::Increasing;
+pub use self::increasing::{Decreasing, Increasing, NonDecreasing, NonIncreasing};
+mod lex;
+pub use self::lex::{LexChain, LexLess, LexLessEq};
+mod linear;
+pub use self::linear::{Linear, LinearNotEqual, LinearRelation};
 mod regular;
+pub use self::regular::{Dfa, Regular};
+mod reified;
+pub use self::reified::{Reification, Reified};
 mod sum;
 pub use self::sum::SumConstraint;
+mod table;
+pub use self::table::Table;
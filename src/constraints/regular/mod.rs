@@ -0,0 +1,326 @@
+use constraints::{Constraint, PropagationState};
+use std::collections::HashMap;
+use std::sync::Arc;
+use variables::domains::{IterableDomain, PrunableDomain};
+use variables::handlers::{
+    VariableContainerHandler, VariableContainerView, VariablesHandler,
+};
+use variables::{Array, VariableError, VariableId, VariableState};
+
+/// Deterministic finite automaton which the symbols are `i32` values. The states are
+/// numbered from `0` to `nb_states - 1`.
+#[derive(Clone, Debug)]
+pub struct Dfa {
+    initial: usize,
+    transitions: Vec<HashMap<i32, usize>>,
+    accepting: Vec<bool>,
+}
+
+impl Dfa {
+    /// Returns the automaton with the transitions `(from, symbol, to)`, or `None` if a
+    /// state is out of range or if two transitions leave the same state with the same
+    /// symbol.
+    pub fn new(
+        nb_states: usize,
+        initial: usize,
+        transitions: Vec<(usize, i32, usize)>,
+        accepting: Vec<usize>,
+    ) -> Option<Dfa> {
+        if initial >= nb_states {
+            return None;
+        }
+        let mut table = vec![HashMap::new(); nb_states];
+        for (from, symbol, to) in transitions.into_iter() {
+            if from >= nb_states || to >= nb_states {
+                return None;
+            }
+            if let Some(&previous) = table[from].get(&symbol) {
+                if previous != to {
+                    return None;
+                }
+            }
+            table[from].insert(symbol, to);
+        }
+        let mut is_accepting = vec![false; nb_states];
+        for state in accepting.into_iter() {
+            if state >= nb_states {
+                return None;
+            }
+            is_accepting[state] = true;
+        }
+        Some(Dfa {
+            initial,
+            transitions: table,
+            accepting: is_accepting,
+        })
+    }
+
+    pub fn nb_states(&self) -> usize {
+        self.transitions.len()
+    }
+
+    /// Returns the state reached from `state` by reading `symbol`.
+    pub fn next(&self, state: usize, symbol: i32) -> Option<usize> {
+        self.transitions[state].get(&symbol).cloned()
+    }
+
+    /// Returns `true` if the automaton accepts the sequence of symbols.
+    pub fn accepts<Symbols>(&self, symbols: Symbols) -> bool
+    where
+        Symbols: IntoIterator<Item = i32>,
+    {
+        let mut state = self.initial;
+        for symbol in symbols.into_iter() {
+            match self.next(state, symbol) {
+                Some(next) => state = next,
+                None => return false,
+            }
+        }
+        self.accepting[state]
+    }
+}
+
+/// Enforces that the sequence of values taken by the variables of the array is a
+/// word accepted by the automaton.
+///
+/// The propagation unfolds the automaton into a layered graph, a layer of states
+/// for each variable. A forward pass marks the states reachable from the initial
+/// state and a backward pass keeps those leading to an accepting state. A value is
+/// kept if it labels an edge between two kept states, so the constraint is domain
+/// consistent.
+#[derive(Clone)]
+pub struct Regular<Views>
+where
+    Views: VariableContainerView,
+{
+    array: Views,
+    dfa: Arc<Dfa>,
+    output: Option<Vec<(VariableId, VariableState)>>,
+}
+
+impl<Views> Regular<Views>
+where
+    Views: VariableContainerView,
+{
+    pub fn new(array: Views, dfa: Dfa) -> Regular<Views> {
+        Regular {
+            array,
+            dfa: Arc::new(dfa),
+            output: None,
+        }
+    }
+
+    fn filter<Var, VarArray>(
+        &self,
+        array: &mut VarArray,
+        output: &mut Vec<(VariableId, VariableState)>,
+    ) -> Result<(), VariableError>
+    where
+        Var: PrunableDomain<Type = i32> + IterableDomain,
+        VarArray: Array<Variable = Var>,
+    {
+        let dfa = &self.dfa;
+        let nb_states = dfa.nb_states();
+        let len = array.len();
+
+        // Forward pass: states reachable from the initial state.
+        let mut layers = vec![vec![false; nb_states]; len + 1];
+        layers[0][dfa.initial] = true;
+        for (idx, var) in array.iter().enumerate() {
+            for state in 0..nb_states {
+                if !layers[idx][state] {
+                    continue;
+                }
+                for value in var.iter() {
+                    if let Some(next) = dfa.next(state, value) {
+                        layers[idx + 1][next] = true;
+                    }
+                }
+            }
+        }
+
+        // Backward pass: states leading to an accepting state.
+        for (reached, &accepting) in layers[len].iter_mut().zip(dfa.accepting.iter()) {
+            *reached &= accepting;
+        }
+        for idx in (0..len).rev() {
+            let var = array.get_unchecked(idx);
+            for state in 0..nb_states {
+                if layers[idx][state] {
+                    layers[idx][state] = var.iter().any(|value| {
+                        dfa.next(state, value)
+                            .is_some_and(|next| layers[idx + 1][next])
+                    });
+                }
+            }
+        }
+        if !layers[0][dfa.initial] {
+            return Err(VariableError::DomainWipeout);
+        }
+
+        for (idx, var) in array.iter_mut().enumerate() {
+            let (from, to) = (&layers[idx], &layers[idx + 1]);
+            let state = var.retains_if(|&value| {
+                (0..nb_states).any(|state| {
+                    from[state] && dfa.next(state, value).is_some_and(|next| to[next])
+                })
+            })?;
+            match state {
+                VariableState::NoChange => {}
+                state => {
+                    output.push((var.id(), state));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<Var, VarArray, Views, Handler> Constraint<Handler> for Regular<Views>
+where
+    Handler: VariablesHandler + VariableContainerHandler<VarArray, View = Views> + Clone,
+    Views: VariableContainerView<Container = VarArray> + 'static,
+    Var: PrunableDomain<Type = i32> + IterableDomain,
+    VarArray: Array<Variable = Var>,
+{
+    fn box_clone(&self) -> Box<Constraint<Handler>> {
+        let ref_self: &Regular<Views> = self;
+        let cloned: Regular<Views> = <Regular<Views> as Clone>::clone(ref_self);
+
+        Box::new(cloned) as Box<Constraint<Handler>>
+    }
+    fn propagate(
+        &mut self,
+        variables_handler: &mut Handler,
+    ) -> Result<PropagationState, VariableError> {
+        let mut output = vec![];
+        self.output = None;
+
+        self.filter(variables_handler.get_mut(&self.array), &mut output)?;
+
+        if !output.is_empty() {
+            self.output = Some(output);
+            Ok(PropagationState::FixPoint)
+        } else {
+            Ok(PropagationState::NoChange)
+        }
+    }
+    #[allow(unused)]
+    fn prepare(&mut self, states: Box<Iterator<Item = VariableId>>) {
+        // Do nothing
+    }
+    fn result(&mut self) -> Box<Iterator<Item = (VariableId, VariableState)>> {
+        use std::mem;
+        let mut res = None;
+        mem::swap(&mut self.output, &mut res);
+        match res {
+            None => Box::new(vec![].into_iter()),
+            Some(changes) => Box::new(changes.into_iter()),
+        }
+    }
+    fn dependencies(
+        &self,
+        variables_handler: &Handler,
+    ) -> Box<Iterator<Item = (VariableId, VariableState)>> {
+        let deps: Vec<_> = variables_handler
+            .get(&self.array)
+            .iter()
+            .map(|var| (var.id(), VariableState::ValuesChange))
+            .collect();
+        Box::new(deps.into_iter())
+    }
+    fn initialise(
+        &mut self,
+        variables_handler: &mut Handler,
+    ) -> Result<PropagationState, VariableError> {
+        self.propagate(variables_handler)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use branchers::values_selector::MinValueSelector;
+    use branchers::variables_selector::SequentialVariableSelector;
+    use model::Model;
+    use search::Solver;
+    use variables::domains::AssignableDomain;
+    use variables::int_var::IntVarValues;
+    use variables::Variable;
+
+    // Words over {0, 1} without two consecutive 1.
+    fn no_consecutive_ones() -> Dfa {
+        Dfa::new(2, 0, vec![(0, 0, 0), (0, 1, 1), (1, 0, 0)], vec![0, 1]).unwrap()
+    }
+
+    #[test]
+    fn dfa() {
+        let dfa = no_consecutive_ones();
+        assert_eq!(dfa.nb_states(), 2);
+        assert!(dfa.accepts(vec![1, 0, 1, 0, 0]));
+        assert!(!dfa.accepts(vec![0, 1, 1]));
+        assert!(Dfa::new(2, 2, vec![], vec![0]).is_none());
+        assert!(Dfa::new(2, 0, vec![(0, 1, 2)], vec![0]).is_none());
+        assert!(Dfa::new(2, 0, vec![(0, 1, 0), (0, 1, 1)], vec![0]).is_none());
+    }
+
+    #[test]
+    fn solutions() {
+        // The number of words of length n is the Fibonacci number F(n + 2).
+        for &(len, expected) in [(1, 2), (3, 5), (5, 13), (8, 55)].iter() {
+            let mut model = Model::new();
+            let vars: Vec<_> =
+                (0..len).map(|_| model.new_int_var(0, 1).unwrap()).collect();
+            let array = model.new_array_of_refs(vars.clone()).unwrap();
+            model.post(Regular::new(array, no_consecutive_ones()));
+            model.branch(
+                SequentialVariableSelector::new(vars.into_iter()).unwrap(),
+                MinValueSelector::new(),
+            );
+            let space = model.build().unwrap();
+            assert_eq!(Solver::new(space).count_solutions(), expected);
+        }
+    }
+
+    #[test]
+    fn prunes() {
+        let mut model = Model::new();
+        let vars: Vec<_> = (0..3).map(|_| model.new_int_var(0, 2).unwrap()).collect();
+        let array = model.new_array_of_refs(vars.clone()).unwrap();
+        model.post(Regular::new(array, no_consecutive_ones()));
+        let mut space = model.build().unwrap();
+        space.run().unwrap();
+        // 2 is not a symbol of the automaton.
+        for view in vars.iter() {
+            let var: &IntVarValues = space.get_variable(view);
+            assert_eq!(var.iter().collect::<Vec<_>>(), vec![0, 1]);
+        }
+        {
+            let var: &mut IntVarValues = space.variables.get_mut(&vars[1]);
+            var.set_value(1).unwrap();
+        }
+        space.run().unwrap();
+        let var: &IntVarValues = space.get_variable(&vars[0]);
+        assert_eq!(var.value(), Some(0));
+        let var: &IntVarValues = space.get_variable(&vars[2]);
+        assert_eq!(var.value(), Some(0));
+    }
+
+    #[test]
+    fn infeasible() {
+        // Only the words ending with 1 are accepted.
+        let dfa = Dfa::new(
+            2,
+            0,
+            vec![(0, 0, 0), (0, 1, 1), (1, 0, 0), (1, 1, 1)],
+            vec![1],
+        )
+        .unwrap();
+        let mut model = Model::new();
+        let x = model.new_int_var(0, 1).unwrap();
+        let y = model.new_int_var(0, 0).unwrap();
+        let array = model.new_array_of_refs(vec![x, y]).unwrap();
+        model.post(Regular::new(array, dfa));
+        assert!(model.build().is_err());
+    }
+}
@@ -0,0 +1,270 @@
+use constraints::{Constraint, PropagationState, Reifiable};
+use variables::bool_var::BoolVar;
+use variables::domains::AssignableDomain;
+use variables::handlers::macros::VarView;
+use variables::handlers::{VariableContainerHandler, VariablesHandler};
+use variables::{Variable, VariableError, VariableId, VariableState};
+
+/// The link between the Boolean variable and the constraint of a `Reified`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reification {
+    /// The Boolean variable is `true` if and only if the constraint holds.
+    Full,
+    /// The constraint holds if the Boolean variable is `true`.
+    Half,
+}
+
+/// Links a Boolean variable to a constraint: `boolean <-> constraint` for a full
+/// reification and `boolean -> constraint` for a half reification.
+///
+/// The constraint is propagated once the Boolean variable is `true`, and its negation
+/// once the Boolean variable is `false` for a full reification. Otherwise the Boolean
+/// variable is assigned when the constraint is entailed or disentailed.
+#[derive(Clone)]
+pub struct Reified<Handler>
+where
+    Handler: VariablesHandler,
+{
+    boolean: VarView<BoolVar>,
+    constraint: Box<Reifiable<Handler>>,
+    reification: Reification,
+    output: Option<Vec<(VariableId, VariableState)>>,
+}
+
+impl<Handler> Reified<Handler>
+where
+    Handler: VariablesHandler,
+{
+    pub fn new<C>(
+        boolean: VarView<BoolVar>,
+        constraint: C,
+        reification: Reification,
+    ) -> Reified<Handler>
+    where
+        C: Reifiable<Handler> + 'static,
+    {
+        Reified {
+            boolean,
+            constraint: Box::new(constraint),
+            reification,
+            output: None,
+        }
+    }
+
+    pub fn full<C>(boolean: VarView<BoolVar>, constraint: C) -> Reified<Handler>
+    where
+        C: Reifiable<Handler> + 'static,
+    {
+        Reified::new(boolean, constraint, Reification::Full)
+    }
+
+    pub fn half<C>(boolean: VarView<BoolVar>, constraint: C) -> Reified<Handler>
+    where
+        C: Reifiable<Handler> + 'static,
+    {
+        Reified::new(boolean, constraint, Reification::Half)
+    }
+}
+
+impl<Handler> Constraint<Handler> for Reified<Handler>
+where
+    Handler: VariablesHandler
+        + VariableContainerHandler<BoolVar, View = VarView<BoolVar>>
+        + Clone
+        + 'static,
+{
+    fn box_clone(&self) -> Box<Constraint<Handler>> {
+        let ref_self: &Reified<Handler> = self;
+        let cloned: Reified<Handler> = <Reified<Handler> as Clone>::clone(ref_self);
+
+        Box::new(cloned) as Box<Constraint<Handler>>
+    }
+    fn propagate(
+        &mut self,
+        variables_handler: &mut Handler,
+    ) -> Result<PropagationState, VariableError> {
+        let mut output = vec![];
+        self.output = None;
+
+        let value =
+            VariableContainerHandler::<BoolVar>::get(variables_handler, &self.boolean)
+                .value();
+        match (value, self.reification) {
+            (Some(true), _) => {
+                self.constraint.propagate(variables_handler)?;
+                output.extend(self.constraint.result());
+            }
+            (Some(false), Reification::Full) => {
+                self.constraint.propagate_negation(variables_handler)?;
+                output.extend(self.constraint.result());
+            }
+            (Some(false), Reification::Half) => {}
+            (None, reification) => {
+                let value = match self.constraint.entailment(variables_handler) {
+                    Some(true) if reification == Reification::Full => Some(true),
+                    Some(false) => Some(false),
+                    _ => None,
+                };
+                if let Some(value) = value {
+                    let boolean = VariableContainerHandler::<BoolVar>::get_mut(
+                        variables_handler,
+                        &self.boolean,
+                    );
+                    match boolean.set_value(value)? {
+                        VariableState::NoChange => {}
+                        state => {
+                            output.push((boolean.id(), state));
+                        }
+                    }
+                }
+            }
+        }
+
+        if !output.is_empty() {
+            self.output = Some(output);
+            Ok(PropagationState::FixPoint)
+        } else {
+            Ok(PropagationState::NoChange)
+        }
+    }
+    fn prepare(&mut self, states: Box<Iterator<Item = VariableId>>) {
+        self.constraint.prepare(states);
+    }
+    fn result(&mut self) -> Box<Iterator<Item = (VariableId, VariableState)>> {
+        use std::mem;
+        let mut res = None;
+        mem::swap(&mut self.output, &mut res);
+        match res {
+            None => Box::new(vec![].into_iter()),
+            Some(changes) => Box::new(changes.into_iter()),
+        }
+    }
+    fn dependencies(
+        &self,
+        variables: &Handler,
+    ) -> Box<Iterator<Item = (VariableId, VariableState)>> {
+        let boolean = VariableContainerHandler::<BoolVar>::get(variables, &self.boolean);
+        let deps: Vec<_> = Some((boolean.id(), VariableState::ValuesChange))
+            .into_iter()
+            .chain(self.constraint.dependencies(variables))
+            .collect();
+        Box::new(deps.into_iter())
+    }
+    fn initialise(
+        &mut self,
+        variables_handler: &mut Handler,
+    ) -> Result<PropagationState, VariableError> {
+        self.propagate(variables_handler)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use branchers::values_selector::MinValueSelector;
+    use branchers::variables_selector::SequentialVariableSelector;
+    use constraints::arithmetic::{
+        EqualConstant, GreaterThanConstant, LessOrEqualThanConstant, LessThan,
+    };
+    use model::Model;
+    use search::Solver;
+    use variables::domains::IterableDomain;
+    use variables::int_var::IntVarValues;
+
+    #[test]
+    fn full_solutions() {
+        // b <-> (x < y) with x, y in 0..=2: b is determined by x and y.
+        let mut model = Model::new();
+        let b = model.new_bool_var();
+        let x = model.new_int_var(0, 2).unwrap();
+        let y = model.new_int_var(0, 2).unwrap();
+        model.post(Reified::full(b, LessThan::new(x, y)));
+        model.branch(
+            SequentialVariableSelector::new(vec![x, y].into_iter()).unwrap(),
+            MinValueSelector::new(),
+        );
+        let space = model.build().unwrap();
+        let solutions: Vec<_> = Solver::new(space)
+            .solutions()
+            .map(|solution| {
+                let b: &BoolVar = solution.get_variable(&b);
+                let x: &IntVarValues = solution.get_variable(&x);
+                let y: &IntVarValues = solution.get_variable(&y);
+                (b.value().unwrap(), x.value().unwrap(), y.value().unwrap())
+            })
+            .collect();
+        assert_eq!(solutions.len(), 9);
+        assert!(solutions.iter().all(|&(b, x, y)| b == (x < y)));
+    }
+
+    #[test]
+    fn half_solutions() {
+        // b -> (x = 1): b can be false for any value of x.
+        let mut model = Model::new();
+        let b = model.new_bool_var();
+        let x = model.new_int_var(0, 2).unwrap();
+        model.post(Reified::half(b, EqualConstant::new(x, 1)));
+        model.branch(
+            SequentialVariableSelector::new(vec![b].into_iter()).unwrap(),
+            MinValueSelector::new(),
+        );
+        model.branch(
+            SequentialVariableSelector::new(vec![x].into_iter()).unwrap(),
+            MinValueSelector::new(),
+        );
+        let space = model.build().unwrap();
+        assert_eq!(Solver::new(space).count_solutions(), 4);
+    }
+
+    #[test]
+    fn boolean_prunes() {
+        let mut model = Model::new();
+        let b = model.new_bool_var();
+        let c = model.new_bool_var();
+        let x = model.new_int_var(0, 5).unwrap();
+        model.post(Reified::full(b, GreaterThanConstant::new(x, 2)));
+        model.post(Reified::full(c, EqualConstant::new(x, 4)));
+        let mut space = model.build().unwrap();
+        {
+            let b: &mut BoolVar = space.variables.get_mut(&b);
+            b.set_value(false).unwrap();
+        }
+        space.run().unwrap();
+        // x <= 2 so x = 4 is disentailed.
+        let var: &IntVarValues = space.get_variable(&x);
+        assert_eq!(var.iter().collect::<Vec<_>>(), vec![0, 1, 2]);
+        let var: &BoolVar = space.get_variable(&c);
+        assert_eq!(var.value(), Some(false));
+    }
+
+    #[test]
+    fn entailment_assigns_boolean() {
+        let mut model = Model::new();
+        let b = model.new_bool_var();
+        let c = model.new_bool_var();
+        let x = model.new_int_var(0, 3).unwrap();
+        model.post(Reified::full(b, LessOrEqualThanConstant::new(x, 3)));
+        model.post(Reified::half(c, EqualConstant::new(x, 5)));
+        let mut space = model.build().unwrap();
+        space.run().unwrap();
+        let var: &BoolVar = space.get_variable(&b);
+        assert_eq!(var.value(), Some(true));
+        let var: &BoolVar = space.get_variable(&c);
+        assert_eq!(var.value(), Some(false));
+    }
+
+    #[test]
+    fn infeasible_without_search() {
+        let mut model = Model::new();
+        let b = model.new_bool_var();
+        let x = model.new_int_var(0, 3).unwrap();
+        model.post(Reified::full(b, EqualConstant::new(x, 1)));
+        model.post(Reified::half(b, GreaterThanConstant::new(x, 1)));
+        let mut space = model.build().unwrap();
+        {
+            let b: &mut BoolVar = space.variables.get_mut(&b);
+            b.set_value(true).unwrap();
+        }
+        assert!(space.run().is_err());
+    }
+}
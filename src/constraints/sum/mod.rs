@@ -8,7 +8,7 @@ use variables::domains::OrderedDomain;
 use variables::handlers::{
     VariableContainerHandler, VariableContainerView, VariablesHandler,
 };
-use variables::{Array, Variable, VariableError, VariableId, VariableState};
+use variables::{Array, VariableError, VariableId, VariableState};
 
 #[derive(Clone)]
 enum Type {
@@ -46,8 +46,8 @@ where
         Coefs: IntoIterator<Item = VarType>,
     {
         SumConstraint {
-            res: res,
-            array: array,
+            res,
+            array,
             coefs: coefs.into_iter().collect(),
             indexes: Arc::new(HashMap::new()),
             input: None,
@@ -62,8 +62,8 @@ where
     Handler: VariablesHandler
         + VariableContainerHandler<Var, View = View>
         + VariableContainerHandler<VarArray, View = Views>,
-    View: VariableContainerView + 'static,
-    Views: VariableContainerView + 'static,
+    View: VariableContainerView<Container = Var> + 'static,
+    Views: VariableContainerView<Container = VarArray> + 'static,
     Var: OrderedDomain<Type = VarType>,
     VarArray: Array<Variable = Var>,
     VarType: Ord
@@ -78,7 +78,7 @@ where
 {
     //constraint_box_clone!(Handler);
     fn box_clone(&self) -> Box<Constraint<Handler>> {
-        let ref_self: &Self = &self;
+        let ref_self: &Self = self;
         let cloned: Self = <Self as Clone>::clone(ref_self);
 
         Box::new(cloned) as Box<Constraint<Handler>>
@@ -117,7 +117,8 @@ where
             }
         }
 
-        let res: &mut Var = variables_handler.get_mut(&self.res);
+        let res: &mut Var =
+            unsafe { unsafe_from_raw_point!(variables_handler.get_mut(&self.res)) };
         let array: &mut VarArray = variables_handler.get_mut(&self.array);
 
         let _contributions: Vec<_> = array
@@ -141,8 +142,6 @@ where
         change = change || (r != VariableState::NoChange);
         let r = res.weak_lowerbound(min.clone())?;
         change = change || (r != VariableState::NoChange);
-        let mut output = vec![];
-        output.push((res.id(), r));
 
         let f = res.unchecked_max() - min;
         //if f < 0 {
@@ -201,8 +200,8 @@ where
         variables_handler: &mut Handler,
     ) -> Result<PropagationState, VariableError> {
         {
-            let res: &mut Var = variables_handler.get_mut(&self.res);
-            let array: &mut VarArray = variables_handler.get_mut(&self.array);
+            let res: &Var = variables_handler.get(&self.res);
+            let array: &VarArray = variables_handler.get(&self.array);
             let indexes = Arc::get_mut(&mut self.indexes).unwrap();
             indexes.insert(res.id(), Type::Result);
             for (pos, id) in array.iter().map(|var| var.id()).enumerate() {
@@ -0,0 +1,508 @@
+use constraints::{Constraint, PropagationState};
+use std::collections::HashMap;
+use std::sync::Arc;
+use variables::domains::{IterableDomain, PrunableDomain};
+use variables::handlers::{
+    VariableContainerHandler, VariableContainerView, VariablesHandler,
+};
+use variables::{Array, VariableError, VariableId, VariableState};
+
+// Returns the position of a word of `lhs` intersecting `rhs`, starting with
+// `residue`, the position found at the previous call.
+fn find_intersection(lhs: &[u64], rhs: &[u64], residue: usize) -> Option<usize> {
+    if lhs[residue] & rhs[residue] != 0 {
+        return Some(residue);
+    }
+    lhs.iter().zip(rhs.iter()).position(|(l, r)| l & r != 0)
+}
+
+fn count_intersection(lhs: &[u64], rhs: &[u64]) -> u64 {
+    lhs.iter()
+        .zip(rhs.iter())
+        .map(|(l, r)| u64::from((l & r).count_ones()))
+        .sum()
+}
+
+/// Extensional constraint: the variables of the array take the values of one of the
+/// tuples (positive table) or of none of them (negative table).
+///
+/// The propagation follows the compact-table algorithm. The tuples still valid are
+/// stored in a bitset, and each value of each variable has a bitset of the tuples it
+/// appears in. A value is supported by a positive table if its bitset intersects the
+/// valid tuples, and by a negative table if the valid tuples do not forbid all the
+/// combinations of values of the other variables. The valid tuples are updated from
+/// the values removed since the previous propagation, and the positive table keeps
+/// for each value the last word where a support was found (its residue).
+///
+/// # Panics
+/// Posting the table panics if a tuple and the array have different lengths.
+#[derive(Clone)]
+pub struct Table<Views>
+where
+    Views: VariableContainerView,
+{
+    array: Views,
+    positive: bool,
+    tuples: Arc<Vec<Vec<i32>>>,
+    supports: Arc<Vec<HashMap<i32, Vec<u64>>>>,
+    positions: Arc<HashMap<VariableId, Vec<usize>>>,
+    current: Vec<u64>,
+    domains: Vec<Vec<i32>>,
+    residues: Vec<HashMap<i32, usize>>,
+    input: Option<Vec<VariableId>>,
+    output: Option<Vec<(VariableId, VariableState)>>,
+}
+
+impl<Views> Table<Views>
+where
+    Views: VariableContainerView,
+{
+    /// Returns a table where the variables have to take the values of one of the tuples.
+    pub fn new(array: Views, tuples: Vec<Vec<i32>>) -> Table<Views> {
+        Table::new_with_sign(array, tuples, true)
+    }
+
+    /// Returns a table where the variables can not take the values of any of the tuples.
+    pub fn new_negative(array: Views, tuples: Vec<Vec<i32>>) -> Table<Views> {
+        Table::new_with_sign(array, tuples, false)
+    }
+
+    fn new_with_sign(
+        array: Views,
+        mut tuples: Vec<Vec<i32>>,
+        positive: bool,
+    ) -> Table<Views> {
+        tuples.sort();
+        tuples.dedup();
+        Table {
+            array,
+            positive,
+            tuples: Arc::new(tuples),
+            supports: Arc::new(vec![]),
+            positions: Arc::new(HashMap::new()),
+            current: vec![],
+            domains: vec![],
+            residues: vec![],
+            input: None,
+            output: None,
+        }
+    }
+}
+
+impl<Views> Table<Views>
+where
+    Views: VariableContainerView,
+{
+    // Removes from the valid tuples the ones containing a value removed since the
+    // last update. Only the variables reported by `prepare` are visited, or all of
+    // them when no change is reported.
+    fn update_tuples<Var, VarArray>(&mut self, array: &VarArray)
+    where
+        Var: PrunableDomain<Type = i32> + IterableDomain,
+        VarArray: Array<Variable = Var>,
+    {
+        use std::mem;
+        let mut input = None;
+        mem::swap(&mut input, &mut self.input);
+        let positions: Vec<usize> = match input {
+            Some(ref changes) if !changes.is_empty() => changes
+                .iter()
+                .filter_map(|id| self.positions.get(id))
+                .flat_map(|positions| positions.iter().cloned())
+                .collect(),
+            _ => (0..self.domains.len()).collect(),
+        };
+        for pos in positions {
+            let var = array.get_unchecked(pos);
+            if var.size() == self.domains[pos].len() {
+                continue;
+            }
+            let mut domain: Vec<i32> = var.iter().collect();
+            domain.sort();
+            let removed: Vec<i32> = {
+                let mut values = domain.iter().peekable();
+                self.domains[pos]
+                    .iter()
+                    .cloned()
+                    .filter(|value| {
+                        while values.peek().is_some_and(|&kept| kept < value) {
+                            values.next();
+                        }
+                        values.peek() != Some(&value)
+                    })
+                    .collect()
+            };
+            // Either the tuples of the removed values are invalidated, or only the
+            // tuples of the remaining values are kept, whichever visits fewer values.
+            let (values, keep) = if removed.len() < domain.len() {
+                (&removed, false)
+            } else {
+                (&domain, true)
+            };
+            let mut mask = vec![0; self.current.len()];
+            for value in values.iter() {
+                if let Some(tuples) = self.supports[pos].get(value) {
+                    for (word, tuple) in mask.iter_mut().zip(tuples.iter()) {
+                        *word |= tuple;
+                    }
+                }
+            }
+            for (word, mask) in self.current.iter_mut().zip(mask) {
+                if keep {
+                    *word &= mask;
+                } else {
+                    *word &= !mask;
+                }
+            }
+            self.domains[pos] = domain;
+        }
+    }
+
+    fn filter_positive<Var, VarArray>(
+        &mut self,
+        array: &mut VarArray,
+        output: &mut Vec<(VariableId, VariableState)>,
+    ) -> Result<(), VariableError>
+    where
+        Var: PrunableDomain<Type = i32> + IterableDomain,
+        VarArray: Array<Variable = Var>,
+    {
+        if self.current.iter().all(|&word| word == 0) {
+            return Err(VariableError::DomainWipeout);
+        }
+        let current = &self.current;
+        let vars = self
+            .supports
+            .iter()
+            .zip(self.residues.iter_mut())
+            .zip(self.domains.iter_mut())
+            .zip(array.iter_mut());
+        for (((supports, residues), domain), var) in vars {
+            let state = var.retains_if(|value| {
+                let tuples = match supports.get(value) {
+                    Some(tuples) => tuples,
+                    None => return false,
+                };
+                let residue = residues.get(value).cloned().unwrap_or(0);
+                match find_intersection(current, tuples, residue) {
+                    Some(residue) => {
+                        residues.insert(*value, residue);
+                        true
+                    }
+                    None => false,
+                }
+            })?;
+            match state {
+                VariableState::NoChange => {}
+                state => {
+                    // The removed values have no valid tuple, so only the domain is
+                    // updated.
+                    *domain = var.iter().collect();
+                    domain.sort();
+                    output.push((var.id(), state));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Removing a value reduces the number of combinations of the other variables, so
+    // the filtering is repeated until no value is removed.
+    fn filter_negative<Var, VarArray>(
+        &mut self,
+        array: &mut VarArray,
+        output: &mut Vec<(VariableId, VariableState)>,
+    ) -> Result<(), VariableError>
+    where
+        Var: PrunableDomain<Type = i32> + IterableDomain,
+        VarArray: Array<Variable = Var>,
+    {
+        loop {
+            let sizes: Vec<u64> = array.iter().map(|var| var.size() as u64).collect();
+            let mut changed = false;
+            for (pos, (supports, var)) in
+                self.supports.iter().zip(array.iter_mut()).enumerate()
+            {
+                let combinations = sizes
+                    .iter()
+                    .enumerate()
+                    .filter(|&(other, _)| other != pos)
+                    .fold(1u64, |acc, (_, &size)| acc.saturating_mul(size));
+                let current = &self.current;
+                let state = var.remove_if(|value| {
+                    supports.get(value).is_some_and(|tuples| {
+                        count_intersection(current, tuples) >= combinations
+                    })
+                })?;
+                match state {
+                    VariableState::NoChange => {}
+                    state => {
+                        changed = true;
+                        output.push((var.id(), state));
+                    }
+                }
+            }
+            if !changed {
+                return Ok(());
+            }
+            self.update_tuples(array);
+        }
+    }
+}
+
+impl<Var, VarArray, Views, Handler> Constraint<Handler> for Table<Views>
+where
+    Handler: VariablesHandler + VariableContainerHandler<VarArray, View = Views> + Clone,
+    Views: VariableContainerView<Container = VarArray> + 'static,
+    Var: PrunableDomain<Type = i32> + IterableDomain,
+    VarArray: Array<Variable = Var>,
+{
+    fn box_clone(&self) -> Box<Constraint<Handler>> {
+        let ref_self: &Table<Views> = self;
+        let cloned: Table<Views> = <Table<Views> as Clone>::clone(ref_self);
+
+        Box::new(cloned) as Box<Constraint<Handler>>
+    }
+    fn propagate(
+        &mut self,
+        variables_handler: &mut Handler,
+    ) -> Result<PropagationState, VariableError> {
+        let mut output = vec![];
+        self.output = None;
+
+        {
+            let array: &mut VarArray = variables_handler.get_mut(&self.array);
+            self.update_tuples(array);
+            if self.positive {
+                self.filter_positive(array, &mut output)?;
+            } else {
+                self.filter_negative(array, &mut output)?;
+            }
+        }
+
+        if !output.is_empty() {
+            self.output = Some(output);
+            Ok(PropagationState::FixPoint)
+        } else {
+            Ok(PropagationState::NoChange)
+        }
+    }
+    fn prepare(&mut self, states: Box<Iterator<Item = VariableId>>) {
+        self.input = Some(states.collect());
+    }
+    fn result(&mut self) -> Box<Iterator<Item = (VariableId, VariableState)>> {
+        use std::mem;
+        let mut res = None;
+        mem::swap(&mut self.output, &mut res);
+        match res {
+            None => Box::new(vec![].into_iter()),
+            Some(changes) => Box::new(changes.into_iter()),
+        }
+    }
+    fn dependencies(
+        &self,
+        variables_handler: &Handler,
+    ) -> Box<Iterator<Item = (VariableId, VariableState)>> {
+        let deps: Vec<_> = variables_handler
+            .get(&self.array)
+            .iter()
+            .map(|var| (var.id(), VariableState::ValuesChange))
+            .collect();
+        Box::new(deps.into_iter())
+    }
+    fn initialise(
+        &mut self,
+        variables_handler: &mut Handler,
+    ) -> Result<PropagationState, VariableError> {
+        let (len, positions, domains) = {
+            let array = variables_handler.get(&self.array);
+            let mut positions = HashMap::new();
+            let mut domains = Vec::with_capacity(array.len());
+            for (pos, var) in array.iter().enumerate() {
+                positions.entry(var.id()).or_insert_with(Vec::new).push(pos);
+                let mut domain: Vec<i32> = var.iter().collect();
+                domain.sort();
+                domains.push(domain);
+            }
+            (array.len(), positions, domains)
+        };
+        assert!(
+            self.tuples.iter().all(|tuple| tuple.len() == len),
+            "Error Table tuples and array of different lengths."
+        );
+        let nb_tuples = self.tuples.len();
+        let nb_words = nb_tuples.div_ceil(64);
+        let mut supports = vec![HashMap::new(); len];
+        for (idx, tuple) in self.tuples.iter().enumerate() {
+            for (pos, &value) in tuple.iter().enumerate() {
+                let tuples = supports[pos]
+                    .entry(value)
+                    .or_insert_with(|| vec![0; nb_words]);
+                tuples[idx / 64] |= 1 << (idx % 64);
+            }
+        }
+        self.supports = Arc::new(supports);
+        self.positions = Arc::new(positions);
+        self.residues = vec![HashMap::new(); len];
+        self.current = vec![!0; nb_words];
+        if !nb_tuples.is_multiple_of(64) {
+            self.current[nb_words - 1] = (1 << (nb_tuples % 64)) - 1;
+        }
+        // The values of the tuples are added to the domains, so the tuples containing
+        // a value out of the domains are removed by the first update.
+        self.domains = domains
+            .into_iter()
+            .zip(self.supports.iter())
+            .map(|(mut domain, supports)| {
+                domain.extend(supports.keys().cloned());
+                domain.sort();
+                domain.dedup();
+                domain
+            })
+            .collect();
+        self.input = None;
+        self.propagate(variables_handler)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use branchers::values_selector::MinValueSelector;
+    use branchers::variables_selector::SequentialVariableSelector;
+    use model::tests::values;
+    use model::Model;
+    use search::Solver;
+    use variables::domains::AssignableDomain;
+    use variables::handlers::macros::VarView;
+    use variables::int_var::IntVarValues;
+    use variables::Variable;
+
+    fn tuples() -> Vec<Vec<i32>> {
+        vec![
+            vec![0, 0, 1],
+            vec![0, 2, 2],
+            vec![1, 1, 0],
+            vec![2, 0, 2],
+            vec![2, 2, 1],
+        ]
+    }
+
+    fn table_model(positive: bool) -> (Model, Vec<VarView<IntVarValues>>) {
+        let mut model = Model::new();
+        let vars: Vec<_> = (0..3).map(|_| model.new_int_var(0, 2).unwrap()).collect();
+        let array = model.new_array_of_refs(vars.clone()).unwrap();
+        if positive {
+            model.post(Table::new(array, tuples()));
+        } else {
+            model.post(Table::new_negative(array, tuples()));
+        }
+        model.branch(
+            SequentialVariableSelector::new(vars.clone().into_iter()).unwrap(),
+            MinValueSelector::new(),
+        );
+        (model, vars)
+    }
+
+    #[test]
+    fn solutions() {
+        let (model, _) = table_model(true);
+        assert_eq!(Solver::new(model.build().unwrap()).count_solutions(), 5);
+        let (model, _) = table_model(false);
+        assert_eq!(
+            Solver::new(model.build().unwrap()).count_solutions(),
+            27 - 5
+        );
+    }
+
+    #[test]
+    fn solutions_with_other_constraints() {
+        // More than 64 tuples, the tuples with x0 + x1 + x2 + x3 divisible by 3
+        // over 0..=3, with x0 < x1 posted separately.
+        use constraints::arithmetic::LessThan;
+        let mut tuples = vec![];
+        for x in 0..256 {
+            let tuple: Vec<i32> = (0..4).map(|pos| (x >> (2 * pos)) & 3).collect();
+            if tuple.iter().sum::<i32>() % 3 == 0 {
+                tuples.push(tuple);
+            }
+        }
+        let expected = tuples.iter().filter(|tuple| tuple[0] < tuple[1]).count();
+        let mut model = Model::new();
+        let vars: Vec<_> = (0..4).map(|_| model.new_int_var(0, 3).unwrap()).collect();
+        let array = model.new_array_of_refs(vars.clone()).unwrap();
+        model.post(Table::new(array, tuples));
+        model.post(LessThan::new(vars[0], vars[1]));
+        model.branch(
+            SequentialVariableSelector::new(vars.into_iter()).unwrap(),
+            MinValueSelector::new(),
+        );
+        let space = model.build().unwrap();
+        assert_eq!(Solver::new(space).count_solutions(), expected);
+    }
+
+    #[test]
+    fn positive_prunes() {
+        let (mut model, vars) = table_model(true);
+        let first = model.new_array_of_refs(vec![vars[0]]).unwrap();
+        model.post(Table::new(first, vec![vec![0], vec![2]]));
+        let mut space = model.build().unwrap();
+        space.run().unwrap();
+        assert_eq!(values(&space, &vars[1]), vec![0, 2]);
+        // Incremental updates after the removal of values.
+        {
+            let var: &mut IntVarValues = space.variables.get_mut(&vars[2]);
+            var.set_value(2).unwrap();
+        }
+        space.run().unwrap();
+        assert_eq!(values(&space, &vars[0]), vec![0, 2]);
+        {
+            let var: &mut IntVarValues = space.variables.get_mut(&vars[1]);
+            var.set_value(2).unwrap();
+        }
+        space.run().unwrap();
+        let var: &IntVarValues = space.get_variable(&vars[0]);
+        assert_eq!(var.value(), Some(0));
+    }
+
+    #[test]
+    fn negative_prunes() {
+        let (model, vars) = table_model(false);
+        let mut space = model.build().unwrap();
+        {
+            let var: &mut IntVarValues = space.variables.get_mut(&vars[0]);
+            var.set_value(0).unwrap();
+            let var: &mut IntVarValues = space.variables.get_mut(&vars[1]);
+            var.set_value(2).unwrap();
+        }
+        space.run().unwrap();
+        // (0, 2, 2) is forbidden.
+        assert_eq!(values(&space, &vars[2]), vec![0, 1]);
+    }
+
+    #[test]
+    fn infeasible() {
+        let mut model = Model::new();
+        let x = model.new_int_var(3, 4).unwrap();
+        let y = model.new_int_var(0, 2).unwrap();
+        let array = model.new_array_of_refs(vec![x, y]).unwrap();
+        model.post(Table::new(array, vec![vec![0, 1], vec![2, 2]]));
+        assert!(model.build().is_err());
+        // Every combination is forbidden.
+        let mut model = Model::new();
+        let x = model.new_int_var(0, 1).unwrap();
+        let array = model.new_array_of_refs(vec![x]).unwrap();
+        model.post(Table::new_negative(array, vec![vec![0], vec![1]]));
+        assert!(model.build().is_err());
+    }
+
+    #[test]
+    #[should_panic]
+    fn arity_mismatch() {
+        let mut model = Model::new();
+        let x = model.new_int_var(0, 1).unwrap();
+        let array = model.new_array_of_refs(vec![x]).unwrap();
+        model.post(Table::new(array, vec![vec![0, 1]]));
+        let _ = model.build();
+    }
+}
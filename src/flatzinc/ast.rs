@@ -0,0 +1,93 @@
+/// Represents the domain of a FlatZinc declaration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Domain {
+    /// `bool`
+    Bool,
+    /// `int`
+    Int,
+    /// `min..max`
+    Range(i32, i32),
+    /// `{v1, v2, ...}`
+    Set(Vec<i32>),
+    /// `set of int` or `set of min..max`, only allowed for parameters.
+    SetOfInt,
+}
+
+/// Represents a FlatZinc expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    Bool(bool),
+    Int(i32),
+    /// `min..max`
+    Range(i32, i32),
+    /// `{v1, v2, ...}`
+    Set(Vec<i32>),
+    /// Name of a parameter or of a variable.
+    Ident(String),
+    /// `name[idx]`, FlatZinc arrays start at 1.
+    ArrayAccess(String, usize),
+    /// `[e1, e2, ...]`
+    Array(Vec<Expr>),
+    Str(String),
+    /// Nested annotation like `int_search(x, input_order, indomain_min, complete)`.
+    Call(Annotation),
+}
+
+/// Represents an annotation `:: name` or `:: name(args)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Annotation {
+    pub name: String,
+    pub args: Vec<Expr>,
+}
+
+/// Represents a parameter declaration `int: n = 4;` or `array [1..2] of int: c = [1, 2];`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Parameter {
+    pub name: String,
+    pub domain: Domain,
+    pub value: Expr,
+}
+
+/// Represents a variable declaration `var 1..4: x;` or
+/// `array [1..2] of var int: y = [x, z];`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VarDecl {
+    pub name: String,
+    pub domain: Domain,
+    /// Length of the array or `None` for a single variable.
+    pub len: Option<usize>,
+    pub value: Option<Expr>,
+    pub annotations: Vec<Annotation>,
+}
+
+/// Represents a constraint item `constraint int_le(x, y);`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConstraintItem {
+    pub name: String,
+    pub args: Vec<Expr>,
+    pub annotations: Vec<Annotation>,
+}
+
+/// Describes the goal of the solve item.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Goal {
+    Satisfy,
+    Minimize(Expr),
+    Maximize(Expr),
+}
+
+/// Represents the solve item `solve :: int_search(...) satisfy;`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolveItem {
+    pub goal: Goal,
+    pub annotations: Vec<Annotation>,
+}
+
+/// Represents a whole FlatZinc model. The items are kept in declaration order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FlatZincModel {
+    pub parameters: Vec<Parameter>,
+    pub variables: Vec<VarDecl>,
+    pub constraints: Vec<ConstraintItem>,
+    pub solve: SolveItem,
+}
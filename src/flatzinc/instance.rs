@@ -0,0 +1,1126 @@
+use super::ast::{
+    Annotation, ConstraintItem, Domain, Expr, FlatZincModel, Goal, VarDecl,
+};
+use super::FlatZincError;
+use branchers::values_selector::{
+    DomainOrderValueSelector, MaxValueSelector, MinValueSelector,
+};
+use branchers::variables_selector::{
+    SequentialVariableSelector, SmallestDomainVariableSelector,
+};
+use constraints::arithmetic::{
+    Abs, AddConstant, Div, Equal, LessOrEqualThan, LessThan, Max, Min, Mod, Power, Times,
+};
+use constraints::{
+    to_i32, AllDifferent, Channel, ConstantElement, Element, Linear, LinearNotEqual,
+    LinearRelation, Reified,
+};
+use model::{Model, ModelError, ModelSpace};
+use search::branch_and_bound::Objective;
+use std::collections::HashMap;
+use variables::handlers::macros::VarView;
+use variables::bool_var::BoolVar;
+use variables::handlers::VariableContainerHandler;
+use variables::int_var::IntVarValues;
+use variables::Variable;
+
+/// The `Space` built from a FlatZinc model.
+pub type FlatZincSpace = ModelSpace;
+
+type IntView = VarView<IntVarValues>;
+
+/// Largest domain accepted for a variable. The variables store every value of their
+/// domain, so the larger domains are rejected as unsupported.
+pub const MAX_DOMAIN_SIZE: i64 = 1 << 20;
+
+#[derive(Clone, Copy)]
+struct IntVar {
+    view: IntView,
+    min: i32,
+    max: i32,
+}
+
+#[derive(Clone)]
+enum Value {
+    Bool(bool),
+    Int(i32),
+    // None of the supported constraints takes a set, so only their declaration is
+    // kept.
+    Set,
+    Var(IntVar),
+    Array(Vec<Value>),
+}
+
+#[derive(Clone)]
+enum OutputValue {
+    Int(i32),
+    Bool(bool),
+    IntVar(IntView),
+    BoolVar(IntView),
+}
+
+#[derive(Clone)]
+enum Output {
+    Single(String, OutputValue),
+    Array(String, Vec<(i32, i32)>, Vec<OutputValue>),
+}
+
+#[derive(Clone)]
+enum VarDomain {
+    Range(i32, i32),
+    Values(Vec<i32>),
+}
+
+#[derive(Clone, Copy)]
+enum ValueSelection {
+    DomainOrder,
+    Min,
+    Max,
+}
+
+/// A FlatZinc model translated into a `Space`.
+///
+/// The constraints are mapped as follows:
+/// * `int_eq`, `bool_eq` and `bool2int` - `Equal`
+/// * `int_le` and `bool_le` - `LessOrEqualThan`
+/// * `int_lt` and `bool_lt` - `LessThan`
+/// * `int_ne`, `bool_not` and `all_different_int` - `AllDifferent`
+/// * `int_eq_reif`, `int_le_reif`, `int_lt_reif` and their `bool_*` counterparts -
+///   `Reified` over `Equal`, `LessOrEqualThan` and `LessThan`, the Boolean variable
+///   being linked to the `0..1` variable by a `Channel`
+/// * `int_ne_reif` and `bool_ne_reif` - `Reified` over `Equal` with the negation of
+///   the Boolean variable
+/// * `int_plus` - `AddConstant` when one of the operands is fixed, `Linear`
+///   otherwise
+/// * `int_times`, `int_div`, `int_mod`, `int_min`, `int_max`, `int_pow` and
+///   `int_abs` - `Times`, `Div`, `Mod`, `Min`, `Max`, `Power` and `Abs`
+/// * `int_lin_eq` and `int_lin_le` - `Linear`
+/// * `int_lin_ne` - `LinearNotEqual`
+/// * `array_int_element` and `array_bool_element` - `ConstantElement`
+/// * `array_var_int_element` and `array_var_bool_element` - `Element`
+///
+/// The other constraints, among which the half reifications (`*_imp`) and the
+/// reified linear constraints (`int_lin_*_reif`), are reported as unsupported.
+///
+/// The `int_search` and `bool_search` annotations (possibly nested in a
+/// `seq_search`) are translated into `DefaultBrancher`: `first_fail` uses the
+/// `SmallestDomainVariableSelector`, the other variable choices use the
+/// `SequentialVariableSelector`; `indomain_max` uses the `MaxValueSelector`,
+/// `indomain` uses the `DomainOrderValueSelector` and the other value choices use
+/// the `MinValueSelector`. A last brancher assigns the remaining variables.
+#[derive(Clone)]
+pub struct FlatZincInstance {
+    space: FlatZincSpace,
+    objective: Option<(IntView, Objective)>,
+    outputs: Vec<Output>,
+}
+
+impl FlatZincInstance {
+    pub fn new(model: &FlatZincModel) -> Result<FlatZincInstance, FlatZincError> {
+        let mut translator = Translator::new();
+        for parameter in model.parameters.iter() {
+            let value = translator.resolve(&parameter.value)?;
+            translator.values.insert(parameter.name.clone(), value);
+        }
+        let bounds = defined_bounds(model, &translator.values);
+        let mut outputs = Vec::new();
+        for decl in model.variables.iter() {
+            translator.declare(decl, &bounds)?;
+            if let Some(output) = translator.output(decl)? {
+                outputs.push(output);
+            }
+        }
+        for constraint in model.constraints.iter() {
+            translator.constraint(constraint)?;
+        }
+        for annotation in model.solve.annotations.iter() {
+            translator.search(annotation)?;
+        }
+        let objective = match model.solve.goal {
+            Goal::Satisfy => None,
+            Goal::Minimize(ref expr) => {
+                let value = translator.resolve(expr)?;
+                Some((translator.int_var(&value)?.view, Objective::Minimize))
+            }
+            Goal::Maximize(ref expr) => {
+                let value = translator.resolve(expr)?;
+                Some((translator.int_var(&value)?.view, Objective::Maximize))
+            }
+        };
+        let space = translator.finalize()?;
+        Ok(FlatZincInstance {
+            space,
+            objective,
+            outputs,
+        })
+    }
+
+    /// Returns the root space of the search.
+    pub fn space(&self) -> &FlatZincSpace {
+        &self.space
+    }
+
+    /// Returns the variable to optimise and the direction of the optimisation, or
+    /// `None` for a satisfaction problem.
+    pub fn objective(&self) -> Option<(VarView<IntVarValues>, Objective)> {
+        self.objective
+    }
+
+    /// Formats the output variables of `solution` (one line per variable) followed
+    /// by the `----------` separator. Fails if an output variable is not assigned.
+    pub fn format_solution(
+        &self,
+        solution: &FlatZincSpace,
+    ) -> Result<String, FlatZincError> {
+        let mut out = String::new();
+        for output in self.outputs.iter() {
+            match *output {
+                Output::Single(ref name, ref value) => {
+                    out.push_str(&format!(
+                        "{} = {};\n",
+                        name,
+                        format_value(solution, name, value)?
+                    ));
+                }
+                Output::Array(ref name, ref ranges, ref values) => {
+                    let ranges: Vec<_> = ranges
+                        .iter()
+                        .map(|&(min, max)| format!("{}..{}", min, max))
+                        .collect();
+                    let values = values
+                        .iter()
+                        .map(|value| format_value(solution, name, value))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    out.push_str(&format!(
+                        "{} = array{}d({}, [{}]);\n",
+                        name,
+                        ranges.len(),
+                        ranges.join(", "),
+                        values.join(", ")
+                    ));
+                }
+            }
+        }
+        out.push_str("----------");
+        Ok(out)
+    }
+}
+
+fn format_value(
+    solution: &FlatZincSpace,
+    name: &str,
+    value: &OutputValue,
+) -> Result<String, FlatZincError> {
+    let assigned = |view: &IntView| {
+        let var: &IntVarValues = solution.variables.get(view);
+        var.value()
+            .ok_or_else(|| FlatZincError::Unassigned(name.to_string()))
+    };
+    match *value {
+        OutputValue::Int(value) => Ok(format!("{}", value)),
+        OutputValue::Bool(value) => Ok(format!("{}", value)),
+        OutputValue::IntVar(ref view) => Ok(format!("{}", assigned(view)?)),
+        OutputValue::BoolVar(ref view) => Ok(format!("{}", assigned(view)? != 0)),
+    }
+}
+
+struct Translator {
+    model: Model,
+    values: HashMap<String, Value>,
+    constants: HashMap<i32, IntVar>,
+    decisions: Vec<IntView>,
+}
+
+impl Translator {
+    fn new() -> Translator {
+        Translator {
+            model: Model::new(),
+            values: HashMap::new(),
+            constants: HashMap::new(),
+            decisions: Vec::new(),
+        }
+    }
+
+    fn finalize(mut self) -> Result<FlatZincSpace, FlatZincError> {
+        // Assigns the variables not covered by the search annotations.
+        let decisions = self.decisions.clone();
+        self.brancher(decisions, false, ValueSelection::Min);
+        self.model.build().map_err(|error| match error {
+            ModelError::Inconsistent(error) => FlatZincError::Inconsistent(error),
+            error => FlatZincError::InvalidArgument(format!("{:?}", error)),
+        })
+    }
+
+    fn declare(
+        &mut self,
+        decl: &VarDecl,
+        bounds: &HashMap<String, (i32, i32)>,
+    ) -> Result<(), FlatZincError> {
+        let value = match (decl.len, decl.value.as_ref()) {
+            (_, Some(expr)) => self.resolve(expr)?,
+            (None, None) => {
+                let domain = self.domain(decl, bounds)?;
+                Value::Var(self.new_decision(domain, &decl.name)?)
+            }
+            (Some(len), None) => {
+                let domain = self.domain(decl, bounds)?;
+                let mut vars = Vec::with_capacity(len);
+                for _ in 0..len {
+                    vars.push(Value::Var(self.new_decision(domain.clone(), &decl.name)?));
+                }
+                Value::Array(vars)
+            }
+        };
+        self.values.insert(decl.name.clone(), value);
+        Ok(())
+    }
+
+    fn domain(
+        &self,
+        decl: &VarDecl,
+        bounds: &HashMap<String, (i32, i32)>,
+    ) -> Result<VarDomain, FlatZincError> {
+        match decl.domain {
+            Domain::Bool => Ok(VarDomain::Range(0, 1)),
+            Domain::Range(min, max) => Ok(VarDomain::Range(min, max)),
+            Domain::Set(ref values) => Ok(VarDomain::Values(values.clone())),
+            // The variables without domain are supported only if their bounds can be
+            // inferred from the constraint defining them.
+            Domain::Int => match bounds.get(&decl.name) {
+                Some(&(min, max)) => Ok(VarDomain::Range(min, max)),
+                None => Err(FlatZincError::Unsupported(format!(
+                    "unbounded var int {}",
+                    decl.name
+                ))),
+            },
+            Domain::SetOfInt => {
+                Err(FlatZincError::Unsupported("var set of int".to_string()))
+            }
+        }
+    }
+
+    fn output(&self, decl: &VarDecl) -> Result<Option<Output>, FlatZincError> {
+        let is_bool = decl.domain == Domain::Bool;
+        let output_value = |value: &Value| match *value {
+            Value::Int(value) => Ok(OutputValue::Int(value)),
+            Value::Bool(value) => Ok(OutputValue::Bool(value)),
+            Value::Var(var) if is_bool => Ok(OutputValue::BoolVar(var.view)),
+            Value::Var(var) => Ok(OutputValue::IntVar(var.view)),
+            _ => Err(FlatZincError::InvalidArgument(decl.name.clone())),
+        };
+        for annotation in decl.annotations.iter() {
+            match annotation.name.as_str() {
+                "output_var" => {
+                    let value = output_value(&self.values[&decl.name])?;
+                    return Ok(Some(Output::Single(decl.name.clone(), value)));
+                }
+                "output_array" => {
+                    let ranges = match annotation.args.first() {
+                        Some(Expr::Array(ranges)) => ranges
+                            .iter()
+                            .map(|range| match *range {
+                                Expr::Range(min, max) => Ok((min, max)),
+                                _ => Err(FlatZincError::InvalidArgument(
+                                    "output_array".to_string(),
+                                )),
+                            })
+                            .collect::<Result<Vec<_>, _>>()?,
+                        _ => {
+                            return Err(FlatZincError::InvalidArgument(
+                                "output_array".to_string(),
+                            ))
+                        }
+                    };
+                    let values = self
+                        .array(&self.values[&decl.name])?
+                        .iter()
+                        .map(output_value)
+                        .collect::<Result<Vec<_>, _>>()?;
+                    return Ok(Some(Output::Array(decl.name.clone(), ranges, values)));
+                }
+                _ => {}
+            }
+        }
+        Ok(None)
+    }
+
+    fn constraint(&mut self, item: &ConstraintItem) -> Result<(), FlatZincError> {
+        match item.name.as_str() {
+            "int_eq" | "bool_eq" | "bool2int" => {
+                let args = self.args(item, 2)?;
+                let (x, y) = (self.int_var(&args[0])?, self.int_var(&args[1])?);
+                self.model.post(Equal::new(x.view, y.view));
+            }
+            "int_le" | "bool_le" => {
+                let args = self.args(item, 2)?;
+                let (x, y) = (self.int_var(&args[0])?, self.int_var(&args[1])?);
+                self.model.post(LessOrEqualThan::new(x.view, y.view));
+            }
+            "int_lt" | "bool_lt" => {
+                let args = self.args(item, 2)?;
+                let (x, y) = (self.int_var(&args[0])?, self.int_var(&args[1])?);
+                self.model.post(LessThan::new(x.view, y.view));
+            }
+            "int_ne" | "bool_not" => {
+                let args = self.args(item, 2)?;
+                let (x, y) = (self.int_var(&args[0])?, self.int_var(&args[1])?);
+                self.all_different(vec![x, y]);
+            }
+            "int_eq_reif" | "bool_eq_reif" => {
+                let args = self.args(item, 3)?;
+                let (x, y) = (self.int_var(&args[0])?, self.int_var(&args[1])?);
+                let boolean = self.int_var(&args[2])?;
+                let boolean = self.boolean(boolean);
+                self.model
+                    .post(Reified::full(boolean, Equal::new(x.view, y.view)));
+            }
+            "int_ne_reif" | "bool_ne_reif" => {
+                // `b <-> x != y` is posted as `not b <-> x == y`.
+                let args = self.args(item, 3)?;
+                let (x, y) = (self.int_var(&args[0])?, self.int_var(&args[1])?);
+                let boolean = self.int_var(&args[2])?;
+                let negation = self.negation(boolean, &item.name)?;
+                let boolean = self.boolean(negation);
+                self.model
+                    .post(Reified::full(boolean, Equal::new(x.view, y.view)));
+            }
+            "int_le_reif" | "bool_le_reif" => {
+                let args = self.args(item, 3)?;
+                let (x, y) = (self.int_var(&args[0])?, self.int_var(&args[1])?);
+                let boolean = self.int_var(&args[2])?;
+                let boolean = self.boolean(boolean);
+                self.model
+                    .post(Reified::full(boolean, LessOrEqualThan::new(x.view, y.view)));
+            }
+            "int_lt_reif" | "bool_lt_reif" => {
+                let args = self.args(item, 3)?;
+                let (x, y) = (self.int_var(&args[0])?, self.int_var(&args[1])?);
+                let boolean = self.int_var(&args[2])?;
+                let boolean = self.boolean(boolean);
+                self.model
+                    .post(Reified::full(boolean, LessThan::new(x.view, y.view)));
+            }
+            "all_different_int" | "fzn_all_different_int" => {
+                let args = self.args(item, 1)?;
+                let vars = self.int_vars(&args[0])?;
+                self.all_different(vars);
+            }
+            "int_plus" => {
+                let args = self.args(item, 3)?;
+                match (&args[0], &args[1]) {
+                    (&Value::Int(x), y) | (y, &Value::Int(x)) => {
+                        let (y, z) = (self.int_var(y)?, self.int_var(&args[2])?);
+                        self.model.post(AddConstant::new(z.view, y.view, x));
+                    }
+                    _ => {
+                        let vars = vec![
+                            self.int_var(&args[0])?,
+                            self.int_var(&args[1])?,
+                            self.int_var(&args[2])?,
+                        ];
+                        self.linear(vec![1, 1, -1], vars, 0, &item.name)?;
+                    }
+                }
+            }
+            "int_times" | "int_div" | "int_mod" | "int_min" | "int_max" | "int_pow" => {
+                let args = self.args(item, 3)?;
+                let (x, y) = (self.int_var(&args[0])?, self.int_var(&args[1])?);
+                let (x, y, z) = (x.view, y.view, self.int_var(&args[2])?.view);
+                match item.name.as_str() {
+                    "int_times" => self.model.post(Times::new(x, y, z)),
+                    "int_div" => self.model.post(Div::new(x, y, z)),
+                    "int_mod" => self.model.post(Mod::new(x, y, z)),
+                    "int_min" => self.model.post(Min::new(x, y, z)),
+                    "int_max" => self.model.post(Max::new(x, y, z)),
+                    _ => self.model.post(Power::new(x, y, z)),
+                }
+            }
+            "int_abs" => {
+                let args = self.args(item, 2)?;
+                let (x, y) = (self.int_var(&args[0])?, self.int_var(&args[1])?);
+                self.model.post(Abs::new(x.view, y.view));
+            }
+            "int_lin_eq" | "int_lin_le" | "int_lin_ne" => {
+                let args = self.args(item, 3)?;
+                let coefs = self.ints(&args[0])?;
+                let vars = self.int_vars(&args[1])?;
+                let rhs = self.int(&args[2])?;
+                if coefs.len() != vars.len() {
+                    return Err(FlatZincError::InvalidArgument(item.name.clone()));
+                }
+                self.linear(coefs, vars, rhs, &item.name)?;
+            }
+            "array_int_element" | "array_bool_element" => {
+                let args = self.args(item, 3)?;
+                let values = self.ints(&args[1])?;
+                let index = self.int_var(&args[0])?;
+                let index = self.element_index(index, values.len(), &item.name)?;
+                let value = self.int_var(&args[2])?;
+                self.model
+                    .post(ConstantElement::new(value.view, index.view, values));
+            }
+            "array_var_int_element" | "array_var_bool_element" => {
+                let args = self.args(item, 3)?;
+                let vars = self.int_vars(&args[1])?;
+                let index = self.int_var(&args[0])?;
+                let index = self.element_index(index, vars.len(), &item.name)?;
+                let value = self.int_var(&args[2])?;
+                let views = vars.into_iter().map(|var| var.view);
+                match self.model.new_array_of_refs(views) {
+                    Ok(array) => {
+                        self.model.post(Element::new(value.view, index.view, array))
+                    }
+                    Err(_) => return Err(FlatZincError::EmptyDomain(item.name.clone())),
+                }
+            }
+            name => return Err(FlatZincError::Unsupported(name.to_string())),
+        }
+        Ok(())
+    }
+
+    // Resolves the arguments of the constraint, which must have `arity` arguments.
+    fn args(
+        &self,
+        item: &ConstraintItem,
+        arity: usize,
+    ) -> Result<Vec<Value>, FlatZincError> {
+        if item.args.len() != arity {
+            return Err(FlatZincError::InvalidArgument(item.name.clone()));
+        }
+        item.args.iter().map(|arg| self.resolve(arg)).collect()
+    }
+
+    // Returns a Boolean variable equal to the `0..1` variable `var`, the reified
+    // constraints are linked to Boolean variables.
+    fn boolean(&mut self, var: IntVar) -> VarView<BoolVar> {
+        let boolean = self.model.new_bool_var();
+        self.model.post(Channel::new(boolean, var.view));
+        boolean
+    }
+
+    // Returns a `0..1` variable equal to `1 - var`.
+    fn negation(&mut self, var: IntVar, name: &str) -> Result<IntVar, FlatZincError> {
+        let res = self.new_var(VarDomain::Range(0, 1), name)?;
+        self.linear(vec![1, 1], vec![var, res], 1, "int_lin_eq")?;
+        Ok(res)
+    }
+
+    fn all_different(&mut self, vars: Vec<IntVar>) {
+        let views = vars.into_iter().map(|var| var.view);
+        // An empty all_different is always satisfied.
+        if let Ok(array) = self.model.new_array_of_refs(views) {
+            self.model.post(AllDifferent::new(array));
+        }
+    }
+
+    // FlatZinc arrays start at 1 whereas the element constraints start at 0, so
+    // the index is shifted by one.
+    fn element_index(
+        &mut self,
+        index: IntVar,
+        len: usize,
+        name: &str,
+    ) -> Result<IntVar, FlatZincError> {
+        let min = ::std::cmp::max(index.min - 1, 0);
+        let max = ::std::cmp::min(index.max - 1, len as i32 - 1);
+        let res = self.new_var(VarDomain::Range(min, max), name)?;
+        self.model.post(AddConstant::new(index.view, res.view, 1));
+        Ok(res)
+    }
+
+    // Posts `sum(coefs[i] * vars[i]) (= or <= or !=) rhs` according to the name of
+    // the constraint.
+    fn linear(
+        &mut self,
+        coefs: Vec<i32>,
+        vars: Vec<IntVar>,
+        rhs: i32,
+        name: &str,
+    ) -> Result<(), FlatZincError> {
+        let views = vars.into_iter().map(|var| var.view);
+        let array = match self.model.new_array_of_refs(views) {
+            Ok(array) => array,
+            // An empty sum is equal to `0`.
+            Err(_) => {
+                let satisfied = match name {
+                    "int_lin_le" => 0 <= rhs,
+                    "int_lin_ne" => 0 != rhs,
+                    _ => 0 == rhs,
+                };
+                if satisfied {
+                    return Ok(());
+                }
+                return Err(FlatZincError::EmptyDomain(name.to_string()));
+            }
+        };
+        match name {
+            "int_lin_le" => {
+                self.model.post(Linear::new(
+                    array,
+                    coefs,
+                    LinearRelation::LessOrEqual,
+                    rhs,
+                ));
+            }
+            "int_lin_ne" => {
+                self.model.post(LinearNotEqual::new(array, coefs, rhs));
+            }
+            _ => {
+                self.model
+                    .post(Linear::new(array, coefs, LinearRelation::Equal, rhs));
+            }
+        }
+        Ok(())
+    }
+
+    fn search(&mut self, annotation: &Annotation) -> Result<(), FlatZincError> {
+        match annotation.name.as_str() {
+            "int_search" | "bool_search" => {
+                if annotation.args.len() < 3 {
+                    return Err(FlatZincError::InvalidArgument(annotation.name.clone()));
+                }
+                let vars = self.resolve(&annotation.args[0])?;
+                let views = self
+                    .array(&vars)?
+                    .iter()
+                    .filter_map(|value| match *value {
+                        Value::Var(var) => Some(var.view),
+                        _ => None,
+                    })
+                    .collect();
+                let first_fail = match annotation.args[1] {
+                    Expr::Ident(ref choice) => choice == "first_fail",
+                    _ => false,
+                };
+                let values = match annotation.args[2] {
+                    Expr::Ident(ref choice) if choice == "indomain" => {
+                        ValueSelection::DomainOrder
+                    }
+                    Expr::Ident(ref choice) if choice == "indomain_max" => {
+                        ValueSelection::Max
+                    }
+                    _ => ValueSelection::Min,
+                };
+                self.brancher(views, first_fail, values);
+            }
+            "seq_search" => match annotation.args.first() {
+                Some(Expr::Array(searches)) => {
+                    for search in searches.iter() {
+                        if let Expr::Call(ref search) = *search {
+                            self.search(search)?;
+                        }
+                    }
+                }
+                _ => return Err(FlatZincError::InvalidArgument(annotation.name.clone())),
+            },
+            // The other annotations are only hints, they can be ignored.
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn brancher(
+        &mut self,
+        views: Vec<IntView>,
+        first_fail: bool,
+        values: ValueSelection,
+    ) {
+        if views.is_empty() {
+            return;
+        }
+        let views = views.into_iter();
+        if first_fail {
+            let variables_selector = SmallestDomainVariableSelector::new(views).unwrap();
+            match values {
+                ValueSelection::DomainOrder => self
+                    .model
+                    .branch(variables_selector, DomainOrderValueSelector::new()),
+                ValueSelection::Min => self
+                    .model
+                    .branch(variables_selector, MinValueSelector::new()),
+                ValueSelection::Max => self
+                    .model
+                    .branch(variables_selector, MaxValueSelector::new()),
+            }
+        } else {
+            let variables_selector = SequentialVariableSelector::new(views).unwrap();
+            match values {
+                ValueSelection::DomainOrder => self
+                    .model
+                    .branch(variables_selector, DomainOrderValueSelector::new()),
+                ValueSelection::Min => self
+                    .model
+                    .branch(variables_selector, MinValueSelector::new()),
+                ValueSelection::Max => self
+                    .model
+                    .branch(variables_selector, MaxValueSelector::new()),
+            }
+        }
+    }
+
+    fn new_var(
+        &mut self,
+        domain: VarDomain,
+        name: &str,
+    ) -> Result<IntVar, FlatZincError> {
+        let (view, min, max) = match domain {
+            VarDomain::Range(min, max) => {
+                if i64::from(max) - i64::from(min) >= MAX_DOMAIN_SIZE {
+                    return Err(FlatZincError::Unsupported(format!(
+                        "domain of {} larger than {} values",
+                        name, MAX_DOMAIN_SIZE
+                    )));
+                }
+                (self.model.new_int_var(min, max), Some(min), Some(max))
+            }
+            VarDomain::Values(values) => {
+                let min = values.iter().cloned().min();
+                let max = values.iter().cloned().max();
+                (self.model.new_int_var_from_values(values), min, max)
+            }
+        };
+        match (view, min, max) {
+            (Ok(view), Some(min), Some(max)) => Ok(IntVar {
+                view,
+                min,
+                max,
+            }),
+            _ => Err(FlatZincError::EmptyDomain(name.to_string())),
+        }
+    }
+
+    // Creates a variable that has to be assigned by the search.
+    fn new_decision(
+        &mut self,
+        domain: VarDomain,
+        name: &str,
+    ) -> Result<IntVar, FlatZincError> {
+        let var = self.new_var(domain, name)?;
+        self.decisions.push(var.view);
+        Ok(var)
+    }
+
+    fn constant(&mut self, value: i32) -> IntVar {
+        if let Some(var) = self.constants.get(&value) {
+            return *var;
+        }
+        let var = IntVar {
+            view: self.model.new_int_var(value, value).unwrap(),
+            min: value,
+            max: value,
+        };
+        self.constants.insert(value, var);
+        var
+    }
+
+    fn resolve(&self, expr: &Expr) -> Result<Value, FlatZincError> {
+        match *expr {
+            Expr::Bool(value) => Ok(Value::Bool(value)),
+            Expr::Int(value) => Ok(Value::Int(value)),
+            Expr::Range(_, _) | Expr::Set(_) => Ok(Value::Set),
+            Expr::Ident(ref name) => self
+                .values
+                .get(name)
+                .cloned()
+                .ok_or_else(|| FlatZincError::UnknownIdentifier(name.clone())),
+            Expr::ArrayAccess(ref name, idx) => match self.values.get(name) {
+                Some(Value::Array(values)) => {
+                    values.get(idx - 1).cloned().ok_or_else(|| {
+                        FlatZincError::InvalidArgument(format!("{}[{}]", name, idx))
+                    })
+                }
+                Some(_) => Err(FlatZincError::InvalidArgument(name.clone())),
+                None => Err(FlatZincError::UnknownIdentifier(name.clone())),
+            },
+            Expr::Array(ref exprs) => exprs
+                .iter()
+                .map(|expr| self.resolve(expr))
+                .collect::<Result<Vec<_>, _>>()
+                .map(Value::Array),
+            Expr::Str(_) | Expr::Call(_) => {
+                Err(FlatZincError::InvalidArgument(format!("{:?}", expr)))
+            }
+        }
+    }
+
+    fn int(&self, value: &Value) -> Result<i32, FlatZincError> {
+        match *value {
+            Value::Int(value) => Ok(value),
+            Value::Bool(value) => Ok(value as i32),
+            Value::Var(var) if var.min == var.max => Ok(var.min),
+            _ => Err(FlatZincError::InvalidArgument(
+                "expected an integer".to_string(),
+            )),
+        }
+    }
+
+    fn ints(&self, value: &Value) -> Result<Vec<i32>, FlatZincError> {
+        self.array(value)?
+            .iter()
+            .map(|value| self.int(value))
+            .collect()
+    }
+
+    fn int_var(&mut self, value: &Value) -> Result<IntVar, FlatZincError> {
+        match *value {
+            Value::Var(var) => Ok(var),
+            Value::Int(value) => Ok(self.constant(value)),
+            Value::Bool(value) => Ok(self.constant(value as i32)),
+            _ => Err(FlatZincError::InvalidArgument(
+                "expected a variable".to_string(),
+            )),
+        }
+    }
+
+    fn int_vars(&mut self, value: &Value) -> Result<Vec<IntVar>, FlatZincError> {
+        let values = self.array(value)?.clone();
+        values.iter().map(|value| self.int_var(value)).collect()
+    }
+
+    fn array<'a>(&self, value: &'a Value) -> Result<&'a Vec<Value>, FlatZincError> {
+        match *value {
+            Value::Array(ref values) => Ok(values),
+            _ => Err(FlatZincError::InvalidArgument(
+                "expected an array".to_string(),
+            )),
+        }
+    }
+}
+
+// Infers the bounds of the variables declared without domain from the constraints
+// annotated with `defines_var`. FlatZinc declares the variables in their definition
+// order, so the constraints are visited only once.
+fn defined_bounds(
+    model: &FlatZincModel,
+    parameters: &HashMap<String, Value>,
+) -> HashMap<String, (i32, i32)> {
+    let mut bounds = HashMap::new();
+    let mut arrays = HashMap::new();
+    for decl in model.variables.iter() {
+        match (decl.len, &decl.domain, decl.value.as_ref()) {
+            (Some(_), _, Some(Expr::Array(values))) => {
+                arrays.insert(decl.name.clone(), values.clone());
+            }
+            (None, &Domain::Bool, _) => {
+                bounds.insert(decl.name.clone(), (0, 1));
+            }
+            (None, &Domain::Range(min, max), _) => {
+                bounds.insert(decl.name.clone(), (min, max));
+            }
+            (None, Domain::Set(values), _) if !values.is_empty() => {
+                let min = *values.iter().min().unwrap();
+                let max = *values.iter().max().unwrap();
+                bounds.insert(decl.name.clone(), (min, max));
+            }
+            _ => {}
+        }
+    }
+    for constraint in model.constraints.iter() {
+        let defined = constraint
+            .annotations
+            .iter()
+            .filter(|annotation| annotation.name == "defines_var")
+            .filter_map(|annotation| match annotation.args.first() {
+                Some(Expr::Ident(name)) => Some(name.clone()),
+                _ => None,
+            })
+            .next();
+        let defined = match defined {
+            Some(ref name) if !bounds.contains_key(name) => name.clone(),
+            _ => continue,
+        };
+        let terms = match (constraint.name.as_str(), constraint.args.as_slice()) {
+            ("int_lin_eq", &[ref coefs, ref vars, Expr::Int(rhs)]) => {
+                let coefs = match *coefs {
+                    Expr::Array(ref coefs) => coefs.clone(),
+                    Expr::Ident(ref name) => match parameters.get(name) {
+                        Some(Value::Array(coefs)) => coefs
+                            .iter()
+                            .filter_map(|coef| match *coef {
+                                Value::Int(coef) => Some(Expr::Int(coef)),
+                                _ => None,
+                            })
+                            .collect(),
+                        _ => continue,
+                    },
+                    _ => continue,
+                };
+                let vars = match *vars {
+                    Expr::Array(ref vars) => vars.clone(),
+                    Expr::Ident(ref name) => match arrays.get(name) {
+                        Some(vars) => vars.clone(),
+                        None => continue,
+                    },
+                    _ => continue,
+                };
+                let mut terms = vec![(1, Expr::Int(rhs))];
+                let mut defined_coef = 0;
+                for (coef, var) in coefs.into_iter().zip(vars) {
+                    let coef = match coef {
+                        Expr::Int(coef) => coef,
+                        _ => continue,
+                    };
+                    if var == Expr::Ident(defined.clone()) {
+                        defined_coef = coef;
+                    } else {
+                        terms.push((-coef, var));
+                    }
+                }
+                // Only the unit coefficients give exact integer bounds.
+                match defined_coef {
+                    1 => terms,
+                    -1 => terms.into_iter().map(|(coef, var)| (-coef, var)).collect(),
+                    _ => continue,
+                }
+            }
+            ("int_plus", &[ref x, ref y, Expr::Ident(ref z)]) if *z == defined => {
+                vec![(1, x.clone()), (1, y.clone())]
+            }
+            _ => continue,
+        };
+        let mut min = 0i64;
+        let mut max = 0i64;
+        let mut bounded = true;
+        for (coef, term) in terms {
+            let (lb, ub) = match term {
+                Expr::Int(value) => (value, value),
+                Expr::Ident(ref name) => match (bounds.get(name), parameters.get(name)) {
+                    (Some(&bound), _) => bound,
+                    (_, Some(&Value::Int(value))) => (value, value),
+                    _ => {
+                        bounded = false;
+                        break;
+                    }
+                },
+                _ => {
+                    bounded = false;
+                    break;
+                }
+            };
+            let (coef, lb, ub) = (i64::from(coef), i64::from(lb), i64::from(ub));
+            min = min.saturating_add(if coef > 0 { coef * lb } else { coef * ub });
+            max = max.saturating_add(if coef > 0 { coef * ub } else { coef * lb });
+        }
+        if bounded {
+            bounds.insert(defined, (to_i32(min), to_i32(max)));
+        }
+    }
+    bounds
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flatzinc::parse;
+    use search::branch_and_bound::BranchAndBoundSolver;
+    use search::Solver;
+
+    fn instance(input: &str) -> Result<FlatZincInstance, FlatZincError> {
+        FlatZincInstance::new(&parse(input).unwrap())
+    }
+
+    #[test]
+    fn satisfy() {
+        let instance = instance(
+            "var 1..3: x :: output_var;
+             var 1..3: y :: output_var;
+             array [1..2] of var int: xs :: output_array([1..2]) = [x, y];
+             constraint int_lt(x, y);
+             solve :: int_search(xs, input_order, indomain_min, complete) satisfy;",
+        )
+        .unwrap();
+        assert!(instance.objective().is_none());
+        let solver = Solver::new(instance.space().clone());
+        assert_eq!(solver.count_solutions(), 3);
+        let solution = Solver::new(instance.space().clone())
+            .solutions()
+            .next()
+            .unwrap();
+        assert_eq!(
+            instance.format_solution(&solution),
+            Ok("x = 1;\ny = 2;\nxs = array1d(1..2, [1, 2]);\n----------".to_string())
+        );
+    }
+
+    #[test]
+    fn unsatisfiable() {
+        // Detected by the initial propagation.
+        let error = instance(
+            "var 1..3: x;
+             var 1..3: y;
+             constraint int_lt(x, y);
+             constraint int_lt(y, x);
+             solve satisfy;",
+        );
+        match error {
+            Err(FlatZincError::Inconsistent(_)) => {}
+            _ => panic!("the model should be inconsistent"),
+        }
+        // Detected by the search.
+        let instance = instance(
+            "array [1..3] of var 1..2: xs;
+             constraint all_different_int(xs);
+             solve satisfy;",
+        )
+        .unwrap();
+        assert_eq!(Solver::new(instance.space().clone()).count_solutions(), 0);
+    }
+
+    #[test]
+    fn minimize() {
+        // The bounds of cost = 2x - y are inferred from its definition.
+        let instance = instance(
+            "var 0..5: x :: output_var;
+             var 0..5: y :: output_var;
+             var int: cost :: output_var :: is_defined_var;
+             constraint int_lt(x, y);
+             constraint int_lin_eq([2, -1, -1], [x, y, cost], 0) :: defines_var(cost);
+             solve minimize cost;",
+        )
+        .unwrap();
+        let (cost, direction) = instance.objective().unwrap();
+        let mut solver =
+            BranchAndBoundSolver::new(instance.space().clone(), cost, direction);
+        assert!(solver.solve());
+        assert!(solver.is_optimal());
+        assert_eq!(solver.objective_value(), Some(-5));
+        assert_eq!(
+            instance.format_solution(&solver.solution().unwrap()),
+            Ok("x = 0;\ny = 5;\ncost = -5;\n----------".to_string())
+        );
+    }
+
+    #[test]
+    fn nonlinear_arithmetic() {
+        // The other variables are functions of x and y.
+        let instance = instance(
+            "var -3..3: x;
+             var 1..3: y;
+             var -9..9: p;
+             var -3..3: q;
+             var -3..3: r;
+             var -3..3: mn;
+             var -3..3: mx;
+             var -27..27: w;
+             var 0..3: a;
+             constraint int_times(x, y, p);
+             constraint int_div(x, y, q);
+             constraint int_mod(x, y, r);
+             constraint int_min(x, y, mn);
+             constraint int_max(x, y, mx);
+             constraint int_pow(x, y, w);
+             constraint int_abs(x, a);
+             solve satisfy;",
+        )
+        .unwrap();
+        assert_eq!(Solver::new(instance.space().clone()).count_solutions(), 21);
+    }
+
+    #[test]
+    fn reified_comparisons() {
+        let free = instance(
+            "var 0..3: x;
+             var 0..3: y;
+             var bool: b;
+             var bool: c;
+             constraint int_le_reif(x, y, b);
+             constraint int_ne_reif(x, y, c);
+             solve satisfy;",
+        )
+        .unwrap();
+        assert_eq!(Solver::new(free.space().clone()).count_solutions(), 16);
+        // x <= y and x == y.
+        let fixed = instance(
+            "var 0..3: x;
+             var 0..3: y;
+             var bool: b;
+             var bool: c;
+             constraint int_le_reif(x, y, b);
+             constraint int_ne_reif(x, y, c);
+             constraint bool_eq(b, true);
+             constraint bool_eq(c, false);
+             solve satisfy;",
+        )
+        .unwrap();
+        assert_eq!(Solver::new(fixed.space().clone()).count_solutions(), 4);
+    }
+
+    #[test]
+    fn unsupported_constraints() {
+        // The name is checked before the arity.
+        let error = instance(
+            "var 0..3: x;
+             constraint unknown_constraint(x);
+             solve satisfy;",
+        );
+        assert_eq!(
+            error.err(),
+            Some(FlatZincError::Unsupported("unknown_constraint".to_string()))
+        );
+        match instance(
+            "var 0..3: x;
+             var bool: b;
+             constraint int_le_imp(x, 2, b);
+             solve satisfy;",
+        ) {
+            Err(FlatZincError::Unsupported(_)) => {}
+            _ => panic!("the constraint should not be supported"),
+        }
+        match instance(
+            "var 0..3: x;
+             constraint int_lt(x);
+             solve satisfy;",
+        ) {
+            Err(FlatZincError::InvalidArgument(_)) => {}
+            _ => panic!("the arity of the constraint should be checked"),
+        }
+    }
+
+    #[test]
+    fn unbounded_variable() {
+        let error = instance(
+            "var int: x;
+             solve satisfy;",
+        );
+        match error {
+            Err(FlatZincError::Unsupported(_)) => {}
+            _ => panic!("an unbounded variable should not be supported"),
+        }
+    }
+
+    #[test]
+    fn large_domains() {
+        let error = instance(
+            "var -2147483648..2147483647: x;
+             solve satisfy;",
+        );
+        match error {
+            Err(FlatZincError::Unsupported(_)) => {}
+            _ => panic!("the domain should be too large"),
+        }
+        // The inferred bounds saturate instead of overflowing.
+        let error = instance(
+            "var 0..100000: x;
+             var int: y :: is_defined_var;
+             constraint int_lin_eq([100000, -1], [x, y], 0) :: defines_var(y);
+             solve satisfy;",
+        );
+        match error {
+            Err(FlatZincError::Unsupported(_)) => {}
+            _ => panic!("the domain should be too large"),
+        }
+    }
+
+    #[test]
+    fn unassigned_output() {
+        let instance = instance(
+            "var 1..3: x :: output_var;
+             solve satisfy;",
+        )
+        .unwrap();
+        assert_eq!(
+            instance.format_solution(instance.space()),
+            Err(FlatZincError::Unassigned("x".to_string()))
+        );
+    }
+}
@@ -0,0 +1,58 @@
+//! FlatZinc front end. A FlatZinc model is parsed with `parse` and translated into a
+//! `Space` with `FlatZincInstance::new`, the solutions are printed in the standard
+//! FlatZinc output format with `FlatZincInstance::format_solution`.
+//!
+//! Only the integer and boolean variables are supported, the boolean variables are
+//! represented by integer variables with the domain `0..1`.
+use std::fmt;
+use variables::VariableError;
+
+pub mod ast;
+mod instance;
+mod parser;
+
+pub use self::instance::{FlatZincInstance, FlatZincSpace};
+pub use self::parser::parse;
+
+/// Represents an error that occured while reading a FlatZinc model.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FlatZincError {
+    /// The model is not a valid FlatZinc model.
+    Syntax { line: usize, message: String },
+    /// The model uses a feature that crusp does not support (float variables,
+    /// set variables or an unknown constraint).
+    Unsupported(String),
+    /// The name does not refer to any parameter or variable.
+    UnknownIdentifier(String),
+    /// The argument of a constraint or of an annotation does not have the
+    /// expected type.
+    InvalidArgument(String),
+    /// The domain of the variable is empty.
+    EmptyDomain(String),
+    /// The initial propagation of the constraints failed.
+    Inconsistent(VariableError),
+    /// The output variable is not assigned in the solution.
+    Unassigned(String),
+}
+
+impl fmt::Display for FlatZincError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            FlatZincError::Syntax { line, ref message } => {
+                write!(f, "syntax error at line {}: {}", line, message)
+            }
+            FlatZincError::Unsupported(ref what) => write!(f, "unsupported: {}", what),
+            FlatZincError::UnknownIdentifier(ref name) => {
+                write!(f, "unknown identifier: {}", name)
+            }
+            FlatZincError::InvalidArgument(ref what) => {
+                write!(f, "invalid argument: {}", what)
+            }
+            FlatZincError::EmptyDomain(ref name) => write!(f, "empty domain: {}", name),
+            FlatZincError::Inconsistent(ref error) => {
+                write!(f, "inconsistent model: {:?}", error)
+            }
+            FlatZincError::Unassigned(ref name) => write!(f, "unassigned: {}", name),
+        }
+    }
+}
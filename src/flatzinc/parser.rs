@@ -0,0 +1,498 @@
+use super::ast::{
+    Annotation, ConstraintItem, Domain, Expr, FlatZincModel, Goal, Parameter, SolveItem,
+    VarDecl,
+};
+use super::FlatZincError;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Ident(String),
+    Int(i32),
+    Str(String),
+    DotDot,
+    ColonColon,
+    Colon,
+    Semi,
+    Comma,
+    Equal,
+    LBracket,
+    RBracket,
+    LBrace,
+    RBrace,
+    LParen,
+    RParen,
+}
+
+/// Parses a FlatZinc model.
+pub fn parse(input: &str) -> Result<FlatZincModel, FlatZincError> {
+    let tokens = tokenize(input)?;
+    Parser {
+        tokens,
+        pos: 0,
+    }
+    .model()
+}
+
+fn syntax_error<T>(line: usize, message: String) -> Result<T, FlatZincError> {
+    Err(FlatZincError::Syntax {
+        line,
+        message,
+    })
+}
+
+// Each token is paired with its line for error reporting.
+fn tokenize(input: &str) -> Result<Vec<(Token, usize)>, FlatZincError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut line = 1;
+    let mut pos = 0;
+    while pos < chars.len() {
+        let c = chars[pos];
+        let next = chars.get(pos + 1).cloned();
+        match c {
+            '\n' => {
+                line += 1;
+                pos += 1;
+            }
+            c if c.is_whitespace() => pos += 1,
+            '%' => {
+                while pos < chars.len() && chars[pos] != '\n' {
+                    pos += 1;
+                }
+            }
+            '"' => {
+                let start = pos + 1;
+                pos = start;
+                while pos < chars.len() && chars[pos] != '"' {
+                    pos += 1;
+                }
+                if pos == chars.len() {
+                    return syntax_error(line, "unterminated string".to_string());
+                }
+                let value: String = chars[start..pos].iter().collect();
+                tokens.push((Token::Str(value), line));
+                pos += 1;
+            }
+            c if c.is_ascii_digit()
+                || (c == '-' && next.is_some_and(|n| n.is_ascii_digit())) =>
+            {
+                let start = pos;
+                pos += 1;
+                while pos < chars.len() && chars[pos].is_ascii_digit() {
+                    pos += 1;
+                }
+                let is_float = chars.get(pos) == Some(&'.')
+                    && chars.get(pos + 1).is_some_and(|n| n.is_ascii_digit());
+                if is_float || chars.get(pos).is_some_and(|&n| n == 'e' || n == 'E') {
+                    return Err(FlatZincError::Unsupported("float".to_string()));
+                }
+                let value: String = chars[start..pos].iter().collect();
+                match value.parse::<i32>() {
+                    Ok(value) => tokens.push((Token::Int(value), line)),
+                    Err(_) => {
+                        return syntax_error(line, format!("invalid integer {}", value));
+                    }
+                }
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let start = pos;
+                while pos < chars.len()
+                    && (chars[pos].is_alphanumeric() || chars[pos] == '_')
+                {
+                    pos += 1;
+                }
+                let value: String = chars[start..pos].iter().collect();
+                tokens.push((Token::Ident(value), line));
+            }
+            '.' if next == Some('.') => {
+                tokens.push((Token::DotDot, line));
+                pos += 2;
+            }
+            ':' if next == Some(':') => {
+                tokens.push((Token::ColonColon, line));
+                pos += 2;
+            }
+            _ => {
+                let token = match c {
+                    ':' => Token::Colon,
+                    ';' => Token::Semi,
+                    ',' => Token::Comma,
+                    '=' => Token::Equal,
+                    '[' => Token::LBracket,
+                    ']' => Token::RBracket,
+                    '{' => Token::LBrace,
+                    '}' => Token::RBrace,
+                    '(' => Token::LParen,
+                    ')' => Token::RParen,
+                    c => {
+                        return syntax_error(line, format!("unexpected character {}", c))
+                    }
+                };
+                tokens.push((token, line));
+                pos += 1;
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+}
+
+impl Parser {
+    fn model(&mut self) -> Result<FlatZincModel, FlatZincError> {
+        let mut parameters = Vec::new();
+        let mut variables = Vec::new();
+        let mut constraints = Vec::new();
+        let mut solve = None;
+        while let Some(token) = self.peek().cloned() {
+            match token {
+                Token::Ident(ref keyword) if keyword == "predicate" => {
+                    // Predicate declarations are only used by the solver specific
+                    // globals, they are skipped.
+                    while self.next()? != Token::Semi {}
+                }
+                Token::Ident(ref keyword) if keyword == "constraint" => {
+                    self.next()?;
+                    constraints.push(self.constraint()?);
+                }
+                Token::Ident(ref keyword) if keyword == "solve" => {
+                    self.next()?;
+                    solve = Some(self.solve()?);
+                }
+                Token::Ident(ref keyword) if keyword == "var" => {
+                    self.next()?;
+                    variables.push(self.var_decl(None)?);
+                }
+                Token::Ident(ref keyword) if keyword == "array" => {
+                    self.next()?;
+                    self.expect(Token::LBracket)?;
+                    self.expect(Token::Int(1))?;
+                    self.expect(Token::DotDot)?;
+                    let len = self.int()?;
+                    self.expect(Token::RBracket)?;
+                    self.keyword("of")?;
+                    if self.peek() == Some(&Token::Ident("var".to_string())) {
+                        self.next()?;
+                        variables.push(self.var_decl(Some(len as usize))?);
+                    } else {
+                        parameters.push(self.parameter()?);
+                    }
+                }
+                _ => parameters.push(self.parameter()?),
+            }
+        }
+        match solve {
+            Some(solve) => Ok(FlatZincModel {
+                parameters,
+                variables,
+                constraints,
+                solve,
+            }),
+            None => syntax_error(self.line(), "missing solve item".to_string()),
+        }
+    }
+
+    fn parameter(&mut self) -> Result<Parameter, FlatZincError> {
+        let domain = self.domain()?;
+        self.expect(Token::Colon)?;
+        let name = self.ident()?;
+        self.annotations()?;
+        self.expect(Token::Equal)?;
+        let value = self.expr()?;
+        self.expect(Token::Semi)?;
+        Ok(Parameter {
+            name,
+            domain,
+            value,
+        })
+    }
+
+    fn var_decl(&mut self, len: Option<usize>) -> Result<VarDecl, FlatZincError> {
+        let domain = self.domain()?;
+        if domain == Domain::SetOfInt {
+            return Err(FlatZincError::Unsupported("var set of int".to_string()));
+        }
+        self.expect(Token::Colon)?;
+        let name = self.ident()?;
+        let annotations = self.annotations()?;
+        let value = if self.peek() == Some(&Token::Equal) {
+            self.next()?;
+            Some(self.expr()?)
+        } else {
+            None
+        };
+        self.expect(Token::Semi)?;
+        Ok(VarDecl {
+            name,
+            domain,
+            len,
+            value,
+            annotations,
+        })
+    }
+
+    fn constraint(&mut self) -> Result<ConstraintItem, FlatZincError> {
+        let name = self.ident()?;
+        let args = self.args()?;
+        let annotations = self.annotations()?;
+        self.expect(Token::Semi)?;
+        Ok(ConstraintItem {
+            name,
+            args,
+            annotations,
+        })
+    }
+
+    fn solve(&mut self) -> Result<SolveItem, FlatZincError> {
+        let annotations = self.annotations()?;
+        let goal = match self.ident()?.as_str() {
+            "satisfy" => Goal::Satisfy,
+            "minimize" => Goal::Minimize(self.expr()?),
+            "maximize" => Goal::Maximize(self.expr()?),
+            goal => return syntax_error(self.line(), format!("unknown goal {}", goal)),
+        };
+        self.expect(Token::Semi)?;
+        Ok(SolveItem {
+            goal,
+            annotations,
+        })
+    }
+
+    fn domain(&mut self) -> Result<Domain, FlatZincError> {
+        match self.next()? {
+            Token::Ident(ref ty) if ty == "bool" => Ok(Domain::Bool),
+            Token::Ident(ref ty) if ty == "int" => Ok(Domain::Int),
+            Token::Ident(ref ty) if ty == "float" => {
+                Err(FlatZincError::Unsupported("float".to_string()))
+            }
+            Token::Ident(ref ty) if ty == "set" => {
+                self.keyword("of")?;
+                self.domain()?;
+                Ok(Domain::SetOfInt)
+            }
+            Token::Int(min) => {
+                self.expect(Token::DotDot)?;
+                let max = self.int()?;
+                Ok(Domain::Range(min, max))
+            }
+            Token::LBrace => Ok(Domain::Set(self.int_set()?)),
+            token => syntax_error(self.line(), format!("unexpected {:?}", token)),
+        }
+    }
+
+    fn expr(&mut self) -> Result<Expr, FlatZincError> {
+        match self.next()? {
+            Token::Int(value) => {
+                if self.peek() == Some(&Token::DotDot) {
+                    self.next()?;
+                    Ok(Expr::Range(value, self.int()?))
+                } else {
+                    Ok(Expr::Int(value))
+                }
+            }
+            Token::Ident(ref value) if value == "true" => Ok(Expr::Bool(true)),
+            Token::Ident(ref value) if value == "false" => Ok(Expr::Bool(false)),
+            Token::Ident(name) => match self.peek().cloned() {
+                Some(Token::LBracket) => {
+                    self.next()?;
+                    let idx = self.int()?;
+                    self.expect(Token::RBracket)?;
+                    if idx < 1 {
+                        return syntax_error(
+                            self.line(),
+                            format!("invalid index {}", idx),
+                        );
+                    }
+                    Ok(Expr::ArrayAccess(name, idx as usize))
+                }
+                Some(Token::LParen) => Ok(Expr::Call(Annotation {
+                    name,
+                    args: self.args()?,
+                })),
+                _ => Ok(Expr::Ident(name)),
+            },
+            Token::LBracket => {
+                let mut values = Vec::new();
+                if self.peek() == Some(&Token::RBracket) {
+                    self.next()?;
+                    return Ok(Expr::Array(values));
+                }
+                loop {
+                    values.push(self.expr()?);
+                    match self.next()? {
+                        Token::Comma => {}
+                        Token::RBracket => return Ok(Expr::Array(values)),
+                        token => {
+                            return syntax_error(
+                                self.line(),
+                                format!("unexpected {:?}", token),
+                            )
+                        }
+                    }
+                }
+            }
+            Token::LBrace => Ok(Expr::Set(self.int_set()?)),
+            Token::Str(value) => Ok(Expr::Str(value)),
+            token => syntax_error(self.line(), format!("unexpected {:?}", token)),
+        }
+    }
+
+    // Parses `(e1, e2, ...)`.
+    fn args(&mut self) -> Result<Vec<Expr>, FlatZincError> {
+        self.expect(Token::LParen)?;
+        let mut args = Vec::new();
+        if self.peek() == Some(&Token::RParen) {
+            self.next()?;
+            return Ok(args);
+        }
+        loop {
+            args.push(self.expr()?);
+            match self.next()? {
+                Token::Comma => {}
+                Token::RParen => return Ok(args),
+                token => {
+                    return syntax_error(self.line(), format!("unexpected {:?}", token))
+                }
+            }
+        }
+    }
+
+    fn annotations(&mut self) -> Result<Vec<Annotation>, FlatZincError> {
+        let mut annotations = Vec::new();
+        while self.peek() == Some(&Token::ColonColon) {
+            self.next()?;
+            let name = self.ident()?;
+            let args = if self.peek() == Some(&Token::LParen) {
+                self.args()?
+            } else {
+                vec![]
+            };
+            annotations.push(Annotation {
+                name,
+                args,
+            });
+        }
+        Ok(annotations)
+    }
+
+    // Parses the remaining of `{v1, v2, ...}`, the opening brace being consumed.
+    fn int_set(&mut self) -> Result<Vec<i32>, FlatZincError> {
+        let mut values = Vec::new();
+        if self.peek() == Some(&Token::RBrace) {
+            self.next()?;
+            return Ok(values);
+        }
+        loop {
+            values.push(self.int()?);
+            match self.next()? {
+                Token::Comma => {}
+                Token::RBrace => return Ok(values),
+                token => {
+                    return syntax_error(self.line(), format!("unexpected {:?}", token))
+                }
+            }
+        }
+    }
+
+    fn int(&mut self) -> Result<i32, FlatZincError> {
+        match self.next()? {
+            Token::Int(value) => Ok(value),
+            token => {
+                syntax_error(self.line(), format!("expected integer, found {:?}", token))
+            }
+        }
+    }
+
+    fn ident(&mut self) -> Result<String, FlatZincError> {
+        match self.next()? {
+            Token::Ident(value) => Ok(value),
+            token => syntax_error(
+                self.line(),
+                format!("expected identifier, found {:?}", token),
+            ),
+        }
+    }
+
+    fn keyword(&mut self, keyword: &str) -> Result<(), FlatZincError> {
+        self.expect(Token::Ident(keyword.to_string()))
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), FlatZincError> {
+        let token = self.next()?;
+        if token == expected {
+            Ok(())
+        } else {
+            syntax_error(
+                self.line(),
+                format!("expected {:?}, found {:?}", expected, token),
+            )
+        }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(token, _)| token)
+    }
+
+    fn next(&mut self) -> Result<Token, FlatZincError> {
+        match self.tokens.get(self.pos).cloned() {
+            Some((token, _)) => {
+                self.pos += 1;
+                Ok(token)
+            }
+            None => syntax_error(self.line(), "unexpected end of file".to_string()),
+        }
+    }
+
+    // Line of the last consumed token.
+    fn line(&self) -> usize {
+        let pos = if self.pos > 0 { self.pos - 1 } else { 0 };
+        self.tokens.get(pos).map_or(0, |&(_, line)| line)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_model() {
+        let model = parse(
+            "% comment
+             int: n = 2;
+             array [1..2] of var 1..3: xs :: output_array([1..2]);
+             constraint int_le(xs[1], xs[2]);
+             solve maximize xs[2];",
+        )
+        .unwrap();
+        assert_eq!(model.parameters.len(), 1);
+        assert_eq!(model.variables.len(), 1);
+        assert_eq!(model.variables[0].len, Some(2));
+        assert_eq!(model.constraints[0].name, "int_le");
+        assert_eq!(
+            model.constraints[0].args,
+            vec![
+                Expr::ArrayAccess("xs".to_string(), 1),
+                Expr::ArrayAccess("xs".to_string(), 2),
+            ]
+        );
+        assert_eq!(
+            model.solve.goal,
+            Goal::Maximize(Expr::ArrayAccess("xs".to_string(), 2))
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        match parse("var 1..3: x;\nconstraint int_le(x, );\nsolve satisfy;") {
+            Err(FlatZincError::Syntax { line, .. }) => assert_eq!(line, 2),
+            _ => panic!("the model should not be parsed"),
+        }
+        assert_eq!(
+            parse("var float: x;\nsolve satisfy;"),
+            Err(FlatZincError::Unsupported("float".to_string()))
+        );
+    }
+}
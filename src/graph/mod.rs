@@ -2,6 +2,8 @@ use std::collections::HashMap;
 use std::collections::HashSet;
 use std::hash::Hash;
 
+pub mod scc;
+
 pub trait Subsumed {
     fn is_subsumed_under(&self, val: &Self) -> bool;
 }
@@ -14,6 +16,7 @@ where
     Edge: Eq + Subsumed,
 {
     edges1: HashMap<Node1, Vec<(Edge, Vec<Node2>)>>,
+    #[allow(dead_code)]
     edges2: HashMap<Node2, HashSet<Node1>>,
 }
 
@@ -50,6 +53,17 @@ where
     event: Cause,
 }
 
+impl<Node1, Node2, Edge> Default for BipartiteGraphBuilder<Node1, Node2, Edge>
+where
+    Node1: Eq + Hash + Copy,
+    Node2: Eq + Hash + Copy,
+    Edge: Eq + Subsumed,
+{
+    fn default() -> BipartiteGraphBuilder<Node1, Node2, Edge> {
+        BipartiteGraphBuilder::new()
+    }
+}
+
 impl<Node1, Node2, Edge> BipartiteGraphBuilder<Node1, Node2, Edge>
 where
     Node1: Eq + Hash + Copy,
@@ -66,7 +80,7 @@ where
     // add node 2 to node 1
     pub fn insert_node1_to_node2(&mut self, src: Node1, label: Edge, dst: Node2) {
         let edge = self.edges1.entry(src).or_insert(vec![]);
-        let position = edge.iter().position(|&(ref key, _)| *key == label);
+        let position = edge.iter().position(|(key, _)| *key == label);
         let position = match position {
             Some(position) => position,
             None => {
@@ -77,7 +91,7 @@ where
         let &mut (_, ref mut nodes) = unsafe { edge.get_unchecked_mut(position) };
         if !nodes.contains(&dst) {
             nodes.push(dst);
-            self.edges2.entry(dst).or_insert(HashSet::new()).insert(src);
+            self.edges2.entry(dst).or_default().insert(src);
         }
     }
 
@@ -95,9 +109,9 @@ where
     Node2: Eq + Hash + Copy,
     Edge: Eq + Subsumed,
 {
-    pub fn events(&self) -> GraphEvents<Node1, Node2, Edge> {
+    pub fn events(&self) -> GraphEvents<'_, Node1, Node2, Edge> {
         GraphEvents {
-            graph: &self,
+            graph: self,
             events: Vec::new(),
         }
     }
@@ -121,6 +135,7 @@ where
         }
     }
 
+    #[allow(clippy::should_implement_trait)]
     pub fn into_iter(mut self) -> Option<impl Iterator<Item = (Node2, HashSet<Node1>)>> {
         let mut nodes = HashMap::new();
         for event in self.events.drain(0..) {
@@ -137,7 +152,7 @@ where
                     let (ref label, _) = *entry;
                     (*label).is_subsumed_under(&event)
                 })
-                .map(|&(_, ref succs)| {
+                .map(|(_, succs)| {
                     succs
                         .iter()
                         .filter(|&succ| *succ != from)
@@ -177,7 +192,7 @@ where
                     let (ref label, _) = *entry;
                     (*label).is_subsumed_under(&event)
                 })
-                .map(|&(_, ref succs)| {
+                .map(|(_, succs)| {
                     succs
                         .iter()
                         .filter(|&succ| *succ != from)
@@ -211,9 +226,9 @@ where
 {
     pub fn new(src: Node1, from: Node2, event: Cause) -> Self {
         Event {
-            src: src,
-            from: from,
-            event: event,
+            src,
+            from,
+            event,
         }
     }
 }
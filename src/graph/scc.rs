@@ -0,0 +1,70 @@
+use std::cmp;
+
+const UNVISITED: usize = usize::MAX;
+
+/// Computes the strongly connected components of a directed graph with Tarjan's
+/// algorithm. The nodes are numbered from `0` to `successors.len() - 1` and
+/// `successors[node]` lists the heads of the edges leaving `node`.
+///
+/// Returns the component of each node. The components are numbered in reverse
+/// topological order: an edge never goes from a component to a greater one.
+pub fn strongly_connected_components(successors: &[Vec<usize>]) -> Vec<usize> {
+    let len = successors.len();
+    let mut index = vec![UNVISITED; len];
+    let mut lowlink = vec![0; len];
+    let mut on_stack = vec![false; len];
+    let mut stack = vec![];
+    let mut components = vec![UNVISITED; len];
+    let mut next_index = 0;
+    let mut nb_components = 0;
+
+    for root in 0..len {
+        if index[root] != UNVISITED {
+            continue;
+        }
+        // The recursion is unrolled: `(node, pos)` resumes the visit of `node` at
+        // its successor `pos`.
+        let mut calls = vec![(root, 0)];
+        while let Some((node, mut pos)) = calls.pop() {
+            if pos == 0 {
+                index[node] = next_index;
+                lowlink[node] = next_index;
+                next_index += 1;
+                stack.push(node);
+                on_stack[node] = true;
+            } else {
+                let child = successors[node][pos - 1];
+                lowlink[node] = cmp::min(lowlink[node], lowlink[child]);
+            }
+
+            let mut recurse = false;
+            while pos < successors[node].len() {
+                let child = successors[node][pos];
+                pos += 1;
+                if index[child] == UNVISITED {
+                    calls.push((node, pos));
+                    calls.push((child, 0));
+                    recurse = true;
+                    break;
+                } else if on_stack[child] {
+                    lowlink[node] = cmp::min(lowlink[node], index[child]);
+                }
+            }
+            if recurse {
+                continue;
+            }
+
+            if lowlink[node] == index[node] {
+                while let Some(member) = stack.pop() {
+                    on_stack[member] = false;
+                    components[member] = nb_components;
+                    if member == node {
+                        break;
+                    }
+                }
+                nb_components += 1;
+            }
+        }
+    }
+    components
+}
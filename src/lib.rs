@@ -1,3 +1,6 @@
+// The crate is written in the 2015 edition, where trait objects are spelled `Box<Trait>`.
+#![allow(bare_trait_objects)]
+
 extern crate rand;
 extern crate rayon;
 extern crate snowflake;
@@ -10,6 +13,8 @@ pub mod variables;
 pub mod constraints;
 #[macro_use]
 pub mod branchers;
+pub mod flatzinc;
 pub mod graph;
+pub mod model;
 pub mod search;
 pub mod spaces;
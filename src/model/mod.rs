@@ -0,0 +1,314 @@
+//! Programmatic construction of a `Space`. A `Model` offers the same features as the
+//! `cp_model!` macro but the variables, the constraints and the branchers can be
+//! added from runtime data.
+//!
+//! ```
+//! # extern crate crusp;
+//! # use crusp::branchers::values_selector::MinValueSelector;
+//! # use crusp::branchers::variables_selector::SequentialVariableSelector;
+//! # use crusp::constraints::arithmetic::LessThan;
+//! # use crusp::model::{Model, ModelError};
+//! # use crusp::search::Solver;
+//! # use crusp::variables::int_var::IntVarValues;
+//! # use crusp::variables::Variable;
+//! # fn run() -> Result<(), ModelError> {
+//! let mut model = Model::new();
+//! let x = model.new_int_var(0, 9)?;
+//! let y = model.new_int_var(0, 9)?;
+//! model.post(LessThan::new(x, y));
+//! model.branch(
+//!     SequentialVariableSelector::new(vec![x, y].into_iter()).unwrap(),
+//!     MinValueSelector::new(),
+//! );
+//! let space = model.build()?;
+//!
+//! let mut solver = Solver::new(space);
+//! assert!(solver.solve());
+//! let solution = solver.solution().unwrap();
+//! let x: &IntVarValues = solution.get_variable(&x);
+//! assert_eq!(x.value(), Some(0));
+//! # Ok(())
+//! # }
+//! # fn main() {
+//! #     run().unwrap();
+//! # }
+//! ```
+use branchers::brancher::DefaultBrancher;
+use branchers::{BranchersHandler, ValuesSelector, VariableSelector};
+use constraints::handlers::{
+    ConstraintsHandlerBuilder, DefaultConstraintsHandler,
+    DefaultConstraintsHandlerBuilder,
+};
+use constraints::Constraint;
+use spaces::{Space, SpaceBuilder};
+use variables::bool_var::{BoolVar, BoolVarBuilder};
+use variables::handlers::macros::{ArrayOfRefsView, ArrayOfVarsView, VarView};
+use variables::handlers::{
+    DefaultVariablesBuilder, DefaultVariablesHandler, VariableContainerHandler,
+    VariableContainerHandlerBuilder,
+};
+use variables::int_var::{IntVarValues, IntVarValuesBuilder};
+use variables::{
+    ArrayOfRefs, ArrayOfVars, ArrayOfVarsBuilder, Variable, VariableBuilder,
+    VariableError,
+};
+
+/// The `Space` built by a `Model`.
+pub type ModelSpace =
+    Space<DefaultVariablesHandler, DefaultConstraintsHandler<DefaultVariablesHandler>>;
+
+/// Represents an error that occured during the construction of a model.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ModelError {
+    /// The domain of the new variable is empty.
+    EmptyDomain,
+    /// The array has no variable.
+    EmptyArray,
+    /// The initial propagation of the constraints failed, the model has no
+    /// solution.
+    Inconsistent(VariableError),
+}
+
+/// Gathers the variables, the constraints and the branchers of a problem, then
+/// builds the `Space` searching its solutions.
+pub struct Model {
+    variables: DefaultVariablesBuilder,
+    constraints: DefaultConstraintsHandlerBuilder<DefaultVariablesHandler>,
+    branchers: BranchersHandler<DefaultVariablesHandler>,
+}
+
+impl Model {
+    pub fn new() -> Model {
+        Model {
+            variables: DefaultVariablesBuilder::new(),
+            constraints: DefaultConstraintsHandlerBuilder::new(),
+            branchers: BranchersHandler::new(),
+        }
+    }
+
+    /// Adds a new variable of any kind managed by the `DefaultVariablesHandler`.
+    pub fn new_var<Builder>(&mut self, builder: Builder) -> VarView<Builder::Variable>
+    where
+        Builder: VariableBuilder,
+        DefaultVariablesHandler: VariableContainerHandler<
+            Builder::Variable,
+            View = VarView<Builder::Variable>,
+        >,
+        DefaultVariablesBuilder: VariableContainerHandlerBuilder<
+            Builder::Variable,
+            VarView<Builder::Variable>,
+            DefaultVariablesHandler,
+            Builder,
+        >,
+    {
+        self.variables.add(builder)
+    }
+
+    /// Adds an array of `len` new variables of any kind managed by the
+    /// `DefaultVariablesHandler`.
+    pub fn new_array<Builder>(
+        &mut self,
+        len: usize,
+        builder: Builder,
+    ) -> Result<ArrayOfVarsView<Builder::Variable>, ModelError>
+    where
+        Builder: VariableBuilder,
+        DefaultVariablesHandler: VariableContainerHandler<
+            ArrayOfVars<Builder::Variable>,
+            View = ArrayOfVarsView<Builder::Variable>,
+        >,
+        DefaultVariablesBuilder: VariableContainerHandlerBuilder<
+            ArrayOfVars<Builder::Variable>,
+            ArrayOfVarsView<Builder::Variable>,
+            DefaultVariablesHandler,
+            ArrayOfVarsBuilder<Builder>,
+        >,
+    {
+        if len == 0 {
+            return Err(ModelError::EmptyArray);
+        }
+        let array =
+            ArrayOfVarsBuilder::new(len, builder).ok_or(ModelError::EmptyArray)?;
+        Ok(self.variables.add(array))
+    }
+
+    /// Adds a new Boolean variable.
+    pub fn new_bool_var(&mut self) -> VarView<BoolVar> {
+        self.new_var(BoolVarBuilder::new())
+    }
+
+    /// Adds a new variable with the domain `min..=max`.
+    pub fn new_int_var(
+        &mut self,
+        min: i32,
+        max: i32,
+    ) -> Result<VarView<IntVarValues>, ModelError> {
+        let builder =
+            IntVarValuesBuilder::new(min, max).ok_or(ModelError::EmptyDomain)?;
+        Ok(self.variables.add(builder))
+    }
+
+    /// Adds a new variable with the domain `values`.
+    pub fn new_int_var_from_values<Values>(
+        &mut self,
+        values: Values,
+    ) -> Result<VarView<IntVarValues>, ModelError>
+    where
+        Values: IntoIterator<Item = i32>,
+    {
+        let builder = IntVarValuesBuilder::new_from_values(values)
+            .ok_or(ModelError::EmptyDomain)?;
+        Ok(self.variables.add(builder))
+    }
+
+    /// Adds an array of `len` new variables with the domain `min..=max`.
+    pub fn new_int_array(
+        &mut self,
+        len: usize,
+        min: i32,
+        max: i32,
+    ) -> Result<ArrayOfVarsView<IntVarValues>, ModelError> {
+        let builder =
+            IntVarValuesBuilder::new(min, max).ok_or(ModelError::EmptyDomain)?;
+        self.new_array(len, builder)
+    }
+
+    /// Adds an array referencing existing variables.
+    pub fn new_array_of_refs<Var, Views>(
+        &mut self,
+        views: Views,
+    ) -> Result<ArrayOfRefsView<Var>, ModelError>
+    where
+        Var: Variable,
+        Views: IntoIterator<Item = VarView<Var>>,
+        DefaultVariablesHandler:
+            VariableContainerHandler<ArrayOfRefs<Var>, View = ArrayOfRefsView<Var>>,
+        DefaultVariablesBuilder: VariableContainerHandlerBuilder<
+            ArrayOfRefs<Var>,
+            ArrayOfRefsView<Var>,
+            DefaultVariablesHandler,
+            Vec<VarView<Var>>,
+        >,
+    {
+        let views: Vec<_> = views.into_iter().collect();
+        if views.is_empty() {
+            return Err(ModelError::EmptyArray);
+        }
+        Ok(self.variables.add(views))
+    }
+
+    /// Adds the constraint to the model.
+    pub fn post<C>(&mut self, constraint: C)
+    where
+        C: Constraint<DefaultVariablesHandler> + 'static,
+    {
+        self.constraints.add(Box::new(constraint));
+    }
+
+    /// Adds a brancher to the model. The branchers are used in the order they have
+    /// been added: a brancher is used once all the variables of the previous
+    /// branchers are assigned.
+    pub fn branch<Var, VarSel, ValSel>(
+        &mut self,
+        variables_selector: VarSel,
+        values_selector: ValSel,
+    ) where
+        Var: Variable + 'static,
+        DefaultVariablesHandler: VariableContainerHandler<Var, View = VarView<Var>>,
+        VarSel: VariableSelector<DefaultVariablesHandler, VarView<Var>>
+            + Clone
+            + Send
+            + 'static,
+        ValSel: ValuesSelector<DefaultVariablesHandler, VarView<Var>>
+            + Clone
+            + Send
+            + 'static,
+    {
+        let brancher = DefaultBrancher::new(variables_selector, values_selector);
+        self.branchers.add_specific_brancher(Box::new(brancher));
+    }
+
+    /// Builds the `Space`. Fails if the initial propagation of the constraints
+    /// proves that the model has no solution.
+    pub fn build(self) -> Result<ModelSpace, ModelError> {
+        SpaceBuilder::new(self.variables, self.constraints, self.branchers)
+            .finalize()
+            .map_err(ModelError::Inconsistent)
+    }
+}
+
+impl Default for Model {
+    fn default() -> Model {
+        Model::new()
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use branchers::values_selector::MinValueSelector;
+    use branchers::variables_selector::SequentialVariableSelector;
+    use constraints::arithmetic::LessThan;
+    use search::Solver;
+    use variables::domains::{IterableDomain, OrderedDomain};
+
+    // Helpers shared by the tests of the constraints.
+
+    pub fn values(space: &ModelSpace, view: &VarView<IntVarValues>) -> Vec<i32> {
+        let var: &IntVarValues = space.get_variable(view);
+        var.iter().collect()
+    }
+
+    pub fn bounds(space: &ModelSpace, view: &VarView<IntVarValues>) -> (i32, i32) {
+        let var: &IntVarValues = space.get_variable(view);
+        (var.unchecked_min(), var.unchecked_max())
+    }
+
+    #[test]
+    fn build_and_solve() {
+        let mut model = Model::new();
+        let vars: Vec<_> = (0..3).map(|_| model.new_int_var(0, 3).unwrap()).collect();
+        for pair in vars.windows(2) {
+            model.post(LessThan::new(pair[0], pair[1]));
+        }
+        model.branch(
+            SequentialVariableSelector::new(vars.clone().into_iter()).unwrap(),
+            MinValueSelector::new(),
+        );
+        let space = model.build().unwrap();
+        let solver = Solver::new(space.clone());
+        assert_eq!(solver.count_solutions(), 4);
+
+        // The propagation at the root reduces the domains before any branching.
+        let mut space = space;
+        assert!(space.run().is_ok());
+        let bounds: Vec<_> = vars.iter().map(|var| bounds(&space, var)).collect();
+        assert_eq!(bounds, vec![(0, 1), (1, 2), (2, 3)]);
+    }
+
+    #[test]
+    fn inconsistent_model() {
+        let mut model = Model::new();
+        let x = model.new_int_var(5, 9).unwrap();
+        let y = model.new_int_var(0, 5).unwrap();
+        model.post(LessThan::new(x, y));
+        match model.build() {
+            Err(ModelError::Inconsistent(VariableError::DomainWipeout)) => {}
+            _ => panic!("The model should be inconsistent."),
+        }
+    }
+
+    #[test]
+    fn empty_domain_and_array() {
+        let mut model = Model::new();
+        assert_eq!(model.new_int_var(3, 2).err(), Some(ModelError::EmptyDomain));
+        assert_eq!(
+            model.new_int_var_from_values(vec![]).err(),
+            Some(ModelError::EmptyDomain)
+        );
+        assert_eq!(
+            model.new_int_array(0, 0, 3).err(),
+            Some(ModelError::EmptyArray)
+        );
+    }
+}
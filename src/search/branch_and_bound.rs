@@ -0,0 +1,370 @@
+use constraints::handlers::ConstraintsHandler;
+use search::limits::{SearchLimits, SearchMonitor, SearchStatus};
+use search::statistics::SearchStatistics;
+use spaces::{Space, SpaceState};
+use std::collections::VecDeque;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+use variables::domains::OrderedDomain;
+use variables::handlers::{
+    VariableContainerHandler, VariableContainerView, VariablesHandler,
+};
+use variables::{VariableError, VariableState};
+
+/// Describes the direction of the optimisation.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Objective {
+    /// Searches for the solution with the smallest objective value.
+    Minimize,
+    /// Searches for the solution with the greatest objective value.
+    Maximize,
+}
+
+// A branch fails if it empties the domain of the objective.
+type Branch<Variables, Constraints> =
+    Box<Fn(&mut Space<Variables, Constraints>) -> Result<(), VariableError>>;
+type Branches<Variables, Constraints> =
+    Box<Iterator<Item = Branch<Variables, Constraints>>>;
+type Nodes<Variables, Constraints> =
+    VecDeque<(Space<Variables, Constraints>, Branches<Variables, Constraints>)>;
+
+/// Depth first branch and bound solver. Each time a solution is found, the objective
+/// variable of the following nodes is forced to be strictly better than the value of
+/// that solution. The search ends when the search tree is exhausted, the last solution
+/// found is then optimal.
+///
+/// * `Var` - The type of the objective variable.
+/// * `View` - The view of the objective variable.
+pub struct BranchAndBoundSolver<Variables, Constraints, Var, View>
+where
+    Variables: VariablesHandler + VariableContainerHandler<Var, View = View> + Debug,
+    Constraints: ConstraintsHandler<Variables>,
+    View: VariableContainerView<Container = Var>,
+    Var: OrderedDomain,
+    Var::Type: Ord + Eq + Clone,
+{
+    init: Space<Variables, Constraints>,
+    objective: View,
+    direction: Objective,
+    best: Option<Var::Type>,
+    solution: Option<Space<Variables, Constraints>>,
+    optimal: bool,
+    limits: SearchLimits,
+    monitor: SearchMonitor,
+    statistics: SearchStatistics,
+    phantom: PhantomData<Var>,
+}
+
+impl<Variables, Constraints, Var, View>
+    BranchAndBoundSolver<Variables, Constraints, Var, View>
+where
+    Variables: VariablesHandler
+        + VariableContainerHandler<Var, View = View>
+        + Clone
+        + 'static
+        + Debug,
+    Constraints: ConstraintsHandler<Variables> + 'static,
+    View: VariableContainerView<Container = Var> + 'static,
+    Var: OrderedDomain + 'static,
+    Var::Type: Ord + Eq + Clone + 'static,
+{
+    /// Creates a new solver optimising the variable `objective` of `space`.
+    ///
+    /// # Parameters
+    /// * `space` - The root space of the search.
+    /// * `objective` - The view of the variable to optimise.
+    /// * `direction` - Either `Objective::Minimize` or `Objective::Maximize`.
+    pub fn new(
+        space: Space<Variables, Constraints>,
+        objective: View,
+        direction: Objective,
+    ) -> Self {
+        Self::with_limits(space, objective, direction, SearchLimits::new())
+    }
+
+    /// Creates a new solver optimising the variable `objective` of `space` within
+    /// the budget `limits`. If the search is interrupted, the best solution found so
+    /// far is kept but it is not proved optimal.
+    pub fn with_limits(
+        space: Space<Variables, Constraints>,
+        objective: View,
+        direction: Objective,
+        limits: SearchLimits,
+    ) -> Self {
+        BranchAndBoundSolver {
+            init: space,
+            objective,
+            direction,
+            best: None,
+            solution: None,
+            optimal: false,
+            limits,
+            monitor: SearchMonitor::new(limits),
+            statistics: SearchStatistics::new(),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Creates a new solver minimising the variable `objective` of `space`.
+    pub fn minimize(space: Space<Variables, Constraints>, objective: View) -> Self {
+        Self::new(space, objective, Objective::Minimize)
+    }
+
+    /// Creates a new solver maximising the variable `objective` of `space`.
+    pub fn maximize(space: Space<Variables, Constraints>, objective: View) -> Self {
+        Self::new(space, objective, Objective::Maximize)
+    }
+
+    /// Explores the whole search tree and returns `true` if at least one solution has
+    /// been found. The best solution is retrieved with the `solution` method.
+    pub fn solve(&mut self) -> bool {
+        let mut nodes: Nodes<Variables, Constraints> = VecDeque::new();
+        let mut root = self.init.clone();
+        root.reset_propagations();
+        self.monitor = SearchMonitor::new(self.limits);
+        self.monitor.count_propagations(root.propagation_counters());
+        if self.monitor.node() {
+            self.explore(root, &mut nodes);
+        }
+
+        while !nodes.is_empty() {
+            let next = match nodes.back_mut() {
+                Some(&mut (ref space, ref mut branches)) => {
+                    branches.next().map(|branch| (space.clone(), branch))
+                }
+                _ => unreachable!("nodes is empty can't reach this case!"),
+            };
+            match next {
+                Some((mut space, branch)) => {
+                    if !self.monitor.node() {
+                        break;
+                    }
+                    match branch(&mut space) {
+                        Ok(()) => self.explore(space, &mut nodes),
+                        Err(_) => self.monitor.failure(),
+                    }
+                }
+                None => {
+                    nodes.pop_back();
+                }
+            }
+        }
+        // The search tree has been exhausted: no better solution exists.
+        self.optimal = self.solution.is_some() && !self.monitor.is_interrupted();
+        self.statistics = self.monitor.statistics();
+        self.solution.is_some()
+    }
+
+    /// Returns how the last call to `solve` ended.
+    pub fn status(&self) -> SearchStatus {
+        self.monitor.status(self.best.is_some())
+    }
+
+    /// Returns the statistics of the last call to `solve`.
+    pub fn statistics(&self) -> &SearchStatistics {
+        &self.statistics
+    }
+
+    /// Returns the best solution found (if any).
+    pub fn solution(&mut self) -> Option<Space<Variables, Constraints>> {
+        use std::mem;
+        let mut sol = None;
+        mem::swap(&mut sol, &mut self.solution);
+        sol
+    }
+
+    /// Returns the value of the objective of the best solution found (if any).
+    pub fn objective_value(&self) -> Option<Var::Type> {
+        self.best.clone()
+    }
+
+    /// Returns `true` if the best solution has been proved optimal.
+    pub fn is_optimal(&self) -> bool {
+        self.optimal
+    }
+
+    fn explore(
+        &mut self,
+        mut space: Space<Variables, Constraints>,
+        nodes: &mut Nodes<Variables, Constraints>,
+    ) {
+        self.monitor.depth(nodes.len());
+        if self.tighten(&mut space).is_err() {
+            self.monitor.failure();
+            return;
+        }
+        let state = space.run();
+        match state {
+            Ok(SpaceState::Subsumed) => {
+                let value = {
+                    let var: &Var = space.variables.get(&self.objective);
+                    var.value()
+                };
+                match value {
+                    Some(value) => {
+                        self.monitor.solution();
+                        self.best = Some(value);
+                        self.solution = Some(space);
+                    }
+                    None => {
+                        // The branchers do not cover the objective, so the remaining
+                        // values are explored from the best to the worst.
+                        let branches = self.objective_branches(&space);
+                        nodes.push_back((space, branches));
+                    }
+                }
+            }
+            Ok(SpaceState::Branches(branches)) => {
+                let branches = branches.map(|branch| {
+                    Box::new(move |space: &mut Space<Variables, Constraints>| {
+                        branch(space);
+                        Ok(())
+                    }) as Branch<Variables, Constraints>
+                });
+                nodes.push_back((space, Box::new(branches)));
+            }
+            Err(_) => self.monitor.failure(),
+        }
+    }
+
+    // Forces the objective to be strictly better than the best solution found so far.
+    fn tighten(
+        &self,
+        space: &mut Space<Variables, Constraints>,
+    ) -> Result<VariableState, VariableError> {
+        match self.best {
+            None => Ok(VariableState::NoChange),
+            Some(ref best) => {
+                let var: &mut Var = space.variables.get_mut(&self.objective);
+                match self.direction {
+                    Objective::Minimize => var.strict_upperbound(best.clone()),
+                    Objective::Maximize => var.strict_lowerbound(best.clone()),
+                }
+            }
+        }
+    }
+
+    // Two branches: the objective takes its best bound or the objective is strictly
+    // worse than its best bound.
+    fn objective_branches(
+        &self,
+        space: &Space<Variables, Constraints>,
+    ) -> Branches<Variables, Constraints> {
+        let var: &Var = space.variables.get(&self.objective);
+        let direction = self.direction;
+        let bound = match direction {
+            Objective::Minimize => var.unchecked_min(),
+            Objective::Maximize => var.unchecked_max(),
+        };
+        let (view, value) = (self.objective.clone(), bound.clone());
+        let assign: Branch<Variables, Constraints> =
+            Box::new(move |space: &mut Space<Variables, Constraints>| {
+                let var: &mut Var = space.variables.get_mut(&view);
+                match direction {
+                    Objective::Minimize => var.weak_upperbound(value.clone()),
+                    Objective::Maximize => var.weak_lowerbound(value.clone()),
+                }
+                .map(|_| ())
+            });
+        let (view, value) = (self.objective.clone(), bound);
+        let refute: Branch<Variables, Constraints> =
+            Box::new(move |space: &mut Space<Variables, Constraints>| {
+                let var: &mut Var = space.variables.get_mut(&view);
+                match direction {
+                    Objective::Minimize => var.strict_lowerbound(value.clone()),
+                    Objective::Maximize => var.strict_upperbound(value.clone()),
+                }
+                .map(|_| ())
+            });
+        Box::new(vec![assign, refute].into_iter())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use branchers::brancher::DefaultBrancher;
+    use branchers::values_selector::MaxValueSelector;
+    use branchers::variables_selector::SequentialVariableSelector;
+    use branchers::BranchersHandler;
+    use constraints::arithmetic::LessThan;
+    use constraints::handlers::{
+        ConstraintsHandlerBuilder, DefaultConstraintsHandler,
+        DefaultConstraintsHandlerBuilder,
+    };
+    use variables::handlers::default_handler::{Builder, Handler};
+    use variables::handlers::macros::VarView;
+    use variables::handlers::{VariableContainerHandlerBuilder, VariablesHandlerBuilder};
+    use variables::int_var::{IntVarValues, IntVarValuesBuilder};
+    use variables::Variable;
+
+    type TestSpace = Space<Handler, DefaultConstraintsHandler<Handler>>;
+
+    // x < y with x, y in 0..=5, and y < x if `infeasible`.
+    fn space(
+        infeasible: bool,
+    ) -> (TestSpace, VarView<IntVarValues>, VarView<IntVarValues>) {
+        let mut variables = Builder::new_builder();
+        let mut constraints = DefaultConstraintsHandlerBuilder::new();
+        let mut branchers = BranchersHandler::new();
+        let x = variables.add(IntVarValuesBuilder::new(0, 5).unwrap());
+        let y = variables.add(IntVarValuesBuilder::new(0, 5).unwrap());
+        constraints.add(Box::new(LessThan::new(x, y)));
+        if infeasible {
+            constraints.add(Box::new(LessThan::new(y, x)));
+        }
+        let selector = SequentialVariableSelector::new(vec![x, y].into_iter()).unwrap();
+        let brancher = DefaultBrancher::new(selector, MaxValueSelector::new());
+        branchers.add_specific_brancher(Box::new(brancher));
+        let mut variables = variables.finalize();
+        let constraints = constraints.finalize(&mut variables).unwrap();
+        (Space::new(variables, constraints, branchers), x, y)
+    }
+
+    #[test]
+    fn minimize() {
+        // The first solution found by the branchers is x = 4 and y = 5.
+        let (space, _, y) = space(false);
+        let mut solver = BranchAndBoundSolver::minimize(space, y);
+        assert!(solver.solve());
+        assert!(solver.is_optimal());
+        assert_eq!(solver.status(), SearchStatus::Complete);
+        assert_eq!(solver.objective_value(), Some(1));
+        // Every improving solution is counted.
+        assert!(solver.statistics().solutions > 1);
+        let solution = solver.solution().unwrap();
+        let var: &IntVarValues = solution.get_variable(&y);
+        assert_eq!(var.value(), Some(1));
+    }
+
+    #[test]
+    fn maximize() {
+        let (space, x, _) = space(false);
+        let mut solver = BranchAndBoundSolver::maximize(space, x);
+        assert!(solver.solve());
+        assert!(solver.is_optimal());
+        assert_eq!(solver.objective_value(), Some(4));
+    }
+
+    #[test]
+    fn infeasible() {
+        let (space, x, _) = space(true);
+        let mut solver = BranchAndBoundSolver::minimize(space, x);
+        assert!(!solver.solve());
+        assert!(!solver.is_optimal());
+        assert_eq!(solver.status(), SearchStatus::Infeasible);
+        assert_eq!(solver.objective_value(), None);
+    }
+
+    #[test]
+    fn interrupted() {
+        let (space, _, y) = space(false);
+        let limits = SearchLimits::new().with_max_solutions(1);
+        let mut solver =
+            BranchAndBoundSolver::with_limits(space, y, Objective::Minimize, limits);
+        assert!(solver.solve());
+        assert!(!solver.is_optimal());
+        assert_eq!(solver.status(), SearchStatus::Interrupted);
+        assert_eq!(solver.objective_value(), Some(5));
+    }
+}
@@ -59,17 +59,21 @@ macro_rules! cp_model {
 
 
         let mut variables_handler = variables_handler.finalize();
-        let constraints_handler = constraints_handler.finalize(&mut variables_handler).unwrap();
-
-        let space = Space::new(variables_handler, constraints_handler, branchers_handler);
-        let mut solver = Solver::new(space);
-        if solver.solve() {
-            let solution = solver.solution().unwrap();
-            Some(($(
-                        solution.get_variable(&$out).clone()
-                   ),+,))
-        } else {
-            None
+        match constraints_handler.finalize(&mut variables_handler) {
+            // The initial propagation proves that the model has no solution.
+            Err(_) => None,
+            Ok(constraints_handler) => {
+                let space = Space::new(variables_handler, constraints_handler, branchers_handler);
+                let mut solver = Solver::new(space);
+                if solver.solve() {
+                    let solution = solver.solution().unwrap();
+                    Some(($(
+                                solution.get_variable(&$out).clone()
+                           ),+,))
+                } else {
+                    None
+                }
+            }
         }
     }};
     (
@@ -125,17 +129,21 @@ macro_rules! cp_model {
 
 
         let mut variables_handler = variables_handler.finalize();
-        let constraints_handler = constraints_handler.finalize(&mut variables_handler).unwrap();
-
-        let space = Space::new(variables_handler, constraints_handler, branchers_handler);
-        let mut solver = ParallelSolver::new(space);
-        if solver.solve() {
-            let solution = solver.solution().unwrap();
-            Some(($(
-                        solution.get_variable(&$out).clone()
-                   ),+,))
-        } else {
-            None
+        match constraints_handler.finalize(&mut variables_handler) {
+            // The initial propagation proves that the model has no solution.
+            Err(_) => None,
+            Ok(constraints_handler) => {
+                let space = Space::new(variables_handler, constraints_handler, branchers_handler);
+                let mut solver = ParallelSolver::new(space);
+                if solver.solve() {
+                    let solution = solver.solution().unwrap();
+                    Some(($(
+                                solution.get_variable(&$out).clone()
+                           ),+,))
+                } else {
+                    None
+                }
+            }
         }
     }};
     (
@@ -191,19 +199,101 @@ macro_rules! cp_model {
 
 
         let mut variables_handler = variables_handler.finalize();
-        let constraints_handler = constraints_handler.finalize(&mut variables_handler).unwrap();
-
-        let space = Space::new(variables_handler, constraints_handler, branchers_handler);
-        let mut solver = SolverPathRecomputing::new(space);
-        if solver.solve() {
-            let solution = solver.solution().unwrap();
-            Some(($(
-                        solution.get_variable(&$out).clone()
-                   ),+,))
-        } else {
-            None
+        match constraints_handler.finalize(&mut variables_handler) {
+            // The initial propagation proves that the model has no solution.
+            Err(_) => None,
+            Ok(constraints_handler) => {
+                let space = Space::new(variables_handler, constraints_handler, branchers_handler);
+                let mut solver = SolverPathRecomputing::new(space);
+                if solver.solve() {
+                    let solution = solver.solution().unwrap();
+                    Some(($(
+                                solution.get_variable(&$out).clone()
+                           ),+,))
+                } else {
+                    None
+                }
+            }
         }
     }};
+    (
+        model {
+            $($tail:tt)*
+        }
+        branchers {
+            $($branches: tt)*
+        }
+        $direction: ident $objective: ident;
+        output (
+            $($out: ident),+
+            );
+    ) => {{
+        #[allow(unused_imports)]
+        use $crate::constraints::handlers::*;
+        #[allow(unused_imports)]
+        use $crate::branchers::*;
+        #[allow(unused_imports)]
+        use $crate::branchers::brancher::*;
+        #[allow(unused_imports)]
+        use $crate::branchers::values_selector::*;
+        #[allow(unused_imports)]
+        use $crate::branchers::variables_selector::*;
+        #[allow(unused_imports)]
+        use $crate::constraints::*;
+        #[allow(unused_imports)]
+        use $crate::constraints::handlers::*;
+        #[allow(unused_imports)]
+        use $crate::search::*;
+        #[allow(unused_imports)]
+        use $crate::search::branch_and_bound::*;
+        #[allow(unused_imports)]
+        use $crate::spaces::*;
+        #[allow(unused_imports)]
+        use $crate::variables::*;
+        #[allow(unused_imports)]
+        use $crate::variables::handlers::*;
+        #[allow(unused_imports)]
+        use $crate::variables::domains::*;
+        #[allow(unused_imports)]
+        use $crate::variables::int_var::*;
+
+        let mut variables_handler = default_handler::Builder::new();
+        let mut constraints_handler = DefaultConstraintsHandlerBuilder::new();
+        let mut branchers_handler = BranchersHandler::new();
+
+        cp_model!(variables = variables_handler; constraints = constraints_handler; $($tail)*);
+
+        cp_model!(variables = variables_handler; branchers = branchers_handler; $($branches)*);
+
+
+        let mut variables_handler = variables_handler.finalize();
+        match constraints_handler.finalize(&mut variables_handler) {
+            // The initial propagation proves that the model has no solution.
+            Err(_) => None,
+            Ok(constraints_handler) => {
+                let space = Space::new(variables_handler, constraints_handler, branchers_handler);
+                let mut solver = BranchAndBoundSolver::new(
+                    space,
+                    $objective,
+                    cp_model!(@Objective $direction)
+                );
+                if solver.solve() {
+                    let solution = solver.solution().unwrap();
+                    Some(($(
+                                solution.get_variable(&$out).clone()
+                           ),+,))
+                } else {
+                    None
+                }
+            }
+        }
+    }};
+    (@Objective minimize) => {
+        $crate::search::branch_and_bound::Objective::Minimize
+    };
+    (@Objective maximize) => {
+        $crate::search::branch_and_bound::Objective::Maximize
+    };
     () => {};
     (variables = $variables: ident; constraints = $constraints: ident;) => {};
     (
@@ -242,6 +332,24 @@ macro_rules! cp_model {
 
         cp_model!(variables = $variables; constraints = $constraints; $($tail)*);
     };
+    (
+        variables = $variables: ident; constraints = $constraints: ident;
+        let $x: ident = var bool;
+        $($tail:tt)*
+    ) => {
+        let $x = $variables.add($crate::variables::bool_var::BoolVarBuilder::new());
+        cp_model!(variables = $variables; constraints = $constraints; $($tail)*);
+    };
+    (
+        variables = $variables: ident; constraints = $constraints: ident;
+        let $x: ident = array[$len: tt] of var bool;
+        $($tail:tt)*
+    ) => {
+        let $x = ArrayOfVarsBuilder::new($len, $crate::variables::bool_var::BoolVarBuilder::new()).unwrap();
+        let $x = $variables.add($x);
+
+        cp_model!(variables = $variables; constraints = $constraints; $($tail)*);
+    };
     (
         variables = $variables: ident; constraints = $constraints: ident;
         let $x: ident = $array: ident[$idx: expr];
@@ -281,6 +389,22 @@ macro_rules! cp_model {
 
         cp_model!(variables = $variables; constraints = $constraints; $($tail)*);
     };
+    (variables = $variables: ident; constraints = $constraints: ident; constraint non_decreasing($x:ident); $($tail:tt)*) => {
+        {
+            $constraints.add(Box::new($crate::constraints::NonDecreasing::new($x)));
+
+            cp_model!(variables = $variables; constraints = $constraints; $($tail)*);
+        }
+    };
+    (variables = $variables: ident; constraints = $constraints: ident; constraint non_decreasing([$($x:tt),+]); $($tail:tt)*) => {
+        {
+            {
+                let list = cp_model!(@List in $variables; $($x),*);
+                $constraints.add(Box::new($crate::constraints::NonDecreasing::new(list)));
+            }
+            cp_model!(variables = $variables; constraints = $constraints; $($tail)*);
+        }
+    };
     (variables = $variables: ident; constraints = $constraints: ident; constraint increasing($x:ident); $($tail:tt)*) => {
         {
             $constraints.add(Box::new($crate::constraints::Increasing::new($x)));
@@ -297,6 +421,87 @@ macro_rules! cp_model {
             cp_model!(variables = $variables; constraints = $constraints; $($tail)*);
         }
     };
+    (variables = $variables: ident; constraints = $constraints: ident; constraint non_increasing($x:ident); $($tail:tt)*) => {
+        {
+            $constraints.add(Box::new($crate::constraints::NonIncreasing::new($x)));
+
+            cp_model!(variables = $variables; constraints = $constraints; $($tail)*);
+        }
+    };
+    (variables = $variables: ident; constraints = $constraints: ident; constraint non_increasing([$($x:tt),+]); $($tail:tt)*) => {
+        {
+            {
+                let list = cp_model!(@List in $variables; $($x),*);
+                $constraints.add(Box::new($crate::constraints::NonIncreasing::new(list)));
+            }
+            cp_model!(variables = $variables; constraints = $constraints; $($tail)*);
+        }
+    };
+    (variables = $variables: ident; constraints = $constraints: ident; constraint decreasing($x:ident); $($tail:tt)*) => {
+        {
+            $constraints.add(Box::new($crate::constraints::Decreasing::new($x)));
+
+            cp_model!(variables = $variables; constraints = $constraints; $($tail)*);
+        }
+    };
+    (variables = $variables: ident; constraints = $constraints: ident; constraint decreasing([$($x:tt),+]); $($tail:tt)*) => {
+        {
+            {
+                let list = cp_model!(@List in $variables; $($x),*);
+                $constraints.add(Box::new($crate::constraints::Decreasing::new(list)));
+            }
+            cp_model!(variables = $variables; constraints = $constraints; $($tail)*);
+        }
+    };
+    (variables = $variables: ident; constraints = $constraints: ident; constraint $b:ident <-> ($($c: tt)+); $($tail:tt)*) => {
+        {
+            $constraints.add(Box::new(
+                    $crate::constraints::Reified::full($b, cp_model!(@Reifiable $($c)+))));
+
+            cp_model!(variables = $variables; constraints = $constraints; $($tail)*);
+        }
+    };
+    (variables = $variables: ident; constraints = $constraints: ident; constraint $b:ident -> ($($c: tt)+); $($tail:tt)*) => {
+        {
+            $constraints.add(Box::new(
+                    $crate::constraints::Reified::half($b, cp_model!(@Reifiable $($c)+))));
+
+            cp_model!(variables = $variables; constraints = $constraints; $($tail)*);
+        }
+    };
+    (@Reifiable $x:ident < $y: ident) => {
+        $crate::constraints::arithmetic::LessThan::new($x, $y)
+    };
+    (@Reifiable $x:ident <= $y: ident) => {
+        $crate::constraints::arithmetic::LessOrEqualThan::new($x, $y)
+    };
+    (@Reifiable $x:ident > $y: ident) => {
+        $crate::constraints::arithmetic::GreaterThan::new($x, $y)
+    };
+    (@Reifiable $x:ident >= $y: ident) => {
+        $crate::constraints::arithmetic::GreaterOrEqualThan::new($x, $y)
+    };
+    (@Reifiable $x:ident == $y: ident) => {
+        $crate::constraints::arithmetic::Equal::new($x, $y)
+    };
+    (@Reifiable $x:ident |==| $y: ident) => {
+        $crate::constraints::arithmetic::EqualBounds::new($x, $y)
+    };
+    (@Reifiable $x:ident < $c: expr) => {
+        $crate::constraints::arithmetic::LessThanConstant::new($x, $c)
+    };
+    (@Reifiable $x:ident <= $c: expr) => {
+        $crate::constraints::arithmetic::LessOrEqualThanConstant::new($x, $c)
+    };
+    (@Reifiable $x:ident > $c: expr) => {
+        $crate::constraints::arithmetic::GreaterThanConstant::new($x, $c)
+    };
+    (@Reifiable $x:ident >= $c: expr) => {
+        $crate::constraints::arithmetic::GreaterOrEqualThanConstant::new($x, $c)
+    };
+    (@Reifiable $x:ident == $c: expr) => {
+        $crate::constraints::arithmetic::EqualConstant::new($x, $c)
+    };
     (variables = $variables: ident; constraints = $constraints: ident; constraint $x:ident < $y: ident; $($tail:tt)*) => {
         {
             $constraints.add(Box::new(
@@ -367,6 +572,72 @@ macro_rules! cp_model {
             cp_model!(variables = $variables; constraints = $constraints; $($tail)*);
         }
     };
+    (
+        variables = $variables: ident; constraints = $constraints: ident;
+        constraint all_different_bounds($vars: ident);
+        $($tail:tt)*) => {
+        {
+            $constraints.add(Box::new(
+                    $crate::constraints::AllDifferentBounds::new($vars)));
+
+            cp_model!(variables = $variables; constraints = $constraints; $($tail)*);
+        }
+    };
+    (variables = $variables: ident; constraints = $constraints: ident; constraint all_different_bounds([$($y: tt),+]); $($tail:tt)*) => {
+        {
+            {
+            let list = cp_model!(@List in $variables; $($y),+);
+            $constraints.add(Box::new(
+                    $crate::constraints::AllDifferentBounds::new(list)));
+            }
+
+            cp_model!(variables = $variables; constraints = $constraints; $($tail)*);
+        }
+    };
+    (
+        variables = $variables: ident; constraints = $constraints: ident;
+        constraint all_different_domain($vars: ident);
+        $($tail:tt)*) => {
+        {
+            $constraints.add(Box::new(
+                    $crate::constraints::AllDifferentDomain::new($vars)));
+
+            cp_model!(variables = $variables; constraints = $constraints; $($tail)*);
+        }
+    };
+    (variables = $variables: ident; constraints = $constraints: ident; constraint all_different_domain([$($y: tt),+]); $($tail:tt)*) => {
+        {
+            {
+            let list = cp_model!(@List in $variables; $($y),+);
+            $constraints.add(Box::new(
+                    $crate::constraints::AllDifferentDomain::new(list)));
+            }
+
+            cp_model!(variables = $variables; constraints = $constraints; $($tail)*);
+        }
+    };
+    (
+        variables = $variables: ident; constraints = $constraints: ident;
+        constraint table($vars: ident, $tuples: expr);
+        $($tail:tt)*) => {
+        {
+            $constraints.add(Box::new(
+                    $crate::constraints::Table::new($vars, $tuples)));
+
+            cp_model!(variables = $variables; constraints = $constraints; $($tail)*);
+        }
+    };
+    (variables = $variables: ident; constraints = $constraints: ident; constraint table([$($y: tt),+], $tuples: expr); $($tail:tt)*) => {
+        {
+            {
+            let list = cp_model!(@List in $variables; $($y),+);
+            $constraints.add(Box::new(
+                    $crate::constraints::Table::new(list, $tuples)));
+            }
+
+            cp_model!(variables = $variables; constraints = $constraints; $($tail)*);
+        }
+    };
     (
         variables = $variables: ident;
         constraints = $constraints: ident;
@@ -424,6 +695,384 @@ macro_rules! cp_model {
             cp_model!(variables = $variables; constraints = $constraints; $($tail)*);
         }
     };
+    (
+        variables = $variables: ident; constraints = $constraints: ident;
+        constraint $a:tt * $x:tt $($rest:tt)*
+    ) => {
+        cp_model!(@Linear variables = $variables; constraints = $constraints; [$a * $x] $($rest)*);
+    };
+    (
+        variables = $variables: ident; constraints = $constraints: ident;
+        constraint $x:ident + $($rest:tt)*
+    ) => {
+        cp_model!(@Linear variables = $variables; constraints = $constraints; [$x +] $($rest)*);
+    };
+    (@Linear variables = $variables: ident; constraints = $constraints: ident; [$($lhs:tt)+] <= $rhs:ident; $($tail:tt)*) => {
+        cp_model!(@LinearPost $variables; $constraints; [$($lhs)+ + (-1) * $rhs]; LessOrEqual; 0);
+        cp_model!(variables = $variables; constraints = $constraints; $($tail)*);
+    };
+    (@Linear variables = $variables: ident; constraints = $constraints: ident; [$($lhs:tt)+] <= $rhs:tt; $($tail:tt)*) => {
+        cp_model!(@LinearPost $variables; $constraints; [$($lhs)+]; LessOrEqual; expr!($rhs));
+        cp_model!(variables = $variables; constraints = $constraints; $($tail)*);
+    };
+    (@Linear variables = $variables: ident; constraints = $constraints: ident; [$($lhs:tt)+] >= $rhs:ident; $($tail:tt)*) => {
+        cp_model!(@LinearPost $variables; $constraints; [$($lhs)+ + (-1) * $rhs]; GreaterOrEqual; 0);
+        cp_model!(variables = $variables; constraints = $constraints; $($tail)*);
+    };
+    (@Linear variables = $variables: ident; constraints = $constraints: ident; [$($lhs:tt)+] >= $rhs:tt; $($tail:tt)*) => {
+        cp_model!(@LinearPost $variables; $constraints; [$($lhs)+]; GreaterOrEqual; expr!($rhs));
+        cp_model!(variables = $variables; constraints = $constraints; $($tail)*);
+    };
+    (@Linear variables = $variables: ident; constraints = $constraints: ident; [$($lhs:tt)+] == $rhs:ident; $($tail:tt)*) => {
+        cp_model!(@LinearPost $variables; $constraints; [$($lhs)+ + (-1) * $rhs]; Equal; 0);
+        cp_model!(variables = $variables; constraints = $constraints; $($tail)*);
+    };
+    (@Linear variables = $variables: ident; constraints = $constraints: ident; [$($lhs:tt)+] == $rhs:tt; $($tail:tt)*) => {
+        cp_model!(@LinearPost $variables; $constraints; [$($lhs)+]; Equal; expr!($rhs));
+        cp_model!(variables = $variables; constraints = $constraints; $($tail)*);
+    };
+    (@Linear variables = $variables: ident; constraints = $constraints: ident; [$($lhs:tt)+] != $rhs:ident; $($tail:tt)*) => {
+        cp_model!(@LinearPost $variables; $constraints; [$($lhs)+ + (-1) * $rhs]; NotEqual; 0);
+        cp_model!(variables = $variables; constraints = $constraints; $($tail)*);
+    };
+    (@Linear variables = $variables: ident; constraints = $constraints: ident; [$($lhs:tt)+] != $rhs:tt; $($tail:tt)*) => {
+        cp_model!(@LinearPost $variables; $constraints; [$($lhs)+]; NotEqual; expr!($rhs));
+        cp_model!(variables = $variables; constraints = $constraints; $($tail)*);
+    };
+    (@Linear variables = $variables: ident; constraints = $constraints: ident; [$($lhs:tt)+] $next:tt $($rest:tt)*) => {
+        cp_model!(@Linear variables = $variables; constraints = $constraints; [$($lhs)+ $next] $($rest)*);
+    };
+    (@LinearPost $variables: ident; $constraints: ident; [$($lhs:tt)+]; NotEqual; $constant:expr) => {
+        {
+            let mut coefs = vec![];
+            let mut vars = vec![];
+            cp_model!(coefs = coefs; vars = vars; ($($lhs)+));
+            let vars = $variables.add(vars);
+            $constraints.add(Box::new(
+                    $crate::constraints::LinearNotEqual::new(vars, coefs, $constant)));
+        }
+    };
+    (@LinearPost $variables: ident; $constraints: ident; [$($lhs:tt)+]; $relation:ident; $constant:expr) => {
+        {
+            let mut coefs = vec![];
+            let mut vars = vec![];
+            cp_model!(coefs = coefs; vars = vars; ($($lhs)+));
+            let vars = $variables.add(vars);
+            $constraints.add(Box::new(
+                    $crate::constraints::Linear::new(
+                        vars,
+                        coefs,
+                        $crate::constraints::LinearRelation::$relation,
+                        $constant)));
+        }
+    };
+    (
+        variables = $variables: ident; constraints = $constraints: ident;
+        constraint $y:ident = element([$($v:expr),+], $x:ident);
+        $($tail:tt)*) => {
+        {
+            $constraints.add(Box::new(
+                    $crate::constraints::ConstantElement::new($y, $x, vec![$($v),+])));
+
+            cp_model!(variables = $variables; constraints = $constraints; $($tail)*);
+        }
+    };
+    (
+        variables = $variables: ident; constraints = $constraints: ident;
+        constraint $y:ident = element($array:ident, $x:ident);
+        $($tail:tt)*) => {
+        {
+            $constraints.add(Box::new(
+                    $crate::constraints::Element::new($y, $x, $array)));
+
+            cp_model!(variables = $variables; constraints = $constraints; $($tail)*);
+        }
+    };
+    (
+        variables = $variables: ident; constraints = $constraints: ident;
+        constraint $z:ident = $x:ident * $y:ident;
+        $($tail:tt)*) => {
+        {
+            $constraints.add(Box::new(
+                    $crate::constraints::arithmetic::Times::new($x, $y, $z)));
+
+            cp_model!(variables = $variables; constraints = $constraints; $($tail)*);
+        }
+    };
+    (
+        variables = $variables: ident; constraints = $constraints: ident;
+        constraint $z:ident = $x:ident / $y:ident;
+        $($tail:tt)*) => {
+        {
+            $constraints.add(Box::new(
+                    $crate::constraints::arithmetic::Div::new($x, $y, $z)));
+
+            cp_model!(variables = $variables; constraints = $constraints; $($tail)*);
+        }
+    };
+    (
+        variables = $variables: ident; constraints = $constraints: ident;
+        constraint $z:ident = $x:ident % $y:ident;
+        $($tail:tt)*) => {
+        {
+            $constraints.add(Box::new(
+                    $crate::constraints::arithmetic::Mod::new($x, $y, $z)));
+
+            cp_model!(variables = $variables; constraints = $constraints; $($tail)*);
+        }
+    };
+    (
+        variables = $variables: ident; constraints = $constraints: ident;
+        constraint $y:ident = abs($x:ident);
+        $($tail:tt)*) => {
+        {
+            $constraints.add(Box::new(
+                    $crate::constraints::arithmetic::Abs::new($x, $y)));
+
+            cp_model!(variables = $variables; constraints = $constraints; $($tail)*);
+        }
+    };
+    (
+        variables = $variables: ident; constraints = $constraints: ident;
+        constraint $z:ident = min($x:ident, $y:ident);
+        $($tail:tt)*) => {
+        {
+            $constraints.add(Box::new(
+                    $crate::constraints::arithmetic::Min::new($x, $y, $z)));
+
+            cp_model!(variables = $variables; constraints = $constraints; $($tail)*);
+        }
+    };
+    (
+        variables = $variables: ident; constraints = $constraints: ident;
+        constraint $z:ident = min([$($x:ident),+]);
+        $($tail:tt)*) => {
+        {
+            let vars = $variables.add(vec![$($x.clone()),+]);
+            $constraints.add(Box::new(
+                    $crate::constraints::arithmetic::ArrayMin::new($z, vars)));
+
+            cp_model!(variables = $variables; constraints = $constraints; $($tail)*);
+        }
+    };
+    (
+        variables = $variables: ident; constraints = $constraints: ident;
+        constraint $z:ident = min($array:ident);
+        $($tail:tt)*) => {
+        {
+            $constraints.add(Box::new(
+                    $crate::constraints::arithmetic::ArrayMin::new($z, $array)));
+
+            cp_model!(variables = $variables; constraints = $constraints; $($tail)*);
+        }
+    };
+    (
+        variables = $variables: ident; constraints = $constraints: ident;
+        constraint $z:ident = max($x:ident, $y:ident);
+        $($tail:tt)*) => {
+        {
+            $constraints.add(Box::new(
+                    $crate::constraints::arithmetic::Max::new($x, $y, $z)));
+
+            cp_model!(variables = $variables; constraints = $constraints; $($tail)*);
+        }
+    };
+    (
+        variables = $variables: ident; constraints = $constraints: ident;
+        constraint $z:ident = max([$($x:ident),+]);
+        $($tail:tt)*) => {
+        {
+            let vars = $variables.add(vec![$($x.clone()),+]);
+            $constraints.add(Box::new(
+                    $crate::constraints::arithmetic::ArrayMax::new($z, vars)));
+
+            cp_model!(variables = $variables; constraints = $constraints; $($tail)*);
+        }
+    };
+    (
+        variables = $variables: ident; constraints = $constraints: ident;
+        constraint $z:ident = max($array:ident);
+        $($tail:tt)*) => {
+        {
+            $constraints.add(Box::new(
+                    $crate::constraints::arithmetic::ArrayMax::new($z, $array)));
+
+            cp_model!(variables = $variables; constraints = $constraints; $($tail)*);
+        }
+    };
+    (
+        variables = $variables: ident; constraints = $constraints: ident;
+        constraint $z:ident = pow($x:ident, $y:ident);
+        $($tail:tt)*) => {
+        {
+            $constraints.add(Box::new(
+                    $crate::constraints::arithmetic::Power::new($x, $y, $z)));
+
+            cp_model!(variables = $variables; constraints = $constraints; $($tail)*);
+        }
+    };
+    (
+        variables = $variables: ident; constraints = $constraints: ident;
+        constraint $n:ident = count($array:ident, $value:expr);
+        $($tail:tt)*) => {
+        {
+            $constraints.add(Box::new(
+                    $crate::constraints::Count::new($array, $value, $n)));
+
+            cp_model!(variables = $variables; constraints = $constraints; $($tail)*);
+        }
+    };
+    (
+        variables = $variables: ident; constraints = $constraints: ident;
+        constraint $n:ident = count([$($x:tt),+], $value:expr);
+        $($tail:tt)*) => {
+        {
+            let vars = cp_model!(@List in $variables; $($x),+);
+            $constraints.add(Box::new(
+                    $crate::constraints::Count::new(vars, $value, $n)));
+
+            cp_model!(variables = $variables; constraints = $constraints; $($tail)*);
+        }
+    };
+    (
+        variables = $variables: ident; constraints = $constraints: ident;
+        constraint $n:ident = among($array:ident, [$($v:expr),+]);
+        $($tail:tt)*) => {
+        {
+            $constraints.add(Box::new(
+                    $crate::constraints::Among::new($array, vec![$($v),+], $n)));
+
+            cp_model!(variables = $variables; constraints = $constraints; $($tail)*);
+        }
+    };
+    (
+        variables = $variables: ident; constraints = $constraints: ident;
+        constraint $n:ident = among([$($x:tt),+], [$($v:expr),+]);
+        $($tail:tt)*) => {
+        {
+            let vars = cp_model!(@List in $variables; $($x),+);
+            $constraints.add(Box::new(
+                    $crate::constraints::Among::new(vars, vec![$($v),+], $n)));
+
+            cp_model!(variables = $variables; constraints = $constraints; $($tail)*);
+        }
+    };
+    (
+        variables = $variables: ident; constraints = $constraints: ident;
+        constraint at_most($limit:expr, $array:ident, $value:expr);
+        $($tail:tt)*) => {
+        {
+            $constraints.add(Box::new(
+                    $crate::constraints::AtMost::new($limit, $array, $value)));
+
+            cp_model!(variables = $variables; constraints = $constraints; $($tail)*);
+        }
+    };
+    (
+        variables = $variables: ident; constraints = $constraints: ident;
+        constraint at_most($limit:expr, [$($x:tt),+], $value:expr);
+        $($tail:tt)*) => {
+        {
+            let vars = cp_model!(@List in $variables; $($x),+);
+            $constraints.add(Box::new(
+                    $crate::constraints::AtMost::new($limit, vars, $value)));
+
+            cp_model!(variables = $variables; constraints = $constraints; $($tail)*);
+        }
+    };
+    (
+        variables = $variables: ident; constraints = $constraints: ident;
+        constraint at_least($limit:expr, $array:ident, $value:expr);
+        $($tail:tt)*) => {
+        {
+            $constraints.add(Box::new(
+                    $crate::constraints::AtLeast::new($limit, $array, $value)));
+
+            cp_model!(variables = $variables; constraints = $constraints; $($tail)*);
+        }
+    };
+    (
+        variables = $variables: ident; constraints = $constraints: ident;
+        constraint at_least($limit:expr, [$($x:tt),+], $value:expr);
+        $($tail:tt)*) => {
+        {
+            let vars = cp_model!(@List in $variables; $($x),+);
+            $constraints.add(Box::new(
+                    $crate::constraints::AtLeast::new($limit, vars, $value)));
+
+            cp_model!(variables = $variables; constraints = $constraints; $($tail)*);
+        }
+    };
+    (
+        variables = $variables: ident; constraints = $constraints: ident;
+        constraint lex_less($x:ident, $y:ident);
+        $($tail:tt)*) => {
+        {
+            $constraints.add(Box::new(
+                    $crate::constraints::LexLess::new($x, $y)));
+
+            cp_model!(variables = $variables; constraints = $constraints; $($tail)*);
+        }
+    };
+    (
+        variables = $variables: ident; constraints = $constraints: ident;
+        constraint lex_less([$($x:tt),+], [$($y:tt),+]);
+        $($tail:tt)*) => {
+        {
+            let x = cp_model!(@List in $variables; $($x),+);
+            let y = cp_model!(@List in $variables; $($y),+);
+            $constraints.add(Box::new(
+                    $crate::constraints::LexLess::new(x, y)));
+
+            cp_model!(variables = $variables; constraints = $constraints; $($tail)*);
+        }
+    };
+    (
+        variables = $variables: ident; constraints = $constraints: ident;
+        constraint lex_less_eq($x:ident, $y:ident);
+        $($tail:tt)*) => {
+        {
+            $constraints.add(Box::new(
+                    $crate::constraints::LexLessEq::new($x, $y)));
+
+            cp_model!(variables = $variables; constraints = $constraints; $($tail)*);
+        }
+    };
+    (
+        variables = $variables: ident; constraints = $constraints: ident;
+        constraint lex_less_eq([$($x:tt),+], [$($y:tt),+]);
+        $($tail:tt)*) => {
+        {
+            let x = cp_model!(@List in $variables; $($x),+);
+            let y = cp_model!(@List in $variables; $($y),+);
+            $constraints.add(Box::new(
+                    $crate::constraints::LexLessEq::new(x, y)));
+
+            cp_model!(variables = $variables; constraints = $constraints; $($tail)*);
+        }
+    };
+    (
+        variables = $variables: ident; constraints = $constraints: ident;
+        constraint lex_chain([$($row:ident),+]);
+        $($tail:tt)*) => {
+        {
+            $constraints.add(Box::new(
+                    $crate::constraints::LexChain::new(vec![$($row.clone()),+])));
+
+            cp_model!(variables = $variables; constraints = $constraints; $($tail)*);
+        }
+    };
+    (
+        variables = $variables: ident; constraints = $constraints: ident;
+        constraint strict_lex_chain([$($row:ident),+]);
+        $($tail:tt)*) => {
+        {
+            $constraints.add(Box::new(
+                    $crate::constraints::LexChain::strict(vec![$($row.clone()),+])));
+
+            cp_model!(variables = $variables; constraints = $constraints; $($tail)*);
+        }
+    };
     (@Var; $x:ident) => {
         $x
     };
@@ -509,7 +1158,7 @@ macro_rules! cp_model {
             cp_model!(@VecBuilder = x; $($views),+);
             let variables_selector = SequentialVariableSelector::new(x.into_iter()).unwrap();
             let values_selector = DomainOrderValueSelector::new();
-            let brancher = DefaultBrancher::new(variables_selector, values_selector).unwrap();
+            let brancher = DefaultBrancher::new(variables_selector, values_selector);
             $branchers.add_specific_brancher(Box::new(brancher));
 
             cp_model!(variables = $variables; branchers = $branchers; $($tail)*);
@@ -528,7 +1177,7 @@ macro_rules! cp_model {
             }
             let variables_selector = SequentialVariableSelector::new(x.into_iter()).unwrap();
             let values_selector = DomainOrderValueSelector::new();
-            let brancher = DefaultBrancher::new(variables_selector, values_selector).unwrap();
+            let brancher = DefaultBrancher::new(variables_selector, values_selector);
             $branchers.add_specific_brancher(Box::new(brancher));
 
             cp_model!(variables = $variables; branchers = $branchers; $($tail)*);
@@ -545,7 +1194,7 @@ macro_rules! cp_model {
             cp_model!(@VecBuilder = x; $($views),+);
             let variables_selector = SequentialVariableSelector::new(x.into_iter()).unwrap();
             let values_selector = MinOrderValueSelector::new();
-            let brancher = DefaultBrancher::new(variables_selector, values_selector).unwrap();
+            let brancher = DefaultBrancher::new(variables_selector, values_selector);
             $branchers.add_specific_brancher(Box::new(brancher));
 
             cp_model!(variables = $variables; branchers = $branchers; $($tail)*);
@@ -562,7 +1211,7 @@ macro_rules! cp_model {
             cp_model!(@VecBuilder = x; $($views),+);
             let variables_selector = SequentialVariableSelector::new(x.into_iter()).unwrap();
             let values_selector = MaxValueSelector::new();
-            let brancher = DefaultBrancher::new(variables_selector, values_selector).unwrap();
+            let brancher = DefaultBrancher::new(variables_selector, values_selector);
             $branchers.add_specific_brancher(Box::new(brancher));
 
             cp_model!(variables = $variables; branchers = $branchers; $($tail)*);
@@ -579,7 +1228,7 @@ macro_rules! cp_model {
             cp_model!(@VecBuilder = x; $($views),+);
             let variables_selector = SmallestDomainVariableSelector::new(x.into_iter()).unwrap();
             let values_selector = DomainOrderValueSelector::new();
-            let brancher = DefaultBrancher::new(variables_selector, values_selector).unwrap();
+            let brancher = DefaultBrancher::new(variables_selector, values_selector);
             $branchers.add_specific_brancher(Box::new(brancher));
 
             cp_model!(variables = $variables; branchers = $branchers; $($tail)*);
@@ -598,7 +1247,7 @@ macro_rules! cp_model {
             }
             let variables_selector = SmallestDomainVariableSelector::new(x.into_iter()).unwrap();
             let values_selector = DomainOrderValueSelector::new();
-            let brancher = DefaultBrancher::new(variables_selector, values_selector).unwrap();
+            let brancher = DefaultBrancher::new(variables_selector, values_selector);
             $branchers.add_specific_brancher(Box::new(brancher));
 
             cp_model!(variables = $variables; branchers = $branchers; $($tail)*);
@@ -615,7 +1264,7 @@ macro_rules! cp_model {
             cp_model!(@VecBuilder = x; $($views),+);
             let variables_selector = SmallestDomainVariableSelector::new(x.into_iter()).unwrap();
             let values_selector = MinOrderValueSelector::new();
-            let brancher = DefaultBrancher::new(variables_selector, values_selector).unwrap();
+            let brancher = DefaultBrancher::new(variables_selector, values_selector);
             $branchers.add_specific_brancher(Box::new(brancher));
 
             cp_model!(variables = $variables; branchers = $branchers; $($tail)*);
@@ -632,7 +1281,7 @@ macro_rules! cp_model {
             cp_model!(@VecBuilder = x; $($views),+);
             let variables_selector = SmallestDomainVariableSelector::new(x.into_iter()).unwrap();
             let values_selector = MaxValueSelector::new();
-            let brancher = DefaultBrancher::new(variables_selector, values_selector).unwrap();
+            let brancher = DefaultBrancher::new(variables_selector, values_selector);
             $branchers.add_specific_brancher(Box::new(brancher));
 
             cp_model!(variables = $variables; branchers = $branchers; $($tail)*);
@@ -654,3 +1303,210 @@ macro_rules! cp_model {
         cp_model!(@ListBuilder = $list; $($views),*);
     };
 }
+
+// The list builders of the macro push the views one at a time.
+#[cfg(test)]
+#[allow(clippy::vec_init_then_push)]
+mod tests {
+    #[test]
+    fn inconsistent_model() {
+        // The initial propagation empties the domains, no search is needed.
+        let result = cp_model!(
+            model {
+                let x = var int(5 ..= 9);
+                let y = var int(0 ..= 5);
+
+                constraint x < y;
+            }
+            branchers {
+                branch([x, y], variables_order, domain_order);
+            }
+            solve;
+            output (x, y);
+        );
+        assert!(result.is_none());
+        let result = cp_model!(
+            model {
+                let x = var int(5 ..= 9);
+                let y = var int(0 ..= 5);
+
+                constraint x < y;
+            }
+            branchers {
+                branch([x, y], variables_order, domain_order);
+            }
+            par_solve;
+            output (x, y);
+        );
+        assert!(result.is_none());
+        let result = cp_model!(
+            model {
+                let x = var int(5 ..= 9);
+                let y = var int(0 ..= 5);
+
+                constraint x < y;
+            }
+            branchers {
+                branch([x, y], variables_order, domain_order);
+            }
+            no_copy_solve;
+            output (x, y);
+        );
+        assert!(result.is_none());
+        let result = cp_model!(
+            model {
+                let x = var int(5 ..= 9);
+                let y = var int(0 ..= 5);
+
+                constraint x < y;
+            }
+            branchers {
+                branch([x, y], variables_order, domain_order);
+            }
+            minimize y;
+            output (x, y);
+        );
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn reified_constants() {
+        let result = cp_model!(
+            model {
+                let a = var bool;
+                let b = var bool;
+                let x = var int(0 ..= 3);
+
+                constraint a <-> (x > 1);
+                constraint b -> (x == 2 - 1);
+                constraint a < b;
+            }
+            branchers {
+                branch([a, b], variables_order, domain_order);
+                branch([x], variables_order, domain_max);
+            }
+            solve;
+            output (a, b, x);
+        );
+        let (a, b, x) = result.unwrap();
+        assert_eq!((value!(a), value!(b), value!(x)), (false, true, 1));
+    }
+
+    #[test]
+    fn linear_constraints() {
+        let result = cp_model!(
+            model {
+                let x = var int(0 ..= 3);
+                let y = var int(0 ..= 3);
+
+                constraint 3*x + 2*y >= 10;
+                constraint x + y != 4;
+                constraint 1*x <= y;
+            }
+            branchers {
+                branch([x, y], variables_order, domain_order);
+            }
+            solve;
+            output (x, y);
+        );
+        let (x, y) = result.unwrap();
+        assert_eq!((value!(x), value!(y)), (2, 3));
+    }
+
+    #[test]
+    fn nonlinear_constraints() {
+        let result = cp_model!(
+            model {
+                let x = var int((-3) ..= 3);
+                let z = var int(2 ..= 5);
+                let m = var int((-10) ..= 10);
+                let a = var int(0 ..= 10);
+
+                constraint z = x * x;
+                constraint m = max(x, z);
+                constraint a = abs(x);
+            }
+            branchers {
+                branch([x, z, m, a], variables_order, domain_order);
+            }
+            solve;
+            output (x, z, m, a);
+        );
+        let (x, z, m, a) = result.unwrap();
+        assert_eq!((value!(x), value!(z), value!(m), value!(a)), (-2, 4, 4, 2));
+    }
+
+    #[test]
+    fn count_constraints() {
+        let result = cp_model!(
+            model {
+                let x = var int(0 ..= 2);
+                let y = var int(0 ..= 2);
+                let z = var int(0 ..= 2);
+                let n = var int(2 ..= 3);
+                let m = var int(0 ..= 3);
+
+                constraint n = count([x, y, z], 1);
+                constraint m = among([x, y, z], [0, 2]);
+                constraint at_most(1, [x, y, z], 0);
+                constraint at_least(1, [x, y, z], 2);
+            }
+            branchers {
+                branch([x, y, z, n, m], variables_order, domain_order);
+            }
+            solve;
+            output (x, y, z, n, m);
+        );
+        let (x, y, z, n, m) = result.unwrap();
+        assert_eq!(
+            (value!(x), value!(y), value!(z), value!(n), value!(m)),
+            (1, 1, 2, 2, 1)
+        );
+    }
+
+    #[test]
+    fn lex_constraints() {
+        let result = cp_model!(
+            model {
+                let a = var int(0 ..= 1);
+                let b = var int(0 ..= 1);
+                let c = var int(0 ..= 1);
+                let d = var int(0 ..= 1);
+
+                constraint lex_less([a, b], [c, d]);
+                constraint lex_less_eq([c, d], [b, a]);
+            }
+            branchers {
+                branch([a, b, c, d], variables_order, domain_order);
+            }
+            solve;
+            output (a, b, c, d);
+        );
+        let (a, b, c, d) = result.unwrap();
+        assert_eq!((value!(a), value!(b), value!(c), value!(d)), (0, 1, 1, 0));
+    }
+
+    #[test]
+    fn ordering_constraints() {
+        let result = cp_model!(
+            model {
+                let a = var int(0 ..= 3);
+                let b = var int(0 ..= 3);
+                let c = var int(0 ..= 3);
+                let d = var int(0 ..= 3);
+
+                constraint non_decreasing([a, b]);
+                constraint increasing([b, c]);
+                constraint non_increasing([c, d]);
+                constraint decreasing([a, d]);
+            }
+            branchers {
+                branch([a, b, c, d], variables_order, domain_order);
+            }
+            solve;
+            output (a, b, c, d);
+        );
+        let (a, b, c, d) = result.unwrap();
+        assert_eq!((value!(a), value!(b), value!(c), value!(d)), (1, 1, 2, 0));
+    }
+}
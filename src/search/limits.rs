@@ -0,0 +1,354 @@
+use constraints::handlers::PropagationCounters;
+use search::statistics::SearchStatistics;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Describes the budget allowed to a search. A limit set to `None` is unbounded. The
+/// search stops as soon as one of the counters reaches its maximal value.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SearchLimits {
+    /// Maximal number of explored nodes.
+    pub max_nodes: Option<usize>,
+    /// Maximal number of failed nodes.
+    pub max_failures: Option<usize>,
+    /// Maximal number of solutions.
+    pub max_solutions: Option<usize>,
+    /// Instant after which the search is stopped.
+    pub deadline: Option<Instant>,
+}
+
+impl SearchLimits {
+    /// Creates an unbounded `SearchLimits`.
+    pub fn new() -> SearchLimits {
+        SearchLimits {
+            max_nodes: None,
+            max_failures: None,
+            max_solutions: None,
+            deadline: None,
+        }
+    }
+
+    pub fn with_max_nodes(mut self, nodes: usize) -> SearchLimits {
+        self.max_nodes = Some(nodes);
+        self
+    }
+
+    pub fn with_max_failures(mut self, failures: usize) -> SearchLimits {
+        self.max_failures = Some(failures);
+        self
+    }
+
+    pub fn with_max_solutions(mut self, solutions: usize) -> SearchLimits {
+        self.max_solutions = Some(solutions);
+        self
+    }
+
+    pub fn with_deadline(mut self, deadline: Instant) -> SearchLimits {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Sets the deadline to `timeout` from now.
+    pub fn with_timeout(self, timeout: Duration) -> SearchLimits {
+        self.with_deadline(Instant::now() + timeout)
+    }
+}
+
+impl Default for SearchLimits {
+    fn default() -> SearchLimits {
+        SearchLimits::new()
+    }
+}
+
+/// Describes how a search ended.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SearchStatus {
+    /// The search ended without reaching any limit. For an optimisation, the best
+    /// solution is optimal.
+    Complete,
+    /// The search has been stopped by one of its `SearchLimits`.
+    Interrupted,
+    /// The whole search tree has been explored without finding any solution.
+    Infeasible,
+}
+
+/// Keeps track of the budget consumed by a search and of its statistics. The
+/// counters are shared between the clones of a `SearchMonitor`, so the workers of a
+/// parallel search consume the same budget and their statistics are aggregated.
+#[derive(Debug, Clone)]
+pub struct SearchMonitor {
+    limits: SearchLimits,
+    start: Instant,
+    depth: usize,
+    nodes: Arc<AtomicUsize>,
+    failures: Arc<AtomicUsize>,
+    solutions: Arc<AtomicUsize>,
+    max_depth: Arc<AtomicUsize>,
+    propagations: Option<Arc<PropagationCounters>>,
+    interrupted: Arc<AtomicBool>,
+    stop: Arc<AtomicBool>,
+}
+
+impl SearchMonitor {
+    pub fn new(limits: SearchLimits) -> SearchMonitor {
+        SearchMonitor::new_stop(limits, Arc::new(AtomicBool::new(false)))
+    }
+
+    /// Creates a `SearchMonitor` sharing the `stop` flag. Raising the flag stops the
+    /// search without interrupting it (i.e. another worker has already succeeded).
+    pub fn new_stop(limits: SearchLimits, stop: Arc<AtomicBool>) -> SearchMonitor {
+        SearchMonitor {
+            limits,
+            start: Instant::now(),
+            depth: 0,
+            nodes: Arc::new(AtomicUsize::new(0)),
+            failures: Arc::new(AtomicUsize::new(0)),
+            solutions: Arc::new(AtomicUsize::new(0)),
+            max_depth: Arc::new(AtomicUsize::new(0)),
+            propagations: None,
+            interrupted: Arc::new(AtomicBool::new(false)),
+            stop,
+        }
+    }
+
+    pub fn limits(&self) -> &SearchLimits {
+        &self.limits
+    }
+
+    /// Records a new node. Returns `false` if the search has to stop before
+    /// exploring it, the search is then interrupted if one of the limits has been
+    /// reached. As a search only calls this method when a node remains to be
+    /// explored, a search exhausting its tree is never interrupted.
+    pub fn node(&self) -> bool {
+        if self.must_stop() {
+            return false;
+        }
+        if self.limit_reached() {
+            self.interrupt();
+            return false;
+        }
+        self.nodes.fetch_add(1, Ordering::Relaxed);
+        true
+    }
+
+    /// Records a failed node.
+    pub fn failure(&self) {
+        self.failures.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Records a new solution.
+    pub fn solution(&self) {
+        self.solutions.fetch_add(1, Ordering::Relaxed);
+    }
+
+    // A limit is reached as soon as its counter is equal to its maximal value.
+    fn limit_reached(&self) -> bool {
+        let reached = |counter: &AtomicUsize, max: Option<usize>| {
+            max.is_some_and(|max| counter.load(Ordering::Relaxed) >= max)
+        };
+        reached(&self.nodes, self.limits.max_nodes)
+            || reached(&self.failures, self.limits.max_failures)
+            || reached(&self.solutions, self.limits.max_solutions)
+            || self
+                .limits
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline)
+    }
+
+    /// Returns a clone of this monitor for a search rooted one level deeper. The
+    /// depths recorded by the clone are shifted accordingly.
+    pub fn deeper(&self) -> SearchMonitor {
+        let mut monitor = self.clone();
+        monitor.depth += 1;
+        monitor
+    }
+
+    /// Records that a node has been reached at `depth`.
+    pub fn depth(&self, depth: usize) {
+        let depth = self.depth + depth;
+        self.max_depth.fetch_max(depth, Ordering::Relaxed);
+    }
+
+    /// Reports the propagations counted by `counters` in the statistics. The counters
+    /// are shared by the spaces cloned from the root of the search.
+    pub fn count_propagations(&mut self, counters: Arc<PropagationCounters>) {
+        self.propagations = Some(counters);
+    }
+
+    /// Returns the statistics of the search so far.
+    pub fn statistics(&self) -> SearchStatistics {
+        SearchStatistics {
+            nodes: self.nodes.load(Ordering::Relaxed),
+            failures: self.failures.load(Ordering::Relaxed),
+            solutions: self.solutions.load(Ordering::Relaxed),
+            max_depth: self.max_depth.load(Ordering::Relaxed),
+            propagations: self
+                .propagations
+                .as_ref()
+                .map_or(0, |counters| counters.total()),
+            propagations_per_constraint: self
+                .propagations
+                .as_ref()
+                .map_or(Vec::new(), |counters| counters.per_constraint()),
+            time: self.start.elapsed(),
+        }
+    }
+
+    /// Stops the search without marking it as interrupted.
+    pub fn stop(&self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns `true` if the search has to stop.
+    pub fn must_stop(&self) -> bool {
+        self.is_interrupted() || self.stop.load(Ordering::Relaxed)
+    }
+
+    /// Returns `true` if the search has been stopped by one of its limits while some
+    /// nodes remained to be explored.
+    pub fn is_interrupted(&self) -> bool {
+        self.interrupted.load(Ordering::Relaxed)
+    }
+
+    /// Returns the status of a search that ended with or without `solution`.
+    pub fn status(&self, solution: bool) -> SearchStatus {
+        if self.is_interrupted() {
+            SearchStatus::Interrupted
+        } else if solution {
+            SearchStatus::Complete
+        } else {
+            SearchStatus::Infeasible
+        }
+    }
+
+    fn interrupt(&self) {
+        self.interrupted.store(true, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use branchers::brancher::DefaultBrancher;
+    use branchers::values_selector::MinValueSelector;
+    use branchers::variables_selector::SequentialVariableSelector;
+    use branchers::BranchersHandler;
+    use constraints::handlers::{ConstraintsHandlerBuilder, DefaultConstraintsHandlerBuilder};
+    use constraints::AllDifferent;
+    use search::tests::chain;
+    use search::Solver;
+    use spaces::Space;
+    use variables::handlers::default_handler::Builder;
+    use variables::handlers::{VariableContainerHandlerBuilder, VariablesHandlerBuilder};
+    use variables::int_var::IntVarValuesBuilder;
+
+    #[test]
+    fn limits_are_reached_at_their_value() {
+        let monitor = SearchMonitor::new(SearchLimits::new().with_max_nodes(2));
+        assert!(monitor.node());
+        assert!(monitor.node());
+        assert!(!monitor.is_interrupted());
+        assert!(!monitor.node());
+        assert!(monitor.is_interrupted());
+        assert_eq!(monitor.statistics().nodes, 2);
+
+        let monitor = SearchMonitor::new(SearchLimits::new().with_max_failures(1));
+        assert!(monitor.node());
+        monitor.failure();
+        assert!(!monitor.node());
+        assert_eq!(monitor.status(false), SearchStatus::Interrupted);
+
+        let monitor = SearchMonitor::new(SearchLimits::new().with_max_solutions(1));
+        assert!(monitor.node());
+        monitor.solution();
+        assert!(!monitor.node());
+        assert_eq!(monitor.status(true), SearchStatus::Interrupted);
+    }
+
+    #[test]
+    fn stop_does_not_interrupt() {
+        let monitor = SearchMonitor::new(SearchLimits::new());
+        monitor.stop();
+        assert!(!monitor.node());
+        assert!(!monitor.is_interrupted());
+        assert_eq!(monitor.status(true), SearchStatus::Complete);
+    }
+
+    #[test]
+    fn depth() {
+        let monitor = SearchMonitor::new(SearchLimits::new());
+        monitor.depth(3);
+        monitor.deeper().depth(1);
+        monitor.depth(2);
+        assert_eq!(monitor.statistics().max_depth, 3);
+        monitor.deeper().depth(3);
+        assert_eq!(monitor.statistics().max_depth, 4);
+    }
+
+    #[test]
+    fn max_solutions() {
+        let solver =
+            Solver::with_limits(chain(), SearchLimits::new().with_max_solutions(4));
+        let mut solutions = solver.solutions();
+        assert_eq!(solutions.by_ref().count(), 4);
+        // The last solution exhausts the search tree.
+        assert_eq!(solutions.status(), SearchStatus::Complete);
+
+        let solver =
+            Solver::with_limits(chain(), SearchLimits::new().with_max_solutions(3));
+        let mut solutions = solver.solutions();
+        assert_eq!(solutions.by_ref().count(), 3);
+        assert_eq!(solutions.status(), SearchStatus::Interrupted);
+    }
+
+    #[test]
+    fn max_nodes() {
+        let solver = Solver::new(chain());
+        let mut solutions = solver.solutions();
+        assert_eq!(solutions.by_ref().count(), 4);
+        assert_eq!(solutions.status(), SearchStatus::Complete);
+        let nodes = solutions.statistics().nodes;
+
+        let limits = SearchLimits::new().with_max_nodes(nodes);
+        let mut solutions = Solver::with_limits(chain(), limits).solutions();
+        assert_eq!(solutions.by_ref().count(), 4);
+        assert_eq!(solutions.status(), SearchStatus::Complete);
+
+        let limits = SearchLimits::new().with_max_nodes(nodes - 1);
+        let mut solutions = Solver::with_limits(chain(), limits).solutions();
+        solutions.by_ref().count();
+        assert_eq!(solutions.status(), SearchStatus::Interrupted);
+        assert_eq!(solutions.statistics().nodes, nodes - 1);
+    }
+
+    #[test]
+    fn max_failures() {
+        // Three pigeons in two holes, the failures are only detected by the search.
+        let mut variables = Builder::new_builder();
+        let mut constraints = DefaultConstraintsHandlerBuilder::new();
+        let mut branchers = BranchersHandler::new();
+        let vars: Vec<_> = (0..3)
+            .map(|_| variables.add(IntVarValuesBuilder::new(0, 1).unwrap()))
+            .collect();
+        let array = variables.add(vars.clone());
+        constraints.add(Box::new(AllDifferent::new(array)));
+        let selector = SequentialVariableSelector::new(vars.into_iter()).unwrap();
+        let brancher = DefaultBrancher::new(selector, MinValueSelector::new());
+        branchers.add_specific_brancher(Box::new(brancher));
+        let mut variables = variables.finalize();
+        let constraints = constraints.finalize(&mut variables).unwrap();
+        let space = Space::new(variables, constraints, branchers);
+
+        let mut solver = Solver::new(space.clone());
+        assert!(!solver.solve());
+        assert_eq!(solver.status(), Some(SearchStatus::Infeasible));
+        assert!(solver.statistics().failures > 1);
+
+        let limits = SearchLimits::new().with_max_failures(1);
+        let mut solver = Solver::with_limits(space, limits);
+        assert!(!solver.solve());
+        assert_eq!(solver.status(), Some(SearchStatus::Interrupted));
+        assert_eq!(solver.statistics().failures, 1);
+    }
+}
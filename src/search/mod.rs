@@ -1,13 +1,18 @@
 use constraints::handlers::ConstraintsHandler;
-use spaces::{Space, SpaceState};
+use search::limits::{SearchLimits, SearchMonitor, SearchStatus};
+use search::statistics::SearchStatistics;
+use spaces::{Space, SpaceIterator, SpaceState};
 use std::collections::VecDeque;
 use std::fmt::Debug;
 use variables::handlers::VariablesHandler;
 
+pub mod branch_and_bound;
 #[macro_use]
 pub mod dsl;
+pub mod limits;
 pub mod parallel;
 pub mod path_recomputing;
+pub mod statistics;
 
 #[allow(dead_code)]
 #[derive(Clone)]
@@ -17,107 +22,297 @@ where
     Constraints: ConstraintsHandler<Variables>,
 {
     init: Space<Variables, Constraints>,
+    limits: SearchLimits,
+    status: Option<SearchStatus>,
+    statistics: SearchStatistics,
     solution: Option<Space<Variables, Constraints>>,
 }
 
-enum SearchState<Variables, Constraints>
+impl<Variables, Constraints> Solver<Variables, Constraints>
 where
-    Variables: VariablesHandler + Debug,
+    Variables: VariablesHandler + 'static + Debug,
     Constraints: ConstraintsHandler<Variables>,
 {
-    Node(
+    pub fn new(space: Space<Variables, Constraints>) -> Solver<Variables, Constraints> {
+        Solver::with_limits(space, SearchLimits::new())
+    }
+
+    pub fn with_limits(
+        space: Space<Variables, Constraints>,
+        limits: SearchLimits,
+    ) -> Solver<Variables, Constraints> {
+        Solver {
+            init: space,
+            limits,
+            status: None,
+            statistics: SearchStatistics::new(),
+            solution: None,
+        }
+    }
+
+    pub fn solve(&mut self) -> bool {
+        let mut solutions = self.solutions();
+        self.solution = solutions.next();
+        self.status = Some(if self.solution.is_some() {
+            SearchStatus::Complete
+        } else {
+            solutions.status()
+        });
+        self.statistics = solutions.statistics();
+        self.solution.is_some()
+    }
+
+    /// Returns how the last call to `solve` ended or `None` if `solve` has not been
+    /// called yet.
+    pub fn status(&self) -> Option<SearchStatus> {
+        self.status
+    }
+
+    /// Returns the statistics of the last call to `solve`.
+    pub fn statistics(&self) -> &SearchStatistics {
+        &self.statistics
+    }
+
+    pub fn solution(&mut self) -> Option<Space<Variables, Constraints>> {
+        use std::mem;
+        let mut sol = None;
+        mem::swap(&mut sol, &mut self.solution);
+        sol
+    }
+
+    /// Returns a lazy iterator over all the solutions of the initial space.
+    pub fn solutions(&self) -> Solutions<Variables, Constraints> {
+        Solutions::with_limits(self.init.clone(), self.limits)
+    }
+
+    /// Returns the number of solutions of the initial space. The solutions are
+    /// dropped as soon as they are counted.
+    pub fn count_solutions(&self) -> usize {
+        self.solutions().count()
+    }
+}
+
+/// Lazy iterator over the solutions of a `Space`. The depth first search is
+/// suspended after each solution and resumed on the next call to `next`.
+/// The spaces returned by the iterator are the leaves of the search tree, no
+/// extra copy is made.
+pub struct Solutions<Variables, Constraints>
+where
+    Variables: VariablesHandler + 'static + Debug,
+    Constraints: ConstraintsHandler<Variables>,
+{
+    monitor: SearchMonitor,
+    found: bool,
+    root: Option<Space<Variables, Constraints>>,
+    nodes: VecDeque<(
         Space<Variables, Constraints>,
-        Box<Fn(&mut Space<Variables, Constraints>) -> ()>,
-    ),
-    //Finish,
-    BackTrack,
+        SpaceIterator<Variables, Constraints>,
+    )>,
 }
 
-macro_rules! run_space {
-    ($space:expr, $nodes:ident, $solution:expr) => {{
-        let res = $space.run();
-        match res {
+impl<Variables, Constraints> Solutions<Variables, Constraints>
+where
+    Variables: VariablesHandler + 'static + Debug,
+    Constraints: ConstraintsHandler<Variables>,
+{
+    pub fn new(
+        space: Space<Variables, Constraints>,
+    ) -> Solutions<Variables, Constraints> {
+        Solutions::with_limits(space, SearchLimits::new())
+    }
+
+    pub fn with_limits(
+        mut space: Space<Variables, Constraints>,
+        limits: SearchLimits,
+    ) -> Solutions<Variables, Constraints> {
+        space.reset_propagations();
+        let mut monitor = SearchMonitor::new(limits);
+        monitor.count_propagations(space.propagation_counters());
+        Solutions {
+            monitor,
+            found: false,
+            root: Some(space),
+            nodes: VecDeque::new(),
+        }
+    }
+
+    /// Returns how the enumeration ended. The status is only meaningful once the
+    /// iterator has returned `None`.
+    pub fn status(&self) -> SearchStatus {
+        self.monitor.status(self.found)
+    }
+
+    /// Returns the statistics of the enumeration so far.
+    pub fn statistics(&self) -> SearchStatistics {
+        self.monitor.statistics()
+    }
+
+    // propagate => branch => test if search is ended
+    fn explore(
+        &mut self,
+        mut space: Space<Variables, Constraints>,
+    ) -> Option<Space<Variables, Constraints>> {
+        self.monitor.depth(self.nodes.len());
+        let state = space.run();
+        match state {
             Ok(SpaceState::Subsumed) => {
-                $solution = Some($space.clone());
-                return true;
+                self.found = true;
+                self.monitor.solution();
+                Some(space)
             }
             Ok(SpaceState::Branches(branches)) => {
-                $nodes.push_back(($space.clone(), branches));
+                self.nodes.push_back((space, branches));
+                None
+            }
+            _ => {
+                self.monitor.failure();
+                None
             }
-            _ => return false,
         }
-    }};
+    }
 }
 
-// maybe test if has next to avoid unecessary clone
-macro_rules! next_search_state {
-    ($nodes:expr) => {{
-        match $nodes.back_mut() {
-            Some(&mut (ref mut space, ref mut branches)) => match branches.next() {
-                Some(branch) => {
-                    let space = space.clone();
-                    SearchState::Node(space, branch)
-                }
-                None => SearchState::BackTrack,
-            },
-            _ => unreachable!("nodes is empty can't reach this case!"),
-        }
-    }};
-}
+impl<Variables, Constraints> Iterator for Solutions<Variables, Constraints>
+where
+    Variables: VariablesHandler + 'static + Debug,
+    Constraints: ConstraintsHandler<Variables>,
+{
+    type Item = Space<Variables, Constraints>;
 
-macro_rules! run_search_state {
-    ($nodes:expr, $state:expr, $solution:expr) => {
-        match $state {
-            SearchState::Node(mut space, branch) => {
-                branch(&mut space);
-                let res = space.run();
-                match res {
-                    Ok(SpaceState::Subsumed) => {
-                        $solution = Some(space.clone());
-                        return true;
+    fn next(&mut self) -> Option<Space<Variables, Constraints>> {
+        if let Some(root) = self.root.take() {
+            if !self.monitor.node() {
+                return None;
+            }
+            if let Some(solution) = self.explore(root) {
+                return Some(solution);
+            }
+        }
+        while !self.nodes.is_empty() {
+            // maybe test if has next to avoid unecessary clone
+            let next = match self.nodes.back_mut() {
+                Some(&mut (ref space, ref mut branches)) => {
+                    branches.next().map(|branch| (space.clone(), branch))
+                }
+                _ => unreachable!("nodes is empty can't reach this case!"),
+            };
+            match next {
+                Some((mut space, branch)) => {
+                    if !self.monitor.node() {
+                        return None;
                     }
-                    Ok(SpaceState::Branches(branches)) => {
-                        $nodes.push_back((space.clone(), branches));
+                    branch(&mut space);
+                    if let Some(solution) = self.explore(space) {
+                        return Some(solution);
                     }
-                    _ => (),
                 }
-            }
-            SearchState::BackTrack => {
-                $nodes.pop_back();
+                None => {
+                    self.nodes.pop_back();
+                }
             }
         }
-    };
+        None
+    }
 }
 
-impl<Variables, Constraints> Solver<Variables, Constraints>
-where
-    Variables: VariablesHandler + 'static + Debug,
-    Constraints: ConstraintsHandler<Variables>,
-{
-    pub fn new(space: Space<Variables, Constraints>) -> Solver<Variables, Constraints> {
-        Solver {
-            init: space,
-            solution: None,
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use branchers::brancher::DefaultBrancher;
+    use branchers::values_selector::MinValueSelector;
+    use branchers::variables_selector::SequentialVariableSelector;
+    use branchers::BranchersHandler;
+    use constraints::arithmetic::LessThan;
+    use constraints::handlers::{
+        ConstraintsHandlerBuilder, DefaultConstraintsHandler,
+        DefaultConstraintsHandlerBuilder,
+    };
+    use variables::handlers::default_handler::{Builder, Handler};
+    use variables::handlers::macros::VarView;
+    use variables::handlers::{VariableContainerHandlerBuilder, VariablesHandlerBuilder};
+    use variables::int_var::{IntVarValues, IntVarValuesBuilder};
+    use variables::Variable;
+
+    pub type TestSpace = Space<Handler, DefaultConstraintsHandler<Handler>>;
+
+    /// Builds a space of `len` variables with the domain `0..=max`, branched in order
+    /// on their smallest value, where `vars[i] < vars[j]` for each `(i, j)` of
+    /// `less_than`.
+    pub fn space(
+        len: usize,
+        max: i32,
+        less_than: &[(usize, usize)],
+    ) -> (TestSpace, Vec<VarView<IntVarValues>>) {
+        let mut variables = Builder::new_builder();
+        let mut constraints = DefaultConstraintsHandlerBuilder::new();
+        let mut branchers = BranchersHandler::new();
+        let vars: Vec<_> = (0..len)
+            .map(|_| variables.add(IntVarValuesBuilder::new(0, max).unwrap()))
+            .collect();
+        for &(i, j) in less_than {
+            constraints.add(Box::new(LessThan::new(vars[i], vars[j])));
         }
+        let selector = SequentialVariableSelector::new(vars.clone().into_iter()).unwrap();
+        let brancher = DefaultBrancher::new(selector, MinValueSelector::new());
+        branchers.add_specific_brancher(Box::new(brancher));
+        let mut variables = variables.finalize();
+        let constraints = constraints.finalize(&mut variables).unwrap();
+        (Space::new(variables, constraints, branchers), vars)
     }
 
-    // replace macros by functions?
-    pub fn solve(&mut self) -> bool {
-        let mut nodes = VecDeque::new();
-        // propagate => branch => test if search is ended
-        run_space!(self.init, nodes, self.solution);
+    // x < y < z with x, y, z in 0..=3 has 4 solutions.
+    pub fn chain() -> TestSpace {
+        space(3, 3, &[(0, 1), (1, 2)]).0
+    }
 
-        while !nodes.is_empty() {
-            let state = next_search_state!(nodes);
-            run_search_state!(nodes, state, self.solution);
+    #[test]
+    fn solutions_match_brute_force() {
+        let (space, vars) = space(3, 4, &[(0, 1), (2, 1)]);
+        let solver = Solver::new(space);
+
+        let mut expected = vec![];
+        for x in 0..5 {
+            for y in 0..5 {
+                for z in 0..5 {
+                    if x < y && z < y {
+                        expected.push(vec![x, y, z]);
+                    }
+                }
+            }
         }
-        false
+        let found: Vec<_> = solver
+            .solutions()
+            .map(|solution| {
+                vars.iter()
+                    .map(|view| {
+                        let var: &IntVarValues = solution.get_variable(view);
+                        var.value().unwrap()
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        assert_eq!(found, expected);
+        assert_eq!(solver.count_solutions(), expected.len());
     }
 
-    pub fn solution(&mut self) -> Option<Space<Variables, Constraints>> {
-        use std::mem;
-        let mut sol = None;
-        mem::swap(&mut sol, &mut self.solution);
-        sol
+    #[test]
+    fn solutions_are_lazy() {
+        let (space, _) = space(4, 9, &[]);
+        let solver = Solver::new(space);
+        let mut solutions = solver.solutions();
+        assert_eq!(solutions.by_ref().take(2).count(), 2);
+        // Only the first branches of the search tree have been explored.
+        assert_eq!(solutions.statistics().solutions, 2);
+        assert!(solutions.statistics().nodes < 10);
+    }
+
+    #[test]
+    fn no_solution() {
+        let (space, _) = space(2, 5, &[(0, 1), (1, 0)]);
+        let solver = Solver::new(space);
+        let mut solutions = solver.solutions();
+        assert!(solutions.next().is_none());
+        assert_eq!(solutions.status(), SearchStatus::Infeasible);
+        assert_eq!(solutions.statistics().nodes, 1);
     }
 }
@@ -1,9 +1,12 @@
+use branchers::Branches;
 use constraints::handlers::ConstraintsHandler;
 use rayon::prelude::*;
 use rayon::ThreadPoolBuilder;
 use spaces::{BranchState, Space};
 //use std::collections::VecDeque;
+use search::limits::{SearchLimits, SearchMonitor, SearchStatus};
 use search::path_recomputing::SolverPathRecomputing;
+use search::statistics::SearchStatistics;
 use std::fmt::Debug;
 use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
@@ -19,6 +22,10 @@ where
     init: Space<Variables, Constraints>,
     solution: Option<Space<Variables, Constraints>>,
     level: usize,
+    limits: SearchLimits,
+    monitor: SearchMonitor,
+    status: Option<SearchStatus>,
+    statistics: SearchStatistics,
 }
 
 impl<Variables, Constraints> ParallelSolver<Variables, Constraints>
@@ -28,40 +35,84 @@ where
 {
     pub fn new(
         space: Space<Variables, Constraints>,
+    ) -> ParallelSolver<Variables, Constraints> {
+        ParallelSolver::with_limits(space, SearchLimits::new())
+    }
+
+    /// Creates a new solver. The budget defined by `limits` is shared by all the
+    /// workers.
+    pub fn with_limits(
+        space: Space<Variables, Constraints>,
+        limits: SearchLimits,
     ) -> ParallelSolver<Variables, Constraints> {
         ParallelSolver {
             init: space,
             solution: None,
             level: 4,
+            limits,
+            monitor: SearchMonitor::new(limits),
+            status: None,
+            statistics: SearchStatistics::new(),
         }
     }
 
     // replace macros by functions?
     //pub fn solve(&mut self) -> Option<Space<Variables, Constraints>> {
     pub fn solve(&mut self) -> bool {
-        match self.init.run_branch() {
+        let stop = Arc::new(AtomicBool::new(false));
+        self.init.reset_propagations();
+        self.monitor = SearchMonitor::new_stop(self.limits, stop);
+        self.monitor
+            .count_propagations(self.init.propagation_counters());
+        self.monitor.node();
+        self.monitor.depth(0);
+        let state = self.init.run_branch();
+        let found = match state {
             Ok(BranchState::Subsumed) => {
+                self.monitor.solution();
                 self.solution = Some(self.init.clone());
                 true
             }
             Ok(BranchState::Branches(branches)) => self.dfs(branches),
-            _ => false,
-        }
+            _ => {
+                self.monitor.failure();
+                false
+            }
+        };
+        self.status = Some(if found {
+            SearchStatus::Complete
+        } else {
+            self.monitor.status(false)
+        });
+        self.statistics = self.monitor.statistics();
+        found
+    }
+
+    /// Returns how the last call to `solve` ended or `None` if `solve` has not been
+    /// called yet.
+    pub fn status(&self) -> Option<SearchStatus> {
+        self.status
+    }
+
+    /// Returns the statistics of the last call to `solve`, aggregated over all the
+    /// workers.
+    pub fn statistics(&self) -> &SearchStatistics {
+        &self.statistics
     }
 
     fn solve_space(
         &self,
         space: Space<Variables, Constraints>,
-        stop: Arc<AtomicBool>,
+        monitor: SearchMonitor,
     ) -> Option<Space<Variables, Constraints>> {
-        let mut solver = SolverPathRecomputing::new_stop(space, stop);
+        let mut solver = SolverPathRecomputing::new_monitored(space, monitor);
         solver.solve();
         solver.solution()
     }
 
     fn dfs(
         &mut self,
-        branches: Box<Iterator<Item = Box<Fn(&mut Variables) -> () + Send>>>,
+        branches: Branches<Variables>,
     ) -> bool {
         let _depth = 2usize;
         self.par_dfs(branches)
@@ -69,10 +120,10 @@ where
 
     fn par_dfs(
         &mut self,
-        branches: Box<Iterator<Item = Box<Fn(&mut Variables) -> () + Send>>>,
+        branches: Branches<Variables>,
     ) -> bool {
         let mut branches = branches.collect::<Vec<_>>();
-        let stop = Arc::new(AtomicBool::new(false));
+        let monitor = self.monitor.deeper();
 
         //self.solution = branches
         //.into_par_iter()
@@ -95,13 +146,16 @@ where
                 .map(|branch| {
                     let mut space = self.init.clone();
                     branch(&mut space.variables);
-                    self.solve_space(space, stop.clone())
+                    self.solve_space(space, monitor.clone())
                 })
                 .find_any(Option::is_some)
                 .map(Option::unwrap);
             if self.solution.is_some() {
                 return true;
             }
+            if monitor.is_interrupted() {
+                return false;
+            }
         }
         self.solution.is_some()
     }
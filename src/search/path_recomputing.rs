@@ -1,8 +1,11 @@
+use branchers::Branches;
 use constraints::handlers::ConstraintsHandler;
+use search::limits::{SearchLimits, SearchMonitor, SearchStatus};
+use search::statistics::SearchStatistics;
 use spaces::{BranchState, Space};
 use std::collections::VecDeque;
 use std::fmt::Debug;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 use variables::handlers::VariablesHandler;
 
@@ -14,7 +17,8 @@ where
     Constraints: ConstraintsHandler<Variables>,
 {
     init: Space<Variables, Constraints>,
-    stop: Arc<AtomicBool>,
+    monitor: SearchMonitor,
+    statistics: SearchStatistics,
     solution: Option<Space<Variables, Constraints>>,
 }
 
@@ -37,44 +41,94 @@ where
     pub fn new(
         space: Space<Variables, Constraints>,
     ) -> SolverPathRecomputing<Variables, Constraints> {
-        SolverPathRecomputing {
-            init: space,
-            stop: Arc::new(AtomicBool::new(false)),
-            solution: None,
-        }
+        SolverPathRecomputing::with_limits(space, SearchLimits::new())
     }
 
     pub fn new_stop(
-        space: Space<Variables, Constraints>,
+        mut space: Space<Variables, Constraints>,
         stop: Arc<AtomicBool>,
+    ) -> SolverPathRecomputing<Variables, Constraints> {
+        space.reset_propagations();
+        let mut monitor = SearchMonitor::new_stop(SearchLimits::new(), stop);
+        monitor.count_propagations(space.propagation_counters());
+        SolverPathRecomputing::new_monitored(space, monitor)
+    }
+
+    pub fn with_limits(
+        mut space: Space<Variables, Constraints>,
+        limits: SearchLimits,
+    ) -> SolverPathRecomputing<Variables, Constraints> {
+        space.reset_propagations();
+        let mut monitor = SearchMonitor::new(limits);
+        monitor.count_propagations(space.propagation_counters());
+        SolverPathRecomputing::new_monitored(space, monitor)
+    }
+
+    /// Creates a solver consuming the budget of `monitor`. Used to share the same
+    /// budget between several solvers.
+    pub fn new_monitored(
+        space: Space<Variables, Constraints>,
+        monitor: SearchMonitor,
     ) -> SolverPathRecomputing<Variables, Constraints> {
         SolverPathRecomputing {
             init: space,
-            stop: stop,
+            monitor,
+            statistics: SearchStatistics::new(),
             solution: None,
         }
     }
 
     // replace macros by functions?
     pub fn solve(&mut self) -> bool {
-        match self.init.run_branch() {
+        let found = self.search();
+        self.statistics = self.monitor.statistics();
+        found
+    }
+
+    /// Returns the statistics of the last call to `solve`. When the budget is
+    /// shared with other solvers, the statistics of these solvers are included.
+    pub fn statistics(&self) -> &SearchStatistics {
+        &self.statistics
+    }
+
+    fn search(&mut self) -> bool {
+        if !self.monitor.node() {
+            return false;
+        }
+        self.monitor.depth(0);
+        let state = self.init.run_branch();
+        match state {
             Ok(BranchState::Subsumed) => {
+                self.monitor.solution();
                 self.solution = Some(self.init.clone());
                 true
             }
             Ok(BranchState::Branches(branches)) => self.dfs(branches),
-            _ => false,
+            _ => {
+                self.monitor.failure();
+                false
+            }
+        }
+    }
+
+    /// Returns how the last call to `solve` ended.
+    pub fn status(&self) -> SearchStatus {
+        if self.solution.is_some() {
+            SearchStatus::Complete
+        } else {
+            self.monitor.status(false)
         }
     }
 
     fn dfs(
         &mut self,
-        mut branches: Box<Iterator<Item = Box<Fn(&mut Variables) -> () + Send>>>,
+        mut branches: Branches<Variables>,
     ) -> bool {
         let mut space = self.init.clone();
         //depth
         let branch = branches.next();
         if branch.is_none() {
+            self.monitor.solution();
             self.solution = Some(space);
             return true;
         }
@@ -95,13 +149,16 @@ where
         let mut path = VecDeque::new();
 
         'dfs: while let Some(explored_branch) = branch.pop_back() {
-            if self.stop.load(Ordering::Relaxed) {
+            if !self.monitor.node() {
                 break 'dfs;
             }
             explored_branch(&mut space.variables);
-            match space.run_branch() {
+            self.monitor.depth(path.len() + 1);
+            let state = space.run_branch();
+            match state {
                 Ok(BranchState::Subsumed) => {
-                    self.stop.store(true, Ordering::Relaxed);
+                    self.monitor.solution();
+                    self.monitor.stop();
                     self.solution = Some(space);
                     return true;
                 }
@@ -109,6 +166,7 @@ where
                     path.push_back(explored_branch);
                     let next_branch = next_branches.next();
                     if next_branch.is_none() {
+                        self.monitor.solution();
                         self.solution = Some(space);
                         return true;
                     }
@@ -116,6 +174,7 @@ where
                     branches.push_back(next_branches);
                 }
                 _ => {
+                    self.monitor.failure();
                     'backtrack: while !branches.is_empty() {
                         match branches.back_mut() {
                             Some(ref mut next_branches) => {
@@ -0,0 +1,115 @@
+use std::time::Duration;
+
+/// Statistics about a search.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchStatistics {
+    /// Number of explored nodes.
+    pub nodes: usize,
+    /// Number of failed nodes.
+    pub failures: usize,
+    /// Number of solutions found.
+    pub solutions: usize,
+    /// Maximal depth reached by the search.
+    pub max_depth: usize,
+    /// Number of calls to the `propagate` method of the constraints.
+    pub propagations: usize,
+    /// Number of calls to the `propagate` method of each constraint, in the order the
+    /// constraints have been added.
+    pub propagations_per_constraint: Vec<usize>,
+    /// Time spent since the beginning of the search.
+    pub time: Duration,
+}
+
+impl SearchStatistics {
+    pub fn new() -> SearchStatistics {
+        SearchStatistics {
+            nodes: 0,
+            failures: 0,
+            solutions: 0,
+            max_depth: 0,
+            propagations: 0,
+            propagations_per_constraint: Vec::new(),
+            time: Duration::new(0, 0),
+        }
+    }
+}
+
+impl Default for SearchStatistics {
+    fn default() -> SearchStatistics {
+        SearchStatistics::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use search::parallel::ParallelSolver;
+    use search::path_recomputing::SolverPathRecomputing;
+    use search::tests::chain;
+    use search::Solver;
+    use std::sync::Arc;
+
+    #[test]
+    fn solutions_statistics() {
+        let solver = Solver::new(chain());
+        let mut solutions = solver.solutions();
+        assert_eq!(solutions.by_ref().count(), 4);
+        let statistics = solutions.statistics();
+        assert_eq!(statistics.solutions, 4);
+        assert!(statistics.nodes > statistics.solutions);
+        assert!(statistics.max_depth >= 2);
+        assert_eq!(statistics.propagations_per_constraint.len(), 2);
+        assert!(statistics
+            .propagations_per_constraint
+            .iter()
+            .all(|&n| n > 0));
+        assert_eq!(
+            statistics.propagations_per_constraint.iter().sum::<usize>(),
+            statistics.propagations
+        );
+
+        // Each search counts its own propagations.
+        let mut solutions = solver.solutions();
+        assert_eq!(solutions.by_ref().count(), 4);
+        let again = solutions.statistics();
+        assert_eq!(again.nodes, statistics.nodes);
+        assert_eq!(
+            again.propagations_per_constraint,
+            statistics.propagations_per_constraint
+        );
+    }
+
+    #[test]
+    fn counters_are_shared_by_clones() {
+        let space = chain();
+        let clone = space.clone();
+        assert!(Arc::ptr_eq(
+            &space.propagation_counters(),
+            &clone.propagation_counters()
+        ));
+        let mut reset = space.clone();
+        reset.reset_propagations();
+        assert!(!Arc::ptr_eq(
+            &space.propagation_counters(),
+            &reset.propagation_counters()
+        ));
+    }
+
+    #[test]
+    fn solvers_statistics() {
+        let mut solver = Solver::new(chain());
+        assert!(solver.solve());
+        assert_eq!(solver.statistics().solutions, 1);
+        assert!(solver.statistics().propagations > 0);
+
+        let mut solver = SolverPathRecomputing::new(chain());
+        assert!(solver.solve());
+        assert_eq!(solver.statistics().solutions, 1);
+        assert!(solver.statistics().propagations > 0);
+
+        let mut solver = ParallelSolver::new(chain());
+        assert!(solver.solve());
+        assert!(solver.statistics().solutions >= 1);
+        assert!(solver.statistics().nodes >= 1);
+        assert_eq!(solver.statistics().propagations_per_constraint.len(), 2);
+    }
+}
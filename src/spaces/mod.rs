@@ -1,10 +1,14 @@
-use branchers::BranchersHandler;
-use constraints::handlers::{ConstraintsHandler, ConstraintsHandlerBuilder};
+use branchers::{Branches, BranchersHandler};
+use constraints::handlers::{
+    ConstraintsHandler, ConstraintsHandlerBuilder, PropagationCounters,
+};
 use constraints::PropagationState;
 use std::fmt::Debug;
 use std::marker::PhantomData;
+use std::sync::Arc;
 use variables::handlers::{
-    VariableContainerHandler, VariablesHandler, VariablesHandlerBuilder,
+    VariableContainerHandler, VariableContainerView, VariablesHandler,
+    VariablesHandlerBuilder,
 };
 use variables::{VariableContainer, VariableError};
 
@@ -36,22 +40,24 @@ where
         brancher: BranchersHandler<Variables>,
     ) -> Self {
         SpaceBuilder {
-            variables: variables,
-            constraints: constraints,
-            brancher: brancher,
+            variables,
+            constraints,
+            brancher,
             _phantom: PhantomData,
         }
     }
 
-    //fn finalize(self) -> Space<Variables, Constraints> -> Result<PropagationState,VaraibleError> {
-    //let mut variables = self.variables.finalize();
-    //let constraints = self.constraints.finalize(&mut variables)?;
-    //Space {
-    //variables: variables,
-    //constraints: constraints,
-    //branchers: self.branchers,
-    //}
-    //}
+    /// Builds the `Space`. Fails if the initial propagation of the constraints
+    /// empties the domain of a variable.
+    pub fn finalize(self) -> Result<Space<Variables, Constraints>, VariableError> {
+        let mut variables = self.variables.finalize();
+        let constraints = self.constraints.finalize(&mut variables)?;
+        Ok(Space {
+            variables,
+            constraints,
+            brancher: self.brancher,
+        })
+    }
 }
 
 #[derive(Clone)]
@@ -79,7 +85,7 @@ where
     Variables: VariablesHandler + 'static + Debug,
 {
     Subsumed,
-    Branches(Box<Iterator<Item = Box<Fn(&mut Variables) -> () + Send>>>),
+    Branches(Branches<Variables>),
 }
 
 impl<Variables, Constraints> Space<Variables, Constraints>
@@ -93,16 +99,17 @@ where
         brancher: BranchersHandler<Variables>,
     ) -> Space<Variables, Constraints> {
         Space {
-            variables: variables,
-            constraints: constraints,
-            brancher: brancher,
+            variables,
+            constraints,
+            brancher,
         }
     }
 
-    pub fn get_variable<'a, Var, VCH>(&'a self, view: &VCH::View) -> &'a Var
+    pub fn get_variable<'a, Var, View>(&'a self, view: &View) -> &'a Var
     where
         Var: VariableContainer,
-        VCH: VariableContainerHandler<Var>,
+        View: VariableContainerView<Container = Var>,
+        Variables: VariableContainerHandler<Var, View = View>,
     {
         self.variables.get(view)
     }
@@ -124,6 +131,23 @@ where
         }
     }
 
+    /// Returns the number of calls to the `propagate` method of the constraints of
+    /// the spaces sharing the propagation counters of this space.
+    pub fn propagations(&self) -> usize {
+        self.constraints.propagations().total()
+    }
+
+    /// Returns the propagation counters, shared by this space and its clones.
+    pub fn propagation_counters(&self) -> Arc<PropagationCounters> {
+        self.constraints.propagations().clone()
+    }
+
+    /// Gives new propagation counters to this space, so that its clones count their
+    /// propagations separately from the other spaces.
+    pub fn reset_propagations(&mut self) {
+        self.constraints.reset_propagations();
+    }
+
     fn propagate(&mut self) -> Result<PropagationState, VariableError> {
         self.constraints.propagate_all(&mut self.variables)
     }
@@ -138,7 +162,7 @@ where
     Variables: VariablesHandler + Debug,
     Constraints: ConstraintsHandler<Variables>,
 {
-    branches: Box<Iterator<Item = Box<Fn(&mut Variables) -> () + Send>>>,
+    branches: Branches<Variables>,
     phantom_constraints: PhantomData<Constraints>,
 }
 
@@ -155,7 +179,7 @@ where
             .branch(&space.variables)
             .ok()
             .map(|branches| SpaceIterator {
-                branches: branches,
+                branches,
                 phantom_constraints: PhantomData,
             })
     }
@@ -166,9 +190,9 @@ where
     Variables: VariablesHandler + 'static + Debug,
     Constraints: ConstraintsHandler<Variables>,
 {
-    type Item = Box<Fn(&mut Space<Variables, Constraints>) -> ()>;
+    type Item = Box<Fn(&mut Space<Variables, Constraints>)>;
 
-    fn next(&mut self) -> Option<Box<Fn(&mut Space<Variables, Constraints>) -> ()>> {
+    fn next(&mut self) -> Option<Box<Fn(&mut Space<Variables, Constraints>)>> {
         match self.branches.next() {
             Some(branch) => Some(Box::new(move |space| {
                 branch(&mut space.variables);
@@ -1,9 +1,13 @@
 use variables::domains::{
-    AssignableDomain, FiniteDomain, IterableDomain, PrunableDomain,
+    AssignableDomain, FiniteDomain, FromValuesDomain, IterableDomain, OrderedDomain,
+    OrderedPrunableDomain, PrunableDomain,
+};
+use variables::{
+    Variable, VariableBuilder, VariableContainer, VariableError, VariableId,
+    VariableState,
 };
-use variables::{Variable, VariableError, VariableId, VariableState};
 
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
 enum Domain {
     True,
     False,
@@ -11,44 +15,139 @@ enum Domain {
     None,
 }
 
+impl Domain {
+    fn new(has_false: bool, has_true: bool) -> Domain {
+        match (has_false, has_true) {
+            (true, true) => Domain::Both,
+            (false, true) => Domain::True,
+            (true, false) => Domain::False,
+            (false, false) => Domain::None,
+        }
+    }
+
+    fn contains(&self, value: bool) -> bool {
+        match *self {
+            Domain::Both => true,
+            Domain::True => value,
+            Domain::False => !value,
+            Domain::None => false,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BoolVarBuilder {
+    domain: Domain,
+}
+
+impl BoolVarBuilder {
+    pub fn new() -> BoolVarBuilder {
+        BoolVarBuilder {
+            domain: Domain::Both,
+        }
+    }
+
+    /// Returns a builder of a variable already assigned to `value`.
+    pub fn new_from_value(value: bool) -> BoolVarBuilder {
+        BoolVarBuilder {
+            domain: Domain::new(!value, value),
+        }
+    }
+}
+
+impl Default for BoolVarBuilder {
+    fn default() -> BoolVarBuilder {
+        BoolVarBuilder::new()
+    }
+}
+
+impl VariableBuilder for BoolVarBuilder {
+    type Variable = BoolVar;
+
+    fn finalize(self, id: usize) -> BoolVar {
+        BoolVar {
+            domain: self.domain,
+            id,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct BoolVar {
     domain: Domain,
-    state: VariableState,
+    id: usize,
 }
+impl VariableContainer for BoolVar {}
+
+unsafe impl Sync for BoolVar {}
+unsafe impl Send for BoolVar {}
 
 impl BoolVar {
     pub fn new() -> Option<BoolVar> {
         Some(BoolVar {
             domain: Domain::Both,
-            state: VariableState::NoChange,
+            id: 0,
         })
     }
+
+    /// Restricts the domain to the values satisfying the predicate.
+    fn restrict<Predicate>(
+        &mut self,
+        mut pred: Predicate,
+    ) -> Result<VariableState, VariableError>
+    where
+        Predicate: FnMut(bool) -> bool,
+    {
+        let has_false = self.domain.contains(false) && pred(false);
+        let has_true = self.domain.contains(true) && pred(true);
+        let domain = Domain::new(has_false, has_true);
+        if domain == Domain::None {
+            self.domain = Domain::None;
+            Err(VariableError::DomainWipeout)
+        } else if domain == self.domain {
+            Ok(VariableState::NoChange)
+        } else {
+            self.domain = domain;
+            Ok(VariableState::BoundsChange)
+        }
+    }
 }
 
 impl IterableDomain for BoolVar {
-    fn iter<'a>(&'a self) -> Box<Iterator<Item = &Self::Type> + 'a> {
-        unimplemented!()
+    fn iter<'a>(&'a self) -> Box<Iterator<Item = Self::Type> + 'a> {
+        let domain = self.domain;
+        Box::new(
+            [false, true]
+                .iter()
+                .cloned()
+                .filter(move |&value| domain.contains(value)),
+        )
+    }
+}
+
+impl FromValuesDomain for BoolVar {
+    fn new_from_values<Values>(values: Values) -> Option<BoolVar>
+    where
+        Values: IntoIterator<Item = Self::Type>,
+    {
+        let (mut has_false, mut has_true) = (false, false);
+        for value in values.into_iter() {
+            has_false |= !value;
+            has_true |= value;
+        }
+        match Domain::new(has_false, has_true) {
+            Domain::None => None,
+            domain => Some(BoolVar {
+                domain,
+                id: 0,
+            }),
+        }
     }
 }
 
 impl AssignableDomain for BoolVar {
     fn set_value(&mut self, value: Self::Type) -> Result<VariableState, VariableError> {
-        let value = match self.domain {
-            Domain::Both => value,
-            Domain::True if value => {
-                return Ok(VariableState::NoChange);
-            }
-            Domain::False if !value => {
-                return Ok(VariableState::NoChange);
-            }
-            _ => {
-                self.domain = Domain::None;
-                return Err(VariableError::DomainWipeout);
-            }
-        };
-        self.domain = if value { Domain::True } else { Domain::False };
-        Ok(VariableState::BoundsChange)
+        self.restrict(|v| v == value)
     }
 }
 
@@ -67,7 +166,7 @@ impl Variable for BoolVar {
     }
 
     fn id(&self) -> VariableId {
-        unimplemented!()
+        VariableId(self.id)
     }
 }
 
@@ -82,34 +181,91 @@ impl FiniteDomain for BoolVar {
     }
 }
 
+// `false` is less than `true`.
+#[allow(clippy::bool_comparison)]
+impl OrderedDomain for BoolVar {
+    fn min(&self) -> Option<Self::Type> {
+        match self.domain {
+            Domain::None => None,
+            domain => Some(!domain.contains(false)),
+        }
+    }
+    fn max(&self) -> Option<Self::Type> {
+        match self.domain {
+            Domain::None => None,
+            domain => Some(domain.contains(true)),
+        }
+    }
+
+    fn strict_upperbound(
+        &mut self,
+        ub: Self::Type,
+    ) -> Result<VariableState, VariableError> {
+        self.restrict(|v| v < ub)
+    }
+
+    fn weak_upperbound(
+        &mut self,
+        ub: Self::Type,
+    ) -> Result<VariableState, VariableError> {
+        self.restrict(|v| v <= ub)
+    }
+
+    fn strict_lowerbound(
+        &mut self,
+        lb: Self::Type,
+    ) -> Result<VariableState, VariableError> {
+        self.restrict(|v| v > lb)
+    }
+
+    fn weak_lowerbound(
+        &mut self,
+        lb: Self::Type,
+    ) -> Result<VariableState, VariableError> {
+        self.restrict(|v| v >= lb)
+    }
+}
+
 impl PrunableDomain for BoolVar {
-    #[allow(unused)]
     fn equal(
         &mut self,
         value: &mut Self,
     ) -> Result<(VariableState, VariableState), VariableError> {
-        unimplemented!()
+        let (domain_self, domain_value) = (self.domain, value.domain);
+        let ok_self = self.restrict(|v| domain_value.contains(v));
+        let ok_value = value.restrict(|v| domain_self.contains(v));
+        match (ok_self, ok_value) {
+            (Ok(ok_self), Ok(ok_value)) => Ok((ok_self, ok_value)),
+            _ => {
+                self.domain = Domain::None;
+                value.domain = Domain::None;
+                Err(VariableError::DomainWipeout)
+            }
+        }
     }
 
     fn in_values<Values>(
         &mut self,
-        _values: Values,
+        values: Values,
     ) -> Result<VariableState, VariableError>
     where
         Values: IntoIterator<Item = Self::Type>,
     {
-        unimplemented!()
+        let (mut has_false, mut has_true) = (false, false);
+        for value in values.into_iter() {
+            has_false |= !value;
+            has_true |= value;
+        }
+        self.restrict(|v| if v { has_true } else { has_false })
     }
 
-    #[allow(unused)]
     fn remove_value(
         &mut self,
         value: Self::Type,
     ) -> Result<VariableState, VariableError> {
-        unimplemented!()
+        self.restrict(|v| v != value)
     }
 
-    #[allow(unused)]
     fn remove_if<Predicate>(
         &mut self,
         mut pred: Predicate,
@@ -117,10 +273,9 @@ impl PrunableDomain for BoolVar {
     where
         Predicate: FnMut(&Self::Type) -> bool,
     {
-        unimplemented!()
+        self.restrict(|v| !pred(&v))
     }
 
-    #[allow(unused)]
     fn retains_if<Predicate>(
         &mut self,
         mut pred: Predicate,
@@ -128,14 +283,160 @@ impl PrunableDomain for BoolVar {
     where
         Predicate: FnMut(&Self::Type) -> bool,
     {
-        unimplemented!()
+        self.restrict(|v| pred(&v))
     }
 
-    #[allow(unused)]
     fn not_equal(
         &mut self,
         value: &mut BoolVar,
     ) -> Result<(VariableState, VariableState), VariableError> {
-        unimplemented!()
+        match self.value() {
+            Some(val) => {
+                let ok_value = value.remove_value(val)?;
+                Ok((VariableState::NoChange, ok_value))
+            }
+            _ => match value.value() {
+                Some(val) => {
+                    let ok_self = self.remove_value(val)?;
+                    Ok((ok_self, VariableState::NoChange))
+                }
+                _ => Ok((VariableState::NoChange, VariableState::NoChange)),
+            },
+        }
+    }
+}
+
+impl OrderedPrunableDomain for BoolVar {
+    fn in_sorted_values<Values>(
+        &mut self,
+        values: Values,
+    ) -> Result<VariableState, VariableError>
+    where
+        Values: IntoIterator<Item = Self::Type>,
+    {
+        self.in_values(values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn domains() -> Vec<Vec<bool>> {
+        vec![vec![false, true], vec![false], vec![true]]
+    }
+
+    #[test]
+    fn new_from_values() {
+        for domain in domains().into_iter() {
+            let var = BoolVar::new_from_values(domain.clone()).unwrap();
+            assert!(var.iter().eq(domain.iter().cloned()));
+            assert_eq!(var.size(), domain.len());
+            assert_eq!(var.is_affected(), domain.len() == 1);
+        }
+        assert!(BoolVar::new_from_values(vec![]).is_none());
+    }
+
+    #[test]
+    fn set_value() {
+        for domain in domains().into_iter() {
+            for &value in [false, true].iter() {
+                let mut var = BoolVar::new_from_values(domain.clone()).unwrap();
+                let res = var.set_value(value);
+                if !domain.contains(&value) {
+                    assert_eq!(res, Err(VariableError::DomainWipeout));
+                    assert_eq!(var.size(), 0);
+                    assert_eq!(var.value(), None);
+                } else if domain.len() == 1 {
+                    assert_eq!(res, Ok(VariableState::NoChange));
+                    assert_eq!(var.value(), Some(value));
+                } else {
+                    assert_eq!(res, Ok(VariableState::BoundsChange));
+                    assert_eq!(var.value(), Some(value));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn in_values() {
+        let values = [vec![], vec![false], vec![true], vec![true, false, true]];
+        for domain in domains().into_iter() {
+            for allowed in values.iter() {
+                let mut var = BoolVar::new_from_values(domain.clone()).unwrap();
+                let exp_domain: Vec<_> = domain
+                    .iter()
+                    .cloned()
+                    .filter(|value| allowed.contains(value))
+                    .collect();
+                let res = var.in_values(allowed.clone());
+                if exp_domain.is_empty() {
+                    assert_eq!(res, Err(VariableError::DomainWipeout));
+                } else if exp_domain == domain {
+                    assert_eq!(res, Ok(VariableState::NoChange));
+                    assert!(var.iter().eq(exp_domain.into_iter()));
+                } else {
+                    assert_eq!(res, Ok(VariableState::BoundsChange));
+                    assert!(var.iter().eq(exp_domain.into_iter()));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn bounds() {
+        let mut var = BoolVar::new().unwrap();
+        assert_eq!(var.min(), Some(false));
+        assert_eq!(var.max(), Some(true));
+        assert_eq!(
+            var.strict_lowerbound(false),
+            Ok(VariableState::BoundsChange)
+        );
+        assert_eq!(var.value(), Some(true));
+        assert_eq!(var.weak_lowerbound(false), Ok(VariableState::NoChange));
+        assert_eq!(
+            var.strict_upperbound(true),
+            Err(VariableError::DomainWipeout)
+        );
+        assert_eq!(var.min(), None);
+        assert_eq!(var.max(), None);
+    }
+
+    #[test]
+    fn wipeout() {
+        let mut var = BoolVar::new().unwrap();
+        assert_eq!(var.remove_value(false), Ok(VariableState::BoundsChange));
+        assert_eq!(var.remove_value(false), Ok(VariableState::NoChange));
+        assert_eq!(var.remove_value(true), Err(VariableError::DomainWipeout));
+        assert_eq!(var.size(), 0);
+        assert!(var.iter().next().is_none());
+
+        let mut var = BoolVar::new().unwrap();
+        assert_eq!(var.remove_if(|_| true), Err(VariableError::DomainWipeout));
+        let mut var = BoolVar::new().unwrap();
+        assert_eq!(var.retains_if(|_| false), Err(VariableError::DomainWipeout));
+    }
+
+    #[test]
+    fn equal_and_not_equal() {
+        let mut lhs = BoolVar::new().unwrap();
+        let mut rhs = BoolVar::new_from_values(vec![true]).unwrap();
+        assert_eq!(
+            lhs.equal(&mut rhs),
+            Ok((VariableState::BoundsChange, VariableState::NoChange))
+        );
+        assert_eq!(lhs.value(), Some(true));
+
+        let mut lhs = BoolVar::new().unwrap();
+        assert_eq!(
+            lhs.not_equal(&mut rhs),
+            Ok((VariableState::BoundsChange, VariableState::NoChange))
+        );
+        assert_eq!(lhs.value(), Some(false));
+
+        let mut lhs = BoolVar::new_from_values(vec![false]).unwrap();
+        assert_eq!(lhs.equal(&mut rhs), Err(VariableError::DomainWipeout));
+        assert_eq!(lhs.size(), 0);
+        assert_eq!(rhs.size(), 0);
     }
 }
@@ -10,7 +10,10 @@ pub trait FiniteDomain: Variable {
 /// Trait that definies variable allowing to iter through the elements of its domain.
 pub trait IterableDomain: FiniteDomain {
     /// Returns an `Iterator` over the elements of the domain.
-    fn iter<'a>(&'a self) -> Box<Iterator<Item = &Self::Type> + 'a>;
+    ///
+    /// The values are yielded by value: a domain stored as intervals or as a bitset
+    /// does not hold its values and thus can not hand out references to them.
+    fn iter<'a>(&'a self) -> Box<Iterator<Item = Self::Type> + 'a>;
 }
 
 /// Trait that defines variableswhich the domain can be deduced from an interval.
@@ -266,7 +269,7 @@ where
     ///
     /// # Parameters
     /// * `value` - The variable to compare to.
-    fn in_sorted_values<Values: Iterator<Item = Self::Type>>(
+    fn in_sorted_values<Values>(
         &mut self,
         values: Values,
     ) -> Result<VariableState, VariableError>
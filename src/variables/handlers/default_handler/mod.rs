@@ -1,3 +1,12 @@
-use variables::int_var::IntVarValuesBuilder;
+use variables::bool_var::BoolVarBuilder;
+use variables::int_var::{
+    IntVarBitsetBuilder, IntVarBoundsBuilder, IntVarIntervalsBuilder, IntVarValuesBuilder,
+};
 
-variables_handler_build!(IntVarValuesBuilder);
+variables_handler_build!(
+    IntVarValuesBuilder,
+    IntVarBitsetBuilder,
+    IntVarIntervalsBuilder,
+    IntVarBoundsBuilder,
+    BoolVarBuilder
+);
@@ -1,6 +1,7 @@
 use snowflake::ProcessUniqueId;
 use std::marker::PhantomData;
 use std::sync::Arc;
+use variables::handlers::VariableContainerView;
 use variables::{ArrayOfRefs, ArrayOfVars, Variable};
 
 // move Var and ArrayOfVarsView inside macro => find how to handle extern crate ProcessUniqeId
@@ -14,6 +15,7 @@ pub enum VarIndexType {
 // CLone and Copy are implemented manually due to phantom data
 #[derive(Debug)]
 pub struct VarView<Var: Variable> {
+    #[allow(dead_code)]
     id: ProcessUniqueId,
     pub view: VarIndexType,
     phantom: PhantomData<*const Var>,
@@ -23,23 +25,18 @@ unsafe impl<Var: Variable> Send for VarView<Var> {}
 
 impl<Var: Variable> Clone for VarView<Var> {
     fn clone(&self) -> VarView<Var> {
-        VarView {
-            id: self.id,
-            view: self.view,
-            phantom: PhantomData,
-        }
+        *self
     }
 }
 impl<Var: Variable> Copy for VarView<Var> {}
-//impl<Var: Variable> VariableContainerView for VarView<Var> {
-//type Container = Var;
-//type Variable = Var;
-//}
+impl<Var: Variable> VariableContainerView for VarView<Var> {
+    type Container = Var;
+}
 
 impl<Var: Variable> VarView<Var> {
     pub fn new(id: ProcessUniqueId, x: usize) -> VarView<Var> {
         VarView {
-            id: id,
+            id,
             view: VarIndexType::FromVar(x),
             phantom: PhantomData,
         }
@@ -47,7 +44,7 @@ impl<Var: Variable> VarView<Var> {
 
     pub fn new_from_array(id: ProcessUniqueId, x: usize, y: usize) -> VarView<Var> {
         VarView {
-            id: id,
+            id,
             view: VarIndexType::FromArrayOfVars(x, y),
             phantom: PhantomData,
         }
@@ -69,24 +66,19 @@ unsafe impl<Var: Variable> Send for ArrayOfVarsView<Var> {}
 
 impl<Var: Variable> Clone for ArrayOfVarsView<Var> {
     fn clone(&self) -> ArrayOfVarsView<Var> {
-        ArrayOfVarsView {
-            id: self.id,
-            x: self.x,
-            phantom: PhantomData,
-        }
+        *self
     }
 }
 impl<Var: Variable> Copy for ArrayOfVarsView<Var> {}
-//impl<Var: Variable> VariableContainerView for ArrayOfVarsView<Var> {
-//type Container = ArrayOfVars<Var>;
-//type Variable = Var;
-//}
+impl<Var: Variable> VariableContainerView for ArrayOfVarsView<Var> {
+    type Container = ArrayOfVars<Var>;
+}
 
 impl<Var: Variable> ArrayOfVarsView<Var> {
     pub fn new(id: ProcessUniqueId, x: usize) -> ArrayOfVarsView<Var> {
         ArrayOfVarsView {
-            id: id,
-            x: x,
+            id,
+            x,
             phantom: PhantomData,
         }
     }
@@ -110,6 +102,7 @@ impl<Var: Variable> ArrayOfVarsView<Var> {
 
 #[derive(Debug)]
 pub struct ArrayOfRefsView<Var: Variable> {
+    #[allow(dead_code)]
     id: ProcessUniqueId,
     x: usize,
     phantom: PhantomData<Var>,
@@ -117,24 +110,19 @@ pub struct ArrayOfRefsView<Var: Variable> {
 unsafe impl<Var: Variable> Send for ArrayOfRefsView<Var> {}
 impl<Var: Variable> Clone for ArrayOfRefsView<Var> {
     fn clone(&self) -> ArrayOfRefsView<Var> {
-        ArrayOfRefsView {
-            id: self.id,
-            x: self.x,
-            phantom: PhantomData,
-        }
+        *self
     }
 }
 impl<Var: Variable> Copy for ArrayOfRefsView<Var> {}
-//impl<Var: Variable> VariableContainerView for ArrayOfRefsView<Var> {
-//type Container = ArrayOfRefs<Var>;
-//type Variable = Var;
-//}
+impl<Var: Variable> VariableContainerView for ArrayOfRefsView<Var> {
+    type Container = ArrayOfRefs<Var>;
+}
 
 impl<Var: Variable> ArrayOfRefsView<Var> {
     pub fn new(id: ProcessUniqueId, x: usize) -> ArrayOfRefsView<Var> {
         ArrayOfRefsView {
-            id: id,
-            x: x,
+            id,
+            x,
             phantom: PhantomData,
         }
     }
@@ -152,6 +140,12 @@ pub struct VariableHandlerBuilder<Var: Variable> {
     pub variables_ref_view: Vec<Arc<Vec<VarView<Var>>>>,
 }
 
+impl<Var: Variable> Default for VariableHandlerBuilder<Var> {
+    fn default() -> VariableHandlerBuilder<Var> {
+        VariableHandlerBuilder::new()
+    }
+}
+
 impl<Var: Variable> VariableHandlerBuilder<Var> {
     pub fn new() -> Self {
         VariableHandlerBuilder {
@@ -169,7 +163,7 @@ impl<Var: Variable> VariableHandlerBuilder<Var> {
 
         let variables_ref: Vec<ArrayOfRefs<Var>> = variables_ref_view
             .iter()
-            .map(|ref views| {
+            .map(|views| {
                 let ref_array = views
                     .iter()
                     .map(|view| unsafe {
@@ -187,11 +181,11 @@ impl<Var: Variable> VariableHandlerBuilder<Var> {
                 ArrayOfRefs::new(ref_array).unwrap()
             }).collect();
         VariableHandler {
-            id: id,
-            variables: variables,
-            variables_array: variables_array,
-            variables_ref: variables_ref,
-            variables_ref_view: variables_ref_view,
+            id,
+            variables,
+            variables_array,
+            variables_ref,
+            variables_ref_view,
         }
     }
 }
@@ -255,6 +249,12 @@ macro_rules! variables_handler_build {
              ),+
         }
 
+        impl Default for Builder {
+            fn default() -> Builder {
+                Builder::new()
+            }
+        }
+
         impl Builder {
             pub fn new() -> Builder {
                 Builder {
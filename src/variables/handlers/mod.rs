@@ -22,10 +22,8 @@ pub trait VariablesHandlerBuilder<VarHandler: VariablesHandler> {
 /// its holds itsefl. An Array of Variables is a VariableContainer because it holds
 /// a list of Variables.
 pub trait VariableContainerView: Clone {
-    ///// The `Type` managed by the Handler (Variable or Array of Variable).
-    //type Container;
-    ///// The type of `Variable` managed by the handler (Type == Variable if the Type is a variable).
-    //type Variable: Variable;
+    /// The `Type` managed by the Handler (Variable or Array of Variable).
+    type Container: VariableContainer;
 }
 
 /// Gives immutable and mutable acces to owned variables. A `SpecificVariablesHandler`
@@ -82,9 +80,21 @@ where
 pub mod macros;
 pub mod default_handler;
 
+/// The default handler manages every kind of variable provided by the crate. Each kind
+/// of variable has its own views, so a model can mix them and link them with constraints.
 mod default {
-    use variables::int_var::IntVarValuesBuilder;
-    variables_handler_build!(IntVarValuesBuilder);
+    use variables::bool_var::BoolVarBuilder;
+    use variables::int_var::{
+        IntVarBitsetBuilder, IntVarBoundsBuilder, IntVarIntervalsBuilder,
+        IntVarValuesBuilder,
+    };
+    variables_handler_build!(
+        IntVarValuesBuilder,
+        IntVarBitsetBuilder,
+        IntVarIntervalsBuilder,
+        IntVarBoundsBuilder,
+        BoolVarBuilder
+    );
 }
 pub use self::default::Builder as DefaultVariablesBuilder;
 pub use self::default::Handler as DefaultVariablesHandler;
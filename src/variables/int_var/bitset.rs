@@ -0,0 +1,561 @@
+use variables::domains::{
+    AssignableDomain, FiniteDomain, FromRangeDomain, FromValuesDomain, IterableDomain,
+    OrderedDomain, OrderedPrunableDomain, PrunableDomain,
+};
+use variables::{
+    Variable, VariableBuilder, VariableContainer, VariableError, VariableId,
+    VariableState,
+};
+
+const WORD_BITS: usize = 64;
+
+/// Number of words required to store the interval `min..=max`.
+fn nb_words(min: i32, max: i32) -> usize {
+    let width = (i64::from(max) - i64::from(min) + 1) as usize;
+    width.div_ceil(WORD_BITS)
+}
+
+/// Words with the bits `0..width` set.
+fn full_words(width: usize) -> Vec<u64> {
+    let mut words = vec![!0u64; width.div_ceil(WORD_BITS)];
+    let tail = width % WORD_BITS;
+    if tail != 0 {
+        *words.last_mut().unwrap() = (1u64 << tail) - 1;
+    }
+    words
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IntVarBitsetBuilder {
+    offset: i32,
+    words: Vec<u64>,
+}
+
+impl IntVarBitsetBuilder {
+    pub fn new(min: i32, max: i32) -> Option<IntVarBitsetBuilder> {
+        if min > max {
+            None
+        } else {
+            let width = (i64::from(max) - i64::from(min) + 1) as usize;
+            Some(IntVarBitsetBuilder {
+                offset: min,
+                words: full_words(width),
+            })
+        }
+    }
+
+    pub fn new_from_values<Values>(values: Values) -> Option<IntVarBitsetBuilder>
+    where
+        Values: IntoIterator<Item = i32>,
+    {
+        let values = values.into_iter().collect::<Vec<_>>();
+        let (min, max) = match (values.iter().min(), values.iter().max()) {
+            (Some(&min), Some(&max)) => (min, max),
+            _ => return None,
+        };
+        let mut words = vec![0u64; nb_words(min, max)];
+        for value in values.into_iter() {
+            let index = (i64::from(value) - i64::from(min)) as usize;
+            words[index / WORD_BITS] |= 1u64 << (index % WORD_BITS);
+        }
+        Some(IntVarBitsetBuilder {
+            offset: min,
+            words,
+        })
+    }
+}
+
+impl VariableBuilder for IntVarBitsetBuilder {
+    type Variable = IntVarBitset;
+
+    fn finalize(self, id: usize) -> IntVarBitset {
+        let mut var = IntVarBitset {
+            offset: self.offset,
+            words: self.words,
+            size: 0,
+            min: 0,
+            max: 0,
+            id,
+        };
+        var.refresh();
+        var
+    }
+}
+
+/// Integer variable which the domain is stored as a bitset: the value `offset + i`
+/// belongs to the domain if and only if the i-th bit is set. Membership is checked
+/// in constant time and the domain of the variable only requires one bit per value
+/// of its initial interval, it should be used for small domains.
+#[derive(Clone, Debug, Eq)]
+pub struct IntVarBitset {
+    offset: i32,
+    words: Vec<u64>,
+    size: usize,
+    min: i32,
+    max: i32,
+    id: usize,
+}
+impl VariableContainer for IntVarBitset {}
+
+// Two variables are equal if they have the same id and the same domain, whatever the
+// interval covered by their bitsets.
+impl PartialEq for IntVarBitset {
+    fn eq(&self, other: &IntVarBitset) -> bool {
+        self.id == other.id && self.size == other.size && self.iter().eq(other.iter())
+    }
+}
+
+unsafe impl Sync for IntVarBitset {}
+unsafe impl Send for IntVarBitset {}
+
+impl IntVarBitset {
+    pub fn new(min: i32, max: i32) -> Option<IntVarBitset> {
+        IntVarBitsetBuilder::new(min, max).map(|builder| builder.finalize(0))
+    }
+
+    /// Returns `true` if `value` belongs to the domain.
+    pub fn contains(&self, value: i32) -> bool {
+        if value < self.min || value > self.max {
+            false
+        } else {
+            self.is_set(self.index(value))
+        }
+    }
+
+    fn index(&self, value: i32) -> usize {
+        (i64::from(value) - i64::from(self.offset)) as usize
+    }
+
+    fn value_at(&self, index: usize) -> i32 {
+        (i64::from(self.offset) + index as i64) as i32
+    }
+
+    fn is_set(&self, index: usize) -> bool {
+        self.words[index / WORD_BITS] & (1u64 << (index % WORD_BITS)) != 0
+    }
+
+    fn clear(&mut self, index: usize) {
+        self.words[index / WORD_BITS] &= !(1u64 << (index % WORD_BITS));
+    }
+
+    /// Index of the first set bit greater or equal to `index`.
+    fn next_set(&self, index: usize) -> Option<usize> {
+        let mut word = index / WORD_BITS;
+        if word >= self.words.len() {
+            return None;
+        }
+        let mut bits = self.words[word] & (!0u64 << (index % WORD_BITS));
+        loop {
+            if bits != 0 {
+                return Some(word * WORD_BITS + bits.trailing_zeros() as usize);
+            }
+            word += 1;
+            if word == self.words.len() {
+                return None;
+            }
+            bits = self.words[word];
+        }
+    }
+
+    /// Index of the last set bit lesser or equal to `index`.
+    fn prev_set(&self, index: usize) -> Option<usize> {
+        let mut word = index / WORD_BITS;
+        let shift = WORD_BITS - 1 - index % WORD_BITS;
+        let mut bits = self.words[word] & (!0u64 >> shift);
+        loop {
+            if bits != 0 {
+                return Some(
+                    word * WORD_BITS + WORD_BITS - 1 - bits.leading_zeros() as usize,
+                );
+            }
+            if word == 0 {
+                return None;
+            }
+            word -= 1;
+            bits = self.words[word];
+        }
+    }
+
+    /// Clears the bits `from..=to`.
+    fn clear_range(&mut self, from: usize, to: usize) {
+        let (first, last) = (from / WORD_BITS, to / WORD_BITS);
+        for word in first..(last + 1) {
+            let mut mask = !0u64;
+            if word == first {
+                mask &= !0u64 << (from % WORD_BITS);
+            }
+            if word == last {
+                mask &= !0u64 >> (WORD_BITS - 1 - to % WORD_BITS);
+            }
+            self.words[word] &= !mask;
+        }
+    }
+
+    fn count(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    /// Recomputes the size and the bounds of the domain from the bitset.
+    fn refresh(&mut self) {
+        self.size = self.count();
+        if self.size == 0 {
+            self.invalidate();
+        } else {
+            self.min = self.value_at(self.next_set(0).unwrap());
+            let last = self.words.len() * WORD_BITS - 1;
+            self.max = self.value_at(self.prev_set(last).unwrap());
+        }
+    }
+
+    fn invalidate(&mut self) {
+        for word in self.words.iter_mut() {
+            *word = 0;
+        }
+        self.size = 0;
+        self.min = i32::MAX;
+        self.max = i32::MIN;
+    }
+
+    fn domain_change(
+        &mut self,
+        prev_min: i32,
+        prev_max: i32,
+        prev_size: usize,
+    ) -> Result<VariableState, VariableError> {
+        if self.size == 0 {
+            self.invalidate();
+            Err(VariableError::DomainWipeout)
+        } else if self.size == prev_size {
+            Ok(VariableState::NoChange)
+        } else if self.min != prev_min || self.max != prev_max {
+            Ok(VariableState::BoundsChange)
+        } else {
+            Ok(VariableState::ValuesChange)
+        }
+    }
+
+    /// Keeps only the values satisfying the predicate.
+    fn retain<Predicate>(
+        &mut self,
+        mut pred: Predicate,
+    ) -> Result<VariableState, VariableError>
+    where
+        Predicate: FnMut(i32) -> bool,
+    {
+        let (min, max, size) = (self.min, self.max, self.size);
+        for word in 0..self.words.len() {
+            let mut bits = self.words[word];
+            while bits != 0 {
+                let bit = bits.trailing_zeros() as usize;
+                bits &= bits - 1;
+                let index = word * WORD_BITS + bit;
+                if !pred(self.value_at(index)) {
+                    self.clear(index);
+                }
+            }
+        }
+        self.refresh();
+        self.domain_change(min, max, size)
+    }
+}
+
+/// Iterator over the values of an `IntVarBitset`.
+struct BitsetIter<'a> {
+    var: &'a IntVarBitset,
+    word: usize,
+    bits: u64,
+}
+
+impl<'a> Iterator for BitsetIter<'a> {
+    type Item = i32;
+    fn next(&mut self) -> Option<i32> {
+        while self.bits == 0 {
+            self.word += 1;
+            if self.word >= self.var.words.len() {
+                return None;
+            }
+            self.bits = self.var.words[self.word];
+        }
+        let bit = self.bits.trailing_zeros() as usize;
+        self.bits &= self.bits - 1;
+        Some(self.var.value_at(self.word * WORD_BITS + bit))
+    }
+}
+
+impl IterableDomain for IntVarBitset {
+    fn iter<'a>(&'a self) -> Box<Iterator<Item = Self::Type> + 'a> {
+        Box::new(BitsetIter {
+            var: self,
+            word: 0,
+            bits: self.words.first().cloned().unwrap_or(0),
+        })
+    }
+}
+
+impl FromRangeDomain for IntVarBitset {
+    fn new_from_range(min: Self::Type, max: Self::Type) -> Option<IntVarBitset> {
+        IntVarBitset::new(min, max)
+    }
+}
+
+impl FromValuesDomain for IntVarBitset {
+    fn new_from_values<Values>(values: Values) -> Option<IntVarBitset>
+    where
+        Values: IntoIterator<Item = Self::Type>,
+    {
+        IntVarBitsetBuilder::new_from_values(values).map(|builder| builder.finalize(0))
+    }
+}
+
+impl AssignableDomain for IntVarBitset {
+    fn set_value(&mut self, value: Self::Type) -> Result<VariableState, VariableError> {
+        if !self.contains(value) {
+            self.invalidate();
+            Err(VariableError::DomainWipeout)
+        } else if self.size == 1 {
+            Ok(VariableState::NoChange)
+        } else {
+            let index = self.index(value);
+            for word in self.words.iter_mut() {
+                *word = 0;
+            }
+            self.words[index / WORD_BITS] = 1u64 << (index % WORD_BITS);
+            self.size = 1;
+            self.min = value;
+            self.max = value;
+            Ok(VariableState::BoundsChange)
+        }
+    }
+}
+
+impl Variable for IntVarBitset {
+    type Type = i32;
+    fn is_affected(&self) -> bool {
+        self.size == 1
+    }
+
+    fn value(&self) -> Option<Self::Type> {
+        if self.is_affected() {
+            Some(self.min)
+        } else {
+            None
+        }
+    }
+
+    fn id(&self) -> VariableId {
+        VariableId(self.id)
+    }
+}
+
+impl FiniteDomain for IntVarBitset {
+    fn size(&self) -> usize {
+        self.size
+    }
+}
+
+impl OrderedDomain for IntVarBitset {
+    fn min(&self) -> Option<Self::Type> {
+        if self.size == 0 {
+            None
+        } else {
+            Some(self.min)
+        }
+    }
+    fn max(&self) -> Option<Self::Type> {
+        if self.size == 0 {
+            None
+        } else {
+            Some(self.max)
+        }
+    }
+
+    fn strict_upperbound(
+        &mut self,
+        ub: Self::Type,
+    ) -> Result<VariableState, VariableError> {
+        if self.max < ub {
+            Ok(VariableState::NoChange)
+        } else if self.min >= ub {
+            self.invalidate();
+            Err(VariableError::DomainWipeout)
+        } else {
+            self.weak_upperbound(ub - 1)
+        }
+    }
+
+    fn weak_upperbound(
+        &mut self,
+        ub: Self::Type,
+    ) -> Result<VariableState, VariableError> {
+        if self.max <= ub {
+            Ok(VariableState::NoChange)
+        } else if self.min > ub {
+            self.invalidate();
+            Err(VariableError::DomainWipeout)
+        } else {
+            let (index, max_index) = (self.index(ub), self.index(self.max));
+            self.clear_range(index + 1, max_index);
+            self.size = self.count();
+            self.max = self.value_at(self.prev_set(index).unwrap());
+            Ok(VariableState::BoundsChange)
+        }
+    }
+
+    fn strict_lowerbound(
+        &mut self,
+        lb: Self::Type,
+    ) -> Result<VariableState, VariableError> {
+        if self.min > lb {
+            Ok(VariableState::NoChange)
+        } else if self.max <= lb {
+            self.invalidate();
+            Err(VariableError::DomainWipeout)
+        } else {
+            self.weak_lowerbound(lb + 1)
+        }
+    }
+
+    fn weak_lowerbound(
+        &mut self,
+        lb: Self::Type,
+    ) -> Result<VariableState, VariableError> {
+        if self.min >= lb {
+            Ok(VariableState::NoChange)
+        } else if self.max < lb {
+            self.invalidate();
+            Err(VariableError::DomainWipeout)
+        } else {
+            let (min_index, index) = (self.index(self.min), self.index(lb));
+            self.clear_range(min_index, index - 1);
+            self.size = self.count();
+            self.min = self.value_at(self.next_set(index).unwrap());
+            Ok(VariableState::BoundsChange)
+        }
+    }
+}
+
+impl PrunableDomain for IntVarBitset {
+    fn equal(
+        &mut self,
+        value: &mut Self,
+    ) -> Result<(VariableState, VariableState), VariableError> {
+        let ok_self = {
+            let other: &IntVarBitset = value;
+            self.retain(|v| other.contains(v))
+        };
+        let ok_self = match ok_self {
+            Ok(state) => state,
+            Err(error) => {
+                value.invalidate();
+                return Err(error);
+            }
+        };
+        let ok_value = {
+            let other: &IntVarBitset = self;
+            value.retain(|v| other.contains(v))?
+        };
+        Ok((ok_self, ok_value))
+    }
+
+    fn in_values<Values>(
+        &mut self,
+        values: Values,
+    ) -> Result<VariableState, VariableError>
+    where
+        Values: IntoIterator<Item = Self::Type>,
+    {
+        let mut values: Vec<_> = values.into_iter().collect();
+        values.sort();
+        self.in_sorted_values(values)
+    }
+
+    fn remove_value(
+        &mut self,
+        value: Self::Type,
+    ) -> Result<VariableState, VariableError> {
+        if !self.contains(value) {
+            return Ok(VariableState::NoChange);
+        }
+        let (min, max, size) = (self.min, self.max, self.size);
+        let index = self.index(value);
+        self.clear(index);
+        self.size -= 1;
+        if self.size == 0 {
+            self.invalidate();
+        } else if value == min {
+            self.min = self.value_at(self.next_set(index).unwrap());
+        } else if value == max {
+            self.max = self.value_at(self.prev_set(index).unwrap());
+        }
+        self.domain_change(min, max, size)
+    }
+
+    fn remove_if<Predicate>(
+        &mut self,
+        mut pred: Predicate,
+    ) -> Result<VariableState, VariableError>
+    where
+        Predicate: FnMut(&Self::Type) -> bool,
+    {
+        self.retain(|v| !pred(&v))
+    }
+
+    fn retains_if<Predicate>(
+        &mut self,
+        mut pred: Predicate,
+    ) -> Result<VariableState, VariableError>
+    where
+        Predicate: FnMut(&Self::Type) -> bool,
+    {
+        self.retain(|v| pred(&v))
+    }
+
+    fn not_equal(
+        &mut self,
+        value: &mut IntVarBitset,
+    ) -> Result<(VariableState, VariableState), VariableError> {
+        match self.value() {
+            Some(val) => {
+                let ok_value = value.remove_value(val)?;
+                Ok((VariableState::NoChange, ok_value))
+            }
+            _ => match value.value() {
+                Some(val) => {
+                    let ok_self = self.remove_value(val)?;
+                    Ok((ok_self, VariableState::NoChange))
+                }
+                _ => Ok((VariableState::NoChange, VariableState::NoChange)),
+            },
+        }
+    }
+}
+
+impl OrderedPrunableDomain for IntVarBitset {
+    fn in_sorted_values<Values>(
+        &mut self,
+        values: Values,
+    ) -> Result<VariableState, VariableError>
+    where
+        Values: IntoIterator<Item = Self::Type>,
+    {
+        let (min, max, size) = (self.min, self.max, self.size);
+        let mut words = vec![0u64; self.words.len()];
+        for value in values.into_iter() {
+            if self.contains(value) {
+                let index = self.index(value);
+                words[index / WORD_BITS] |= 1u64 << (index % WORD_BITS);
+            }
+        }
+        self.words = words;
+        self.refresh();
+        self.domain_change(min, max, size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    test_int_var!(IntVarBitset);
+}
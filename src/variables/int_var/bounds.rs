@@ -1 +1,322 @@
-pub struct IntVarBounds {}
+use variables::domains::{
+    AssignableDomain, FiniteDomain, FromRangeDomain, OrderedDomain,
+};
+use variables::{
+    Variable, VariableBuilder, VariableContainer, VariableError, VariableId,
+    VariableState,
+};
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IntVarBoundsBuilder {
+    min: i32,
+    max: i32,
+}
+
+impl IntVarBoundsBuilder {
+    pub fn new(min: i32, max: i32) -> Option<IntVarBoundsBuilder> {
+        if min > max {
+            None
+        } else {
+            Some(IntVarBoundsBuilder { min, max })
+        }
+    }
+}
+
+impl VariableBuilder for IntVarBoundsBuilder {
+    type Variable = IntVarBounds;
+
+    fn finalize(self, id: usize) -> IntVarBounds {
+        IntVarBounds {
+            min: self.min,
+            max: self.max,
+            id,
+        }
+    }
+}
+
+/// Integer variable which the domain is the interval `min..=max`. Only the bounds
+/// are stored, so the variable uses a constant amount of memory whatever the size
+/// of its domain. The domain is empty if and only if `max < min`. As for the other
+/// integer variables, any change of the domain is reported as a `BoundsChange`.
+/// The values are not enumerable, so the variable cannot be branched on by the
+/// value selectors which iterate over the domain.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IntVarBounds {
+    min: i32,
+    max: i32,
+    id: usize,
+}
+impl VariableContainer for IntVarBounds {}
+
+unsafe impl Sync for IntVarBounds {}
+unsafe impl Send for IntVarBounds {}
+
+impl IntVarBounds {
+    pub fn new(min: i32, max: i32) -> Option<IntVarBounds> {
+        if min > max {
+            None
+        } else {
+            Some(IntVarBounds {
+                min,
+                max,
+                id: 0,
+            })
+        }
+    }
+
+    fn invalidate(&mut self) {
+        self.min = i32::MAX;
+        self.max = i32::MIN;
+    }
+
+    fn is_empty(&self) -> bool {
+        self.min > self.max
+    }
+}
+
+impl FromRangeDomain for IntVarBounds {
+    fn new_from_range(min: Self::Type, max: Self::Type) -> Option<IntVarBounds> {
+        IntVarBounds::new(min, max)
+    }
+}
+
+impl AssignableDomain for IntVarBounds {
+    fn set_value(&mut self, value: Self::Type) -> Result<VariableState, VariableError> {
+        if self.min > value || self.max < value {
+            self.invalidate();
+            Err(VariableError::DomainWipeout)
+        } else if self.min == self.max {
+            Ok(VariableState::NoChange)
+        } else {
+            self.min = value;
+            self.max = value;
+            Ok(VariableState::BoundsChange)
+        }
+    }
+}
+
+impl Variable for IntVarBounds {
+    type Type = i32;
+    fn is_affected(&self) -> bool {
+        self.min == self.max
+    }
+
+    fn value(&self) -> Option<Self::Type> {
+        if self.is_affected() {
+            Some(self.min)
+        } else {
+            None
+        }
+    }
+
+    fn id(&self) -> VariableId {
+        VariableId(self.id)
+    }
+}
+
+impl FiniteDomain for IntVarBounds {
+    fn size(&self) -> usize {
+        if self.is_empty() {
+            0
+        } else {
+            (i64::from(self.max) - i64::from(self.min) + 1) as usize
+        }
+    }
+}
+
+impl OrderedDomain for IntVarBounds {
+    fn min(&self) -> Option<Self::Type> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(self.min)
+        }
+    }
+    fn max(&self) -> Option<Self::Type> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(self.max)
+        }
+    }
+
+    fn strict_upperbound(
+        &mut self,
+        ub: Self::Type,
+    ) -> Result<VariableState, VariableError> {
+        if self.max < ub {
+            Ok(VariableState::NoChange)
+        } else if self.min >= ub {
+            self.invalidate();
+            Err(VariableError::DomainWipeout)
+        } else {
+            self.max = ub - 1;
+            Ok(VariableState::BoundsChange)
+        }
+    }
+
+    fn weak_upperbound(
+        &mut self,
+        ub: Self::Type,
+    ) -> Result<VariableState, VariableError> {
+        if self.max <= ub {
+            Ok(VariableState::NoChange)
+        } else if self.min > ub {
+            self.invalidate();
+            Err(VariableError::DomainWipeout)
+        } else {
+            self.max = ub;
+            Ok(VariableState::BoundsChange)
+        }
+    }
+
+    fn strict_lowerbound(
+        &mut self,
+        lb: Self::Type,
+    ) -> Result<VariableState, VariableError> {
+        if self.min > lb {
+            Ok(VariableState::NoChange)
+        } else if self.max <= lb {
+            self.invalidate();
+            Err(VariableError::DomainWipeout)
+        } else {
+            self.min = lb + 1;
+            Ok(VariableState::BoundsChange)
+        }
+    }
+
+    fn weak_lowerbound(
+        &mut self,
+        lb: Self::Type,
+    ) -> Result<VariableState, VariableError> {
+        if self.min >= lb {
+            Ok(VariableState::NoChange)
+        } else if self.max < lb {
+            self.invalidate();
+            Err(VariableError::DomainWipeout)
+        } else {
+            self.min = lb;
+            Ok(VariableState::BoundsChange)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Checks the result of `$fn(bound)` against the filtering of `min..=max` by `$op`
+    // for every bounds around the domain.
+    macro_rules! bounds_domain_test {
+        ($testname: ident, $fn: ident, $op: tt) => {
+            #[test]
+            fn $testname() {
+                let domains = vec![(1, 9), (-3, 4), (5, 5)];
+                for (min, max) in domains.into_iter() {
+                    for bound in (min - 2)..(max + 3) {
+                        let mut var = IntVarBounds::new(min, max).unwrap();
+                        let exp_domain: Vec<_> =
+                            (min..=max).filter(|&val| expr!(val $op bound)).collect();
+                        let exp_res = if exp_domain.is_empty() {
+                            Err(VariableError::DomainWipeout)
+                        } else if exp_domain.len() == var.size() {
+                            Ok(VariableState::NoChange)
+                        } else {
+                            Ok(VariableState::BoundsChange)
+                        };
+                        let res = var.$fn(bound);
+                        assert_eq!(
+                            res, exp_res,
+                            "Result expected {:?} for {}..={}.{}({}) found {:?}",
+                            exp_res, min, max, stringify!($fn), bound, res
+                        );
+                        if res.is_ok() {
+                            assert_eq!(
+                                (var.min(), var.max()),
+                                (exp_domain.first().cloned(), exp_domain.last().cloned()),
+                                "Expected {:?} for {}..={}.{}({}) found {:?}",
+                                exp_domain, min, max, stringify!($fn), bound, var
+                            );
+                            assert_eq!(var.size(), exp_domain.len());
+                        } else {
+                            assert_eq!(var.size(), 0);
+                            assert_eq!(var.min(), None);
+                            assert_eq!(var.max(), None);
+                        }
+                    }
+                }
+            }
+        };
+    }
+
+    bounds_domain_test!(strict_upperbound, strict_upperbound, <);
+    bounds_domain_test!(weak_upperbound, weak_upperbound, <=);
+    bounds_domain_test!(strict_lowerbound, strict_lowerbound, >);
+    bounds_domain_test!(weak_lowerbound, weak_lowerbound, >=);
+
+    #[test]
+    fn new_from_range() {
+        let var = IntVarBounds::new_from_range(-1, 22).unwrap();
+        assert_eq!(var.min(), Some(-1));
+        assert_eq!(var.max(), Some(22));
+        assert_eq!(var.size(), 24);
+        assert!(IntVarBounds::new_from_range(3, 2).is_none());
+
+        let var = IntVarBounds::new(i32::MIN, i32::MAX).unwrap();
+        assert_eq!(var.size(), 1 << 32);
+    }
+
+    #[test]
+    fn set_value() {
+        for value in 1..10 {
+            let mut var = IntVarBounds::new(1, 9).unwrap();
+            assert_eq!(var.set_value(value), Ok(VariableState::BoundsChange));
+            assert_eq!(var.value(), Some(value));
+            assert_eq!(var.set_value(value), Ok(VariableState::NoChange));
+        }
+        for &value in [0, 10].iter() {
+            let mut var = IntVarBounds::new(1, 9).unwrap();
+            assert_eq!(var.set_value(value), Err(VariableError::DomainWipeout));
+            assert_eq!(var.size(), 0);
+        }
+    }
+
+    #[test]
+    fn less_than() {
+        let mut lhs = IntVarBounds::new(3, 10).unwrap();
+        let mut rhs = IntVarBounds::new(0, 6).unwrap();
+        assert_eq!(
+            lhs.less_than(&mut rhs),
+            Ok((VariableState::BoundsChange, VariableState::BoundsChange))
+        );
+        assert_eq!((lhs.unchecked_min(), lhs.unchecked_max()), (3, 5));
+        assert_eq!((rhs.unchecked_min(), rhs.unchecked_max()), (4, 6));
+        assert_eq!(
+            lhs.less_than(&mut rhs),
+            Ok((VariableState::NoChange, VariableState::NoChange))
+        );
+
+        let mut lhs = IntVarBounds::new(6, 10).unwrap();
+        let mut rhs = IntVarBounds::new(0, 6).unwrap();
+        assert_eq!(lhs.less_than(&mut rhs), Err(VariableError::DomainWipeout));
+    }
+
+    #[test]
+    fn equal_bounds() {
+        let mut lhs = IntVarBounds::new(3, 10).unwrap();
+        let mut rhs = IntVarBounds::new(0, 6).unwrap();
+        assert_eq!(
+            lhs.equal_bounds(&mut rhs),
+            Ok((VariableState::BoundsChange, VariableState::BoundsChange))
+        );
+        assert_eq!((lhs.unchecked_min(), lhs.unchecked_max()), (3, 6));
+        assert_eq!((rhs.unchecked_min(), rhs.unchecked_max()), (3, 6));
+
+        let mut lhs = IntVarBounds::new(7, 10).unwrap();
+        let mut rhs = IntVarBounds::new(0, 6).unwrap();
+        assert_eq!(
+            lhs.equal_bounds(&mut rhs),
+            Err(VariableError::DomainWipeout)
+        );
+    }
+}
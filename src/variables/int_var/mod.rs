@@ -1,12 +1,16 @@
 #[macro_use]
-
 pub mod tests;
 
+pub use self::bitset::IntVarBitset;
+pub use self::bitset::IntVarBitsetBuilder;
 pub use self::bounds::IntVarBounds;
+pub use self::bounds::IntVarBoundsBuilder;
 pub use self::intervals::IntVarIntervals;
+pub use self::intervals::IntVarIntervalsBuilder;
 pub use self::values::IntVarValues;
 pub use self::values::IntVarValuesBuilder;
 
+mod bitset;
 mod bounds;
 mod intervals;
 mod values;
@@ -3,7 +3,7 @@
 macro_rules! assert_domain_eq{
     ($var: ident, $exp: ident, $name: ident) => {
         assert!(
-        $var.iter().eq($exp.iter()),
+        $var.iter().eq($exp.iter().cloned()),
         "Expected {:?} domain for {:?} found {:?}",
         $exp,
         $name,
@@ -33,17 +33,17 @@ macro_rules! check_domain_and_invariants{
             continue;
         }
         assert_eq!(
-            unwrap_first!($exp_domain), $var.min(),
+            unwrap_first!($exp_domain), $var.unchecked_min(),
             "Min expected {:?} for {:?}.{}({:?}) found {:?}",
             unwrap_first!($exp_domain),
             var_clone, stringify!($fn),
-            $param, $var.min());
+            $param, $var.unchecked_min());
         assert_eq!(
-            unwrap_last!($exp_domain), $var.max(),
+            unwrap_last!($exp_domain), $var.unchecked_max(),
             "Max expected {:?} for {:?}.{}({:?}) found {:?}",
             unwrap_last!($exp_domain),
             var_clone, stringify!($fn),
-            $param, $var.max());
+            $param, $var.unchecked_max());
         assert_eq!(
             $exp_domain.len(), $var.size(),
             "Size expected {:?} for {:?}.{}({:?}) found {:?}",
@@ -71,17 +71,17 @@ macro_rules! check_domain_and_invariants{
             continue;
         }
         assert_eq!(
-            unwrap_first!($exp_domain), $var.min(),
+            unwrap_first!($exp_domain), $var.unchecked_min(),
             "Min expected {:?} for {:?}.{}({:?}) found {:?}",
             unwrap_first!($exp_domain),
             var_clone, stringify!($fn),
-            $param_name, $var.min());
+            $param_name, $var.unchecked_min());
         assert_eq!(
-            unwrap_last!($exp_domain), $var.max(),
+            unwrap_last!($exp_domain), $var.unchecked_max(),
             "Max expected {:?} for {:?}.{}({:?}) found {:?}",
             unwrap_last!($exp_domain),
             var_clone, stringify!($fn),
-            $param_name, $var.max());
+            $param_name, $var.unchecked_max());
         assert_eq!(
             $exp_domain.len(), $var.size(),
             "Size expected {:?} for {:?}.{}({:?}) found {:?}",
@@ -109,17 +109,17 @@ macro_rules! check_domain_and_invariants{
             continue;
         }
         assert_eq!(
-            unwrap_first!($exp_domain), $var.min(),
+            unwrap_first!($exp_domain), $var.unchecked_min(),
             "Min expected {:?} for {:?}.{}({:?}) found {:?}",
             unwrap_first!($exp_domain),
             var_clone, stringify!($fn),
-            $param_name, $var.min());
+            $param_name, $var.unchecked_min());
         assert_eq!(
-            unwrap_last!($exp_domain), $var.max(),
+            unwrap_last!($exp_domain), $var.unchecked_max(),
             "Max expected {:?} for {:?}.{}({:?}) found {:?}",
             unwrap_last!($exp_domain),
             var_clone, stringify!($fn),
-            $param_name, $var.max());
+            $param_name, $var.unchecked_max());
         assert_eq!(
             $exp_domain.len(), $var.size(),
             "Size expected {:?} for {:?}.{}({:?}) found {:?}",
@@ -264,8 +264,8 @@ macro_rules! test_int_var{
             for (min, max) in vars.into_iter() {
                 let var = <$var>::new_from_range(min, max).unwrap();
                 let domain: Vec<_> = (min..(max+1)).collect();
-                assert_eq!(var.min(), min, "min false for: \"{:?}\"", var);
-                assert_eq!(var.max(), max, "max false for: \"{:?}\"", var);
+                assert_eq!(var.unchecked_min(), min, "min false for: \"{:?}\"", var);
+                assert_eq!(var.unchecked_max(), max, "max false for: \"{:?}\"", var);
                 assert_domain_eq!(var, domain, name);
             }
         }
@@ -656,17 +656,17 @@ macro_rules! test_int_var{
                         continue;
                     }
                     assert_eq!(
-                        unwrap_first!(exp_domain_left), var_left.min(),
+                        unwrap_first!(exp_domain_left), var_left.unchecked_min(),
                         "Min expected {:?} for {:?}.{}({:?}) found {:?}",
                         unwrap_first!(exp_domain_left),
                         var_left_clone, stringify!(less_than),
-                        var_right_clone, var_left.min());
+                        var_right_clone, var_left.unchecked_min());
                     assert_eq!(
-                        unwrap_last!(exp_domain_left), var_left.max(),
+                        unwrap_last!(exp_domain_left), var_left.unchecked_max(),
                         "Max expected {:?} for {:?}.{}({:?}) found {:?}",
                         unwrap_last!(exp_domain_left),
                         var_left_clone, stringify!(less_than),
-                        var_right_clone, var_left.max());
+                        var_right_clone, var_left.unchecked_max());
                     assert_eq!(
                         exp_domain_left.len(), var_left.size(),
                         "Size expected {:?} for {:?}.{}({:?}) found {:?}",
@@ -675,17 +675,17 @@ macro_rules! test_int_var{
                         var_right_clone, var_left.size());
                     assert_domain_eq!(var_left, exp_domain_left, name_left);
                     assert_eq!(
-                        unwrap_first!(exp_domain_right), var_right.min(),
+                        unwrap_first!(exp_domain_right), var_right.unchecked_min(),
                         "Min expected {:?} for {:?}.{}({:?}) found {:?}",
                         unwrap_first!(exp_domain_right),
                         var_left_clone, stringify!(less_than),
-                        var_right_clone, var_right.min());
+                        var_right_clone, var_right.unchecked_min());
                     assert_eq!(
-                        unwrap_last!(exp_domain_right), var_right.max(),
+                        unwrap_last!(exp_domain_right), var_right.unchecked_max(),
                         "Max expected {:?} for {:?}.{}({:?}) found {:?}",
                         unwrap_last!(exp_domain_right),
                         var_left_clone, stringify!(less_than),
-                        var_right_clone, var_right.max());
+                        var_right_clone, var_right.unchecked_max());
                     assert_eq!(
                         exp_domain_right.len(), var_right.size(),
                         "Size expected {:?} for {:?}.{}({:?}) found {:?}",
@@ -775,17 +775,17 @@ macro_rules! test_int_var{
                         continue;
                     }
                     assert_eq!(
-                        unwrap_first!(exp_domain_left), var_left.min(),
+                        unwrap_first!(exp_domain_left), var_left.unchecked_min(),
                         "Min expected {:?} for {:?}.{}({:?}) found {:?}",
                         unwrap_first!(exp_domain_left),
                         var_left_clone, stringify!(less_or_equal_than),
-                        var_right_clone, var_left.min());
+                        var_right_clone, var_left.unchecked_min());
                     assert_eq!(
-                        unwrap_last!(exp_domain_left), var_left.max(),
+                        unwrap_last!(exp_domain_left), var_left.unchecked_max(),
                         "Max expected {:?} for {:?}.{}({:?}) found {:?}",
                         unwrap_last!(exp_domain_left),
                         var_left_clone, stringify!(less_or_equal_than),
-                        var_right_clone, var_left.max());
+                        var_right_clone, var_left.unchecked_max());
                     assert_eq!(
                         exp_domain_left.len(), var_left.size(),
                         "Size expected {:?} for {:?}.{}({:?}) found {:?}",
@@ -794,17 +794,17 @@ macro_rules! test_int_var{
                         var_right_clone, var_left.size());
                     assert_domain_eq!(var_left, exp_domain_left, name_left);
                     assert_eq!(
-                        unwrap_first!(exp_domain_right), var_right.min(),
+                        unwrap_first!(exp_domain_right), var_right.unchecked_min(),
                         "Min expected {:?} for {:?}.{}({:?}) found {:?}",
                         unwrap_first!(exp_domain_right),
                         var_left_clone, stringify!(less_or_equal_than),
-                        var_right_clone, var_right.min());
+                        var_right_clone, var_right.unchecked_min());
                     assert_eq!(
-                        unwrap_last!(exp_domain_right), var_right.max(),
+                        unwrap_last!(exp_domain_right), var_right.unchecked_max(),
                         "Max expected {:?} for {:?}.{}({:?}) found {:?}",
                         unwrap_last!(exp_domain_right),
                         var_left_clone, stringify!(less_or_equal_than),
-                        var_right_clone, var_right.max());
+                        var_right_clone, var_right.unchecked_max());
                     assert_eq!(
                         exp_domain_right.len(), var_right.size(),
                         "Size expected {:?} for {:?}.{}({:?}) found {:?}",
@@ -894,17 +894,17 @@ macro_rules! test_int_var{
                         continue;
                     }
                     assert_eq!(
-                        unwrap_first!(exp_domain_left), var_left.min(),
+                        unwrap_first!(exp_domain_left), var_left.unchecked_min(),
                         "Min expected {:?} for {:?}.{}({:?}) found {:?}",
                         unwrap_first!(exp_domain_left),
                         var_left_clone, stringify!(greater_than),
-                        var_right_clone, var_left.min());
+                        var_right_clone, var_left.unchecked_min());
                     assert_eq!(
-                        unwrap_last!(exp_domain_left), var_left.max(),
+                        unwrap_last!(exp_domain_left), var_left.unchecked_max(),
                         "Max expected {:?} for {:?}.{}({:?}) found {:?}",
                         unwrap_last!(exp_domain_left),
                         var_left_clone, stringify!(greater_than),
-                        var_right_clone, var_left.max());
+                        var_right_clone, var_left.unchecked_max());
                     assert_eq!(
                         exp_domain_left.len(), var_left.size(),
                         "Size expected {:?} for {:?}.{}({:?}) found {:?}",
@@ -913,17 +913,17 @@ macro_rules! test_int_var{
                         var_right_clone, var_left.size());
                     assert_domain_eq!(var_left, exp_domain_left, name_left);
                     assert_eq!(
-                        unwrap_first!(exp_domain_right), var_right.min(),
+                        unwrap_first!(exp_domain_right), var_right.unchecked_min(),
                         "Min expected {:?} for {:?}.{}({:?}) found {:?}",
                         unwrap_first!(exp_domain_right),
                         var_left_clone, stringify!(greater_than),
-                        var_right_clone, var_right.min());
+                        var_right_clone, var_right.unchecked_min());
                     assert_eq!(
-                        unwrap_last!(exp_domain_right), var_right.max(),
+                        unwrap_last!(exp_domain_right), var_right.unchecked_max(),
                         "Max expected {:?} for {:?}.{}({:?}) found {:?}",
                         unwrap_last!(exp_domain_right),
                         var_left_clone, stringify!(greater_than),
-                        var_right_clone, var_right.max());
+                        var_right_clone, var_right.unchecked_max());
                     assert_eq!(
                         exp_domain_right.len(), var_right.size(),
                         "Size expected {:?} for {:?}.{}({:?}) found {:?}",
@@ -1013,17 +1013,17 @@ macro_rules! test_int_var{
                         continue;
                     }
                     assert_eq!(
-                        unwrap_first!(exp_domain_left), var_left.min(),
+                        unwrap_first!(exp_domain_left), var_left.unchecked_min(),
                         "Min expected {:?} for {:?}.{}({:?}) found {:?}",
                         unwrap_first!(exp_domain_left),
                         var_left_clone, stringify!(greater_or_equal_than),
-                        var_right_clone, var_left.min());
+                        var_right_clone, var_left.unchecked_min());
                     assert_eq!(
-                        unwrap_last!(exp_domain_left), var_left.max(),
+                        unwrap_last!(exp_domain_left), var_left.unchecked_max(),
                         "Max expected {:?} for {:?}.{}({:?}) found {:?}",
                         unwrap_last!(exp_domain_left),
                         var_left_clone, stringify!(greater_or_equal_than),
-                        var_right_clone, var_left.max());
+                        var_right_clone, var_left.unchecked_max());
                     assert_eq!(
                         exp_domain_left.len(), var_left.size(),
                         "Size expected {:?} for {:?}.{}({:?}) found {:?}",
@@ -1032,17 +1032,17 @@ macro_rules! test_int_var{
                         var_right_clone, var_left.size());
                     assert_domain_eq!(var_left, exp_domain_left, name_left);
                     assert_eq!(
-                        unwrap_first!(exp_domain_right), var_right.min(),
+                        unwrap_first!(exp_domain_right), var_right.unchecked_min(),
                         "Min expected {:?} for {:?}.{}({:?}) found {:?}",
                         unwrap_first!(exp_domain_right),
                         var_left_clone, stringify!(greater_or_equal_than),
-                        var_right_clone, var_right.min());
+                        var_right_clone, var_right.unchecked_min());
                     assert_eq!(
-                        unwrap_last!(exp_domain_right), var_right.max(),
+                        unwrap_last!(exp_domain_right), var_right.unchecked_max(),
                         "Max expected {:?} for {:?}.{}({:?}) found {:?}",
                         unwrap_last!(exp_domain_right),
                         var_left_clone, stringify!(greater_or_equal_than),
-                        var_right_clone, var_right.max());
+                        var_right_clone, var_right.unchecked_max());
                     assert_eq!(
                         exp_domain_right.len(), var_right.size(),
                         "Size expected {:?} for {:?}.{}({:?}) found {:?}",
@@ -1080,8 +1080,6 @@ macro_rules! test_int_var{
                     let var1_base = var1.clone();
                     let var2_base = var2.clone();
                     let res = var1.equal(&mut var2);
-                    let _ = var1.retrieve_state();
-                    let _ = var2.retrieve_state();
                     let dom_eq = domain1
                         .iter()
                         .filter(|&&val| domain2.contains(&val))
@@ -1103,7 +1101,7 @@ macro_rules! test_int_var{
                             .unwrap();
                         assert_var_eq!(var1, var_res);
                         assert_var_eq!(var2, var_res);
-                        let ok1 = if domain1.iter().eq(var1.iter()) {
+                        let ok1 = if domain1.iter().cloned().eq(var1.iter()) {
                             VariableState::NoChange
                         } else if domain1.first() != dom_eq.first() {
                             VariableState::BoundsChange
@@ -1112,7 +1110,7 @@ macro_rules! test_int_var{
                         } else {
                             VariableState::ValuesChange
                         };
-                        let ok2 = if domain2.iter().eq(var2.iter()) {
+                        let ok2 = if domain2.iter().cloned().eq(var2.iter()) {
                             VariableState::NoChange
                         } else if domain2.first() != dom_eq.first() {
                             VariableState::BoundsChange
@@ -1170,7 +1168,6 @@ macro_rules! test_int_var{
                 for value in domain_clone.into_iter() {
                     let mut var = var.clone();
                     let res = var.set_value(value);
-                    let _ = var.retrieve_state();
                     assert!(
                         res == expected,
                         "Expected {:?} for {:?} with value {:?} found {:?}.",
@@ -1367,7 +1364,7 @@ macro_rules! test_int_var{
         #[test]
         fn from_range_iter() {
             let vars = [(0, 1), (-1, 22), (3, 5), (5, 9), (2, 2)]
-                .into_iter()
+                .iter()
                 .map(|&(min, max)| <$var>::new_from_range(min, max))
                 .map(Option::unwrap)
                 .collect::<Vec<_>>();
@@ -18,10 +18,24 @@ impl IntVarValuesBuilder {
             None
         } else {
             Some(IntVarValuesBuilder {
-                domain: (min..(max + 1)).collect(),
+                domain: (min..=max).collect(),
             })
         }
     }
+
+    pub fn new_from_values<Values>(values: Values) -> Option<IntVarValuesBuilder>
+    where
+        Values: IntoIterator<Item = i32>,
+    {
+        let mut domain = values.into_iter().collect::<Vec<_>>();
+        domain.sort();
+        domain.dedup();
+        if domain.is_empty() {
+            None
+        } else {
+            Some(IntVarValuesBuilder { domain })
+        }
+    }
 }
 
 impl VariableBuilder for IntVarValuesBuilder {
@@ -30,7 +44,7 @@ impl VariableBuilder for IntVarValuesBuilder {
     fn finalize(self, id: usize) -> IntVarValues {
         IntVarValues {
             domain: self.domain,
-            id: id,
+            id,
         }
     }
 }
@@ -51,7 +65,7 @@ impl IntVarValues {
             None
         } else {
             Some(IntVarValues {
-                domain: (min..(max + 1)).collect(),
+                domain: (min..=max).collect(),
                 id: 0,
             })
         }
@@ -72,9 +86,7 @@ impl IntVarValues {
             Err(VariableError::DomainWipeout)
         } else if self.size() == prev_size {
             Ok(VariableState::NoChange)
-        } else if self.unchecked_min() != prev_min {
-            Ok(VariableState::BoundsChange)
-        } else if self.unchecked_max() != prev_max {
+        } else if self.unchecked_min() != prev_min || self.unchecked_max() != prev_max {
             Ok(VariableState::BoundsChange)
         } else {
             Ok(VariableState::ValuesChange)
@@ -83,8 +95,8 @@ impl IntVarValues {
 }
 
 impl IterableDomain for IntVarValues {
-    fn iter<'a>(&'a self) -> Box<Iterator<Item = &Self::Type> + 'a> {
-        Box::new(self.domain.iter())
+    fn iter<'a>(&'a self) -> Box<Iterator<Item = Self::Type> + 'a> {
+        Box::new(self.domain.iter().cloned())
     }
 }
 
@@ -94,7 +106,7 @@ impl FromRangeDomain for IntVarValues {
             None
         } else {
             Some(IntVarValues {
-                domain: (min..(max + 1)).collect(),
+                domain: (min..=max).collect(),
                 id: 0,
             })
         }
@@ -114,7 +126,7 @@ impl FromValuesDomain for IntVarValues {
             None
         } else {
             Some(IntVarValues {
-                domain: domain,
+                domain,
                 id: 0,
             })
         }
@@ -174,10 +186,10 @@ impl FiniteDomain for IntVarValues {
 
 impl OrderedDomain for IntVarValues {
     fn min(&self) -> Option<Self::Type> {
-        self.domain.first().map(Clone::clone)
+        self.domain.first().cloned()
     }
     fn max(&self) -> Option<Self::Type> {
-        self.domain.last().map(Clone::clone)
+        self.domain.last().cloned()
     }
 
     fn strict_upperbound(
@@ -248,9 +260,9 @@ impl PrunableDomain for IntVarValues {
         value: &mut Self,
     ) -> Result<(VariableState, VariableState), VariableError> {
         use std::collections::BTreeSet;
-        let s1: BTreeSet<_> = self.iter().map(|&v| v).collect();
-        let s2: BTreeSet<_> = value.iter().map(|&v| v).collect();
-        let domain: Vec<_> = s1.intersection(&s2).map(|val| *val).collect();
+        let s1: BTreeSet<_> = self.iter().collect();
+        let s2: BTreeSet<_> = value.iter().collect();
+        let domain: Vec<_> = s1.intersection(&s2).cloned().collect();
 
         if domain.is_empty() {
             self.invalidate();
@@ -261,9 +273,9 @@ impl PrunableDomain for IntVarValues {
             let check_change = |var: &mut IntVarValues| {
                 if var.size() == domain.len() {
                     VariableState::NoChange
-                } else if var.unchecked_min() != unwrap_first!(domain) {
-                    VariableState::BoundsChange
-                } else if var.unchecked_max() != unwrap_last!(domain) {
+                } else if var.unchecked_min() != unwrap_first!(domain)
+                    || var.unchecked_max() != unwrap_last!(domain)
+                {
                     VariableState::BoundsChange
                 } else {
                     VariableState::ValuesChange
@@ -287,7 +299,7 @@ impl PrunableDomain for IntVarValues {
         let values: Vec<_> = values.into_iter().collect();
         let mut values: Vec<_> = values.into_iter().collect();
         values.sort();
-        self.in_sorted_values(values.into_iter())
+        self.in_sorted_values(values)
     }
 
     // check change function (equality, bounds, values, nochange...)
@@ -305,9 +317,7 @@ impl PrunableDomain for IntVarValues {
                 self.domain.remove(index);
                 if self.size() == 0 {
                     Err(VariableError::DomainWipeout)
-                } else if self.min() != min {
-                    Ok(VariableState::BoundsChange)
-                } else if self.max() != max {
+                } else if self.min() != min || self.max() != max {
                     Ok(VariableState::BoundsChange)
                 } else {
                     Ok(VariableState::ValuesChange)
@@ -371,9 +381,9 @@ impl OrderedPrunableDomain for IntVarValues {
         Values: IntoIterator<Item = Self::Type>,
     {
         use std::collections::BTreeSet;
-        let s1: BTreeSet<_> = self.iter().map(|&v| v).collect();
+        let s1: BTreeSet<_> = self.iter().collect();
         let s2: BTreeSet<_> = values.into_iter().collect();
-        let domain: Vec<_> = s1.intersection(&s2).map(|val| *val).collect();
+        let domain: Vec<_> = s1.intersection(&s2).cloned().collect();
 
         if domain.is_empty() {
             self.invalidate();
@@ -383,9 +393,9 @@ impl OrderedPrunableDomain for IntVarValues {
             let check_change = |var: &mut IntVarValues| {
                 if var.size() == domain.len() {
                     VariableState::NoChange
-                } else if var.unchecked_min() != unwrap_first!(domain) {
-                    VariableState::BoundsChange
-                } else if var.unchecked_max() != unwrap_last!(domain) {
+                } else if var.unchecked_min() != unwrap_first!(domain)
+                    || var.unchecked_max() != unwrap_last!(domain)
+                {
                     VariableState::BoundsChange
                 } else {
                     VariableState::ValuesChange
@@ -87,7 +87,7 @@ pub trait Variable: Clone + VariableContainer {
     /// been updated.
     fn id(&self) -> VariableId;
     fn iter_ids(&self) -> Box<Iterator<Item = VariableId>> {
-        Box::new(iter::once(self.id().clone()))
+        Box::new(iter::once(self.id()))
     }
 }
 
@@ -106,13 +106,17 @@ pub trait Array: VariableContainer {
     /// Returns a reference to the variable at that position without doing bounds check.
     fn get_unchecked(&self, position: usize) -> &Self::Variable;
     /// Returns an iterator over the variables.
-    fn iter<'array>(&'array self) -> Box<Iterator<Item = &Self::Variable> + 'array>;
+    fn iter<'array>(&'array self) -> Box<Iterator<Item = &'array Self::Variable> + 'array>;
     /// Returns an iterator that allows modifying each variable.
     fn iter_mut<'array>(
         &'array mut self,
-    ) -> Box<Iterator<Item = &mut Self::Variable> + 'array>;
+    ) -> Box<Iterator<Item = &'array mut Self::Variable> + 'array>;
     /// Returns the number of variables.
     fn len(&self) -> usize;
+    /// Returns `true` if the array does not contain any variable.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
     fn iter_ids<'array>(&'array self) -> Box<Iterator<Item = VariableId> + 'array> {
         Box::new(self.iter().map(|var| var.id()))
     }
@@ -191,11 +195,11 @@ impl<Var: Variable> Array for ArrayOfVars<Var> {
         unsafe { self.variables.get_unchecked(position) }
     }
 
-    fn iter<'a>(&'a self) -> Box<Iterator<Item = &Var> + 'a> {
+    fn iter<'a>(&'a self) -> Box<Iterator<Item = &'a Var> + 'a> {
         Box::new(self.variables.iter())
     }
 
-    fn iter_mut<'a>(&'a mut self) -> Box<Iterator<Item = &mut Var> + 'a> {
+    fn iter_mut<'a>(&'a mut self) -> Box<Iterator<Item = &'a mut Var> + 'a> {
         Box::new(self.variables.iter_mut())
     }
 
@@ -221,7 +225,7 @@ impl<Var: Variable> ArrayOfRefs<Var> {
     /// *`variables` - Vector of references to variables.
     fn new(variables: Vec<*mut Var>) -> Option<Self> {
         Some(ArrayOfRefs {
-            variables: variables,
+            variables,
         })
     }
 }
@@ -244,11 +248,11 @@ impl<Var: Variable> Array for ArrayOfRefs<Var> {
         unsafe { &(**self.variables.get_unchecked(position)) }
     }
 
-    fn iter<'a>(&'a self) -> Box<Iterator<Item = &Var> + 'a> {
+    fn iter<'a>(&'a self) -> Box<Iterator<Item = &'a Var> + 'a> {
         unsafe { Box::new(self.variables.iter().map(|&var| &*var)) }
     }
 
-    fn iter_mut<'a>(&'a mut self) -> Box<Iterator<Item = &mut Var> + 'a> {
+    fn iter_mut<'a>(&'a mut self) -> Box<Iterator<Item = &'a mut Var> + 'a> {
         unsafe { Box::new(self.variables.iter_mut().map(|&mut var| &mut *var)) }
     }
 